@@ -0,0 +1,261 @@
+use chrono::{Local, TimeZone};
+use roda_core::models::WheelEntry;
+use roda_core::{format_local_date, CsvImportKind, CsvImportPreview, TeamWheel};
+
+const BAR_WIDTH: usize = 10;
+
+pub fn date(timestamp: i64) -> String {
+    format_local_date(timestamp)
+}
+
+pub fn datetime(timestamp: i64) -> String {
+    Local.timestamp_opt(timestamp, 0).single().map_or_else(
+        || timestamp.to_string(),
+        |t| t.format("%Y-%m-%d %H:%M").to_string(),
+    )
+}
+
+/// Left-aligned columns separated by two spaces. Trailing blanks are trimmed.
+pub fn table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    if rows.is_empty() {
+        return "Nothing to show\n".to_string();
+    }
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let header_row: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    for row in std::iter::once(&header_row).chain(rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// One bar per area, e.g. `Health   [#######...]  7  2024-03-01`, followed by
+/// the average of the scored areas.
+pub fn wheel(entries: &[WheelEntry]) -> String {
+    if entries.is_empty() {
+        return "No life areas yet\n".to_string();
+    }
+
+    let name_width = entries
+        .iter()
+        .map(|entry| entry.area.name.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    for WheelEntry { area, score } in entries {
+        let line = match score {
+            Some(score) => {
+                let filled = score.value.clamp(0, BAR_WIDTH as i64) as usize;
+                format!(
+                    "{:<name_width$}  [{}{}]  {:>2}  {}",
+                    area.name,
+                    "#".repeat(filled),
+                    ".".repeat(BAR_WIDTH - filled),
+                    score.value,
+                    date(score.recorded_at),
+                    name_width = name_width
+                )
+            }
+            None => format!(
+                "{:<name_width$}  [{}]   -",
+                area.name,
+                " ".repeat(BAR_WIDTH),
+                name_width = name_width
+            ),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    let values: Vec<i64> = entries
+        .iter()
+        .filter_map(|entry| entry.score.as_ref().map(|score| score.value))
+        .collect();
+    if !values.is_empty() {
+        let average = values.iter().sum::<i64>() as f64 / values.len() as f64;
+        out.push_str(&format!("\nAverage: {:.1}\n", average));
+    }
+    out
+}
+
+/// Per-area statistics, then each contributor's scores unless anonymized.
+pub fn team(team: &TeamWheel) -> String {
+    let stat = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.1}", v));
+
+    let rows: Vec<Vec<String>> = team
+        .areas
+        .iter()
+        .map(|area| {
+            let histogram: Vec<String> = area
+                .histogram
+                .iter()
+                .enumerate()
+                .filter(|(_, count)| **count > 0)
+                .map(|(value, count)| format!("{}x{}", value, count))
+                .collect();
+            vec![
+                area.name.clone(),
+                area.responses.to_string(),
+                stat(area.mean),
+                stat(area.median),
+                stat(area.std_dev),
+                area.min.map_or("-".to_string(), |v| v.to_string()),
+                area.max.map_or("-".to_string(), |v| v.to_string()),
+                histogram.join(" "),
+            ]
+        })
+        .collect();
+
+    let mut out = format!("{} contributors\n\n", team.contributors.len());
+    out.push_str(&table(
+        &["AREA", "N", "MEAN", "MEDIAN", "SD", "MIN", "MAX", "SCORES"],
+        &rows,
+    ));
+
+    for contributor in &team.contributors {
+        let scores: Vec<String> = team
+            .areas
+            .iter()
+            .filter_map(|area| {
+                area.scores
+                    .iter()
+                    .find(|s| s.contributor == contributor.label)
+                    .map(|s| format!("{} {}", area.name, s.value))
+            })
+            .collect();
+        if !scores.is_empty() {
+            out.push_str(&format!("\n{}: {}", contributor.label, scores.join(", ")));
+        }
+    }
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+pub fn csv_import(preview: &CsvImportPreview, kind: CsvImportKind) -> String {
+    let rows: Vec<Vec<String>> = preview
+        .rows
+        .iter()
+        .map(|row| {
+            vec![
+                row.line.to_string(),
+                row.area.clone(),
+                match kind {
+                    CsvImportKind::Scores => row.value.map_or(String::new(), |v| v.to_string()),
+                    CsvImportKind::ActionItems => row.title.clone().unwrap_or_default(),
+                },
+                row.date.map_or("now".to_string(), datetime),
+                row.note.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    let field = match kind {
+        CsvImportKind::Scores => "SCORE",
+        CsvImportKind::ActionItems => "TITLE",
+    };
+    let mut out = table(&["LINE", "AREA", field, "DATE", "NOTE"], &rows);
+
+    if !preview.errors.is_empty() {
+        out.push_str(&format!(
+            "\n{} rows with errors were left out:\n",
+            preview.errors.len()
+        ));
+        for error in &preview.errors {
+            out.push_str(&format!("  line {}: {}\n", error.line, error.message));
+        }
+    }
+    if !preview.new_areas.is_empty() {
+        out.push_str(&format!("\nNew areas: {}\n", preview.new_areas.join(", ")));
+    }
+
+    let noun = match kind {
+        CsvImportKind::Scores => "scores",
+        CsvImportKind::ActionItems => "action items",
+    };
+    out.push_str(&if preview.dry_run {
+        format!(
+            "\nDry run: would import {} {} and create {} areas\n",
+            preview.rows.len(),
+            noun,
+            preview.new_areas.len()
+        )
+    } else {
+        format!(
+            "\nImported {} {} and created {} areas\n",
+            preview.rows.len(),
+            noun,
+            preview.new_areas.len()
+        )
+    });
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roda_core::models::{LifeArea, Score};
+
+    fn entry(name: &str, value: Option<i64>) -> WheelEntry {
+        WheelEntry {
+            area: LifeArea {
+                id: 1,
+                name: name.to_string(),
+                description: None,
+                color: "#10b981".to_string(),
+                order: 0,
+                is_active: true,
+                created_at: 0,
+                updated_at: 0,
+            },
+            score: value.map(|value| Score {
+                id: 1,
+                area_id: 1,
+                value,
+                recorded_at: 0,
+                note: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn table_pads_columns_and_trims_trailing_blanks() {
+        let rows = vec![
+            vec!["1".to_string(), "Health".to_string(), String::new()],
+            vec![
+                "12".to_string(),
+                "Career".to_string(),
+                "archived".to_string(),
+            ],
+        ];
+        assert_eq!(
+            table(&["ID", "AREA", ""], &rows),
+            "ID  AREA\n1   Health\n12  Career  archived\n"
+        );
+        assert_eq!(table(&["ID"], &[]), "Nothing to show\n");
+    }
+
+    #[test]
+    fn wheel_draws_a_bar_per_area_and_averages_scored_ones() {
+        let out = wheel(&[entry("Health", Some(7)), entry("Fun", None)]);
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert!(lines[0].starts_with("Health  [#######...]   7  "));
+        assert_eq!(lines[1], "Fun     [          ]   -");
+        assert_eq!(lines[3], "Average: 7.0");
+    }
+}
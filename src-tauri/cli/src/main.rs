@@ -0,0 +1,891 @@
+mod format;
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::{Parser, Subcommand};
+use roda_core::models::{LifeArea, WheelEntry};
+use roda_core::{
+    change_db_passphrase, create_profile, decrypt_database, delete_profile, delete_template,
+    encrypt_database, get_db_path, get_template, has_db_passphrase, init_database,
+    is_database_encrypted, list_profiles, list_templates, load_team_wheel, local_midnight,
+    move_database, rename_profile, resolve_db_location, save_template, save_wheel_image,
+    set_db_passphrase, set_db_path_override, switch_profile, CsvColumnMapping, CsvImportKind,
+    LocationSource, ReportFormat, Store, TaskListFormat, WheelImageOptions, WheelTheme,
+    DB_PASSPHRASE_ENV, DB_PATH_ENV,
+};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Record scores and action items in your life wheel from the terminal.
+#[derive(Parser)]
+#[command(name = "roda", version)]
+struct Cli {
+    /// Database file to use instead of the configured one
+    #[arg(long, global = true, value_name = "PATH")]
+    db: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage life areas
+    Areas {
+        #[command(subcommand)]
+        command: AreasCommand,
+    },
+    /// Record a score from 0 to 10 for an area, given by name or id
+    Score {
+        area: String,
+        value: i64,
+        /// When the score applies, as YYYY-MM-DD or YYYY-MM-DD HH:MM (local time)
+        #[arg(long)]
+        at: Option<String>,
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Manage action items
+    Actions {
+        #[command(subcommand)]
+        command: ActionsCommand,
+    },
+    /// Show the latest score of every active area
+    Wheel {
+        /// Show the wheel as it was at this date instead; a date without a
+        /// time means the end of that day
+        #[arg(long)]
+        at: Option<String>,
+        /// Draw the wheel to an image instead; .svg or .png picks the format
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Image width and height in pixels
+        #[arg(long, default_value_t = 600)]
+        size: u32,
+        /// Image theme: light or dark
+        #[arg(long, default_value = "light")]
+        theme: String,
+    },
+    /// Write every row of the database as JSON
+    Export {
+        /// File to write to instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import scores or action items from a CSV file, such as a spreadsheet
+    /// export. Areas that do not exist yet are created.
+    ImportCsv {
+        file: PathBuf,
+        /// What each row is: scores or action_items
+        #[arg(long, default_value = "scores")]
+        kind: String,
+        /// Header of the column with the area name
+        #[arg(long, default_value = "area")]
+        area: String,
+        /// Header of the column with the score
+        #[arg(long)]
+        value: Option<String>,
+        /// Header of the column with the date; rows are dated now without it
+        #[arg(long)]
+        date: Option<String>,
+        /// Format of the date column, such as %d/%m/%Y or "%Y-%m-%d %H:%M"
+        #[arg(long)]
+        date_format: Option<String>,
+        /// Header of the column with the action item title
+        #[arg(long)]
+        title: Option<String>,
+        /// Header of the column with the score note or action item description
+        #[arg(long)]
+        note: Option<String>,
+        /// Field separator
+        #[arg(long, default_value_t = ',')]
+        delimiter: char,
+        /// Show what would be imported without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write a review of a week, month, quarter or year as Markdown or HTML
+    Report {
+        /// day, week, month, quarter or year
+        #[arg(long, default_value = "month")]
+        period: String,
+        /// Review the period containing this date instead of the current one
+        #[arg(long)]
+        at: Option<String>,
+        /// File to write to; .md or .html picks the format
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Format to print when no output file is given: markdown or html
+        #[arg(long, default_value = "markdown")]
+        format: String,
+    },
+    /// Write a printable PDF of the wheel, score history and action plan
+    Print {
+        /// PDF file to write
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Write a Markdown note per area, with scores and action items, into a
+    /// folder such as an Obsidian vault
+    Vault {
+        /// Folder to write the notes to; existing notes are overwritten
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Aggregate the exports of several people into a team wheel
+    Team {
+        /// Files written by `roda export`
+        #[arg(required = true)]
+        exports: Vec<PathBuf>,
+        /// Hide who gave which score
+        #[arg(long)]
+        anonymize: bool,
+        /// Print the full result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show or change where the database is stored
+    Db {
+        #[command(subcommand)]
+        command: DbCommand,
+    },
+    /// Share action items with calendar apps as iCalendar files
+    Calendar {
+        #[command(subcommand)]
+        command: CalendarCommand,
+    },
+    /// Add preset sets of areas to the wheel or save your own
+    Templates {
+        #[command(subcommand)]
+        command: TemplatesCommand,
+    },
+    /// Manage independent wheels, each with its own database
+    Profiles {
+        #[command(subcommand)]
+        command: ProfilesCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum AreasCommand {
+    /// List life areas with their latest score
+    List {
+        /// Include archived areas
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbCommand {
+    /// Print the database path and where it was configured
+    Path,
+    /// Move the database to a new file or directory and remember the new location
+    Move { target: PathBuf },
+    /// Encrypt the database with a passphrase asked for on every use
+    Encrypt,
+    /// Store the database unencrypted again
+    Decrypt,
+    /// Change the passphrase of the encrypted database
+    Passphrase,
+}
+
+#[derive(Subcommand)]
+enum TemplatesCommand {
+    /// List built-in and saved templates
+    List,
+    /// Show the areas of a template
+    Show { template: String },
+    /// Add the areas of a template, skipping names already in the wheel
+    Apply { template: String },
+    /// Save the active areas as a template
+    Save {
+        name: String,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Delete a saved template
+    Delete { template: String },
+}
+
+#[derive(Subcommand)]
+enum CalendarCommand {
+    /// Write action items as tasks, archived ones marked completed
+    Export {
+        /// File to write to instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Write open items as all-day events instead, for calendars without tasks
+        #[arg(long)]
+        events: bool,
+    },
+    /// Archive or reopen action items completed or reopened in a calendar
+    Import { file: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum ProfilesCommand {
+    /// List profiles; the active one is marked with *
+    List,
+    /// Create a profile with an empty wheel
+    Create { name: String },
+    /// Rename a profile
+    Rename { name: String, new_name: String },
+    /// Use a profile for every later command and for the desktop app
+    Switch { name: String },
+    /// Delete a profile and its data
+    Delete { name: String },
+}
+
+#[derive(Subcommand)]
+enum ActionsCommand {
+    /// Add an action item to an area
+    Add {
+        area: String,
+        title: String,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// List open action items
+    List {
+        /// Only show items of this area
+        #[arg(long)]
+        area: Option<String>,
+    },
+    /// Archive an action item, scheduling its next occurrence if it recurs
+    Archive { id: i64 },
+    /// Write action items as a todo.txt or Markdown task list
+    Export {
+        /// File to write to instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// todotxt or markdown; defaults to the output file extension
+        #[arg(long)]
+        format: Option<String>,
+    },
+    /// Create or update action items from a todo.txt or Markdown task list
+    Import {
+        file: PathBuf,
+        /// todotxt or markdown; defaults to the file extension
+        #[arg(long)]
+        format: Option<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(path) = cli.db {
+        set_db_path_override(path);
+    }
+
+    let mut out = io::stdout().lock();
+    let result = run(cli.command, &mut out).and_then(|()| Ok(out.flush()?));
+    match result {
+        // The reader went away, as in `roda export | head`: not an error.
+        Ok(()) | Err(CliError::BrokenPipe) => ExitCode::SUCCESS,
+        Err(CliError::Failed(e)) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Why a command stopped.
+enum CliError {
+    Failed(String),
+    /// Standard output was closed before everything was written.
+    BrokenPipe,
+}
+
+impl From<String> for CliError {
+    fn from(e: String) -> Self {
+        CliError::Failed(e)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        if e.kind() == io::ErrorKind::BrokenPipe {
+            CliError::BrokenPipe
+        } else {
+            CliError::Failed(format!("Failed to write output: {}", e))
+        }
+    }
+}
+
+fn run(command: Command, out: &mut impl Write) -> Result<(), CliError> {
+    let command = match command {
+        Command::Team {
+            exports,
+            anonymize,
+            json,
+        } => return run_team(&exports, anonymize, json, out),
+        Command::Db { command } => return run_db(command, out),
+        Command::Profiles { command } => return run_profiles(command, out),
+        command => command,
+    };
+
+    let path = get_db_path()?;
+    unlock_database(&path)?;
+    let store = Store::open(&path)?;
+
+    match command {
+        Command::Areas {
+            command: AreasCommand::List { all },
+        } => {
+            let areas = store.get_life_areas(all)?;
+            let mut rows = Vec::new();
+            for area in areas {
+                let latest = store.get_latest_score(area.id)?;
+                rows.push(vec![
+                    area.id.to_string(),
+                    area.name,
+                    latest.map_or("-".to_string(), |score| score.value.to_string()),
+                    if area.is_active { "" } else { "archived" }.to_string(),
+                ]);
+            }
+            write!(
+                out,
+                "{}",
+                format::table(&["ID", "AREA", "SCORE", ""], &rows)
+            )?;
+        }
+        Command::Score {
+            area,
+            value,
+            at,
+            note,
+        } => {
+            let area = find_area(&store, &area)?;
+            let recorded_at = at.as_deref().map(parse_local_time).transpose()?;
+            let score = store.create_score(area.id, value, recorded_at, note)?;
+            writeln!(
+                out,
+                "Recorded {} for {} at {}",
+                score.value,
+                area.name,
+                format::datetime(score.recorded_at)
+            )?;
+        }
+        Command::Actions { command } => run_actions(&store, command, out)?,
+        Command::Calendar { command } => run_calendar(&store, command, out)?,
+        Command::Templates { command } => run_templates(&store, command, out)?,
+        Command::Wheel {
+            at,
+            output,
+            size,
+            theme,
+        } => {
+            let entries = match at {
+                Some(at) => store.get_wheel_as_of(parse_as_of_time(&at)?)?,
+                None => {
+                    let mut entries = Vec::new();
+                    for area in store.get_life_areas(false)? {
+                        let score = store.get_latest_score(area.id)?;
+                        entries.push(WheelEntry { area, score });
+                    }
+                    entries
+                }
+            };
+            match output {
+                Some(path) => {
+                    let options = WheelImageOptions {
+                        size,
+                        theme: WheelTheme::parse(&theme)?,
+                    };
+                    save_wheel_image(&entries, &path, &options)?;
+                    writeln!(out, "Wrote {}", path.display())?;
+                }
+                None => write!(out, "{}", format::wheel(&entries))?,
+            }
+        }
+        Command::Export { output } => {
+            let export = store.export()?;
+            let json = serde_json::to_string_pretty(&export)
+                .map_err(|e| format!("Failed to serialize export: {}", e))?;
+            match output {
+                Some(path) => std::fs::write(&path, json + "\n")
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?,
+                None => writeln!(out, "{}", json)?,
+            }
+        }
+        Command::Report {
+            period,
+            at,
+            output,
+            format,
+        } => {
+            let at = at.map(|at| parse_local_time(&at)).transpose()?;
+            let report = store.get_review_report(period, at)?;
+            match output {
+                Some(path) => {
+                    report.save(&path)?;
+                    writeln!(out, "Wrote {}", path.display())?;
+                }
+                None => write!(out, "{}", report.render(ReportFormat::parse(&format)?))?,
+            }
+        }
+        Command::ImportCsv {
+            file,
+            kind,
+            area,
+            value,
+            date,
+            date_format,
+            title,
+            note,
+            delimiter,
+            dry_run,
+        } => {
+            let mapping = CsvColumnMapping {
+                kind: CsvImportKind::parse(&kind)?,
+                area,
+                value,
+                date,
+                date_format,
+                title,
+                note,
+                delimiter: Some(delimiter),
+            };
+            let contents = std::fs::read_to_string(&file)
+                .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            let preview = store.import_csv(&contents, &mapping, dry_run)?;
+            write!(out, "{}", format::csv_import(&preview, mapping.kind))?;
+        }
+        Command::Print { output } => {
+            store.get_printable_wheel()?.save(&output)?;
+            writeln!(out, "Wrote {}", output.display())?;
+        }
+        Command::Vault { output } => {
+            let paths = store.get_markdown_vault()?.save(&output)?;
+            writeln!(out, "Wrote {} notes to {}", paths.len(), output.display())?;
+        }
+        Command::Team { .. } | Command::Db { .. } | Command::Profiles { .. } => {
+            unreachable!("handled before opening the store")
+        }
+    }
+
+    Ok(())
+}
+
+fn run_team(
+    exports: &[PathBuf],
+    anonymize: bool,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    let team = load_team_wheel(exports, anonymize)?;
+
+    if json {
+        let json = serde_json::to_string_pretty(&team)
+            .map_err(|e| format!("Failed to serialize team wheel: {}", e))?;
+        writeln!(out, "{}", json)?;
+    } else {
+        write!(out, "{}", format::team(&team))?;
+    }
+
+    Ok(())
+}
+
+fn run_db(command: DbCommand, out: &mut impl Write) -> Result<(), CliError> {
+    match command {
+        DbCommand::Path => {
+            let location = resolve_db_location()?;
+            writeln!(
+                out,
+                "{} ({})",
+                location.path.display(),
+                source_label(location.source)
+            )?;
+        }
+        DbCommand::Move { target } => {
+            unlock_database(&get_db_path()?)?;
+            let location = move_database(&target)?;
+            writeln!(out, "Moved database to {}", location.path.display())?;
+        }
+        DbCommand::Encrypt => {
+            let passphrase = prompt_new_passphrase()?;
+            encrypt_database(&passphrase)?;
+            writeln!(
+                out,
+                "Encrypted the database; keep the passphrase safe, it cannot be recovered"
+            )?;
+        }
+        DbCommand::Decrypt => {
+            unlock_database(&get_db_path()?)?;
+            decrypt_database()?;
+            writeln!(out, "Decrypted the database")?;
+        }
+        DbCommand::Passphrase => {
+            unlock_database(&get_db_path()?)?;
+            // Checks the current passphrase before asking for a new one
+            init_database()?;
+            let passphrase = prompt_new_passphrase()?;
+            change_db_passphrase(&passphrase)?;
+            writeln!(out, "Changed the passphrase")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Asks for the passphrase of an encrypted database unless `RODA_DB_PASSPHRASE`
+/// gives it.
+fn unlock_database(path: &Path) -> Result<(), String> {
+    if !is_database_encrypted(path)? || has_db_passphrase() {
+        return Ok(());
+    }
+
+    let passphrase = rpassword::prompt_password(format!("Passphrase for {}: ", path.display()))
+        .map_err(|e| {
+            format!(
+                "The database is encrypted and the passphrase could not be read ({}); set {}",
+                e, DB_PASSPHRASE_ENV
+            )
+        })?;
+    set_db_passphrase(Some(passphrase));
+    Ok(())
+}
+
+fn prompt_new_passphrase() -> Result<String, String> {
+    let passphrase = rpassword::prompt_password("New passphrase: ")
+        .map_err(|e| format!("Failed to read passphrase: {}", e))?;
+    let confirmation = rpassword::prompt_password("Repeat the passphrase: ")
+        .map_err(|e| format!("Failed to read passphrase: {}", e))?;
+    if passphrase != confirmation {
+        return Err("The passphrases do not match".to_string());
+    }
+    Ok(passphrase)
+}
+
+fn run_profiles(command: ProfilesCommand, out: &mut impl Write) -> Result<(), CliError> {
+    match command {
+        ProfilesCommand::List => {
+            let rows: Vec<Vec<String>> = list_profiles()?
+                .into_iter()
+                .map(|profile| {
+                    vec![
+                        if profile.is_active { "*" } else { "" }.to_string(),
+                        profile.name,
+                        profile.path.display().to_string(),
+                    ]
+                })
+                .collect();
+            write!(
+                out,
+                "{}",
+                format::table(&["", "PROFILE", "DATABASE"], &rows)
+            )?;
+        }
+        ProfilesCommand::Create { name } => {
+            let profile = create_profile(&name)?;
+            writeln!(out, "Created profile {}", profile.name)?;
+        }
+        ProfilesCommand::Rename { name, new_name } => {
+            let profile = rename_profile(&name, &new_name)?;
+            writeln!(out, "Renamed {} to {}", name, profile.name)?;
+        }
+        ProfilesCommand::Switch { name } => {
+            let profile = switch_profile(&name)?;
+            writeln!(out, "Switched to {}", profile.name)?;
+        }
+        ProfilesCommand::Delete { name } => {
+            delete_profile(&name)?;
+            writeln!(out, "Deleted profile {}", name)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn source_label(source: LocationSource) -> String {
+    match source {
+        LocationSource::Flag => "--db flag".to_string(),
+        LocationSource::Environment => DB_PATH_ENV.to_string(),
+        LocationSource::Portable => "portable mode".to_string(),
+        LocationSource::Config => "config file".to_string(),
+        LocationSource::Default => "default".to_string(),
+    }
+}
+
+fn run_calendar(
+    store: &Store,
+    command: CalendarCommand,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    match command {
+        CalendarCommand::Export { output, events } => {
+            let calendar = store.export_ical(if events { "event" } else { "todo" }.to_string())?;
+            match output {
+                Some(path) => std::fs::write(&path, calendar)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?,
+                None => write!(out, "{}", calendar)?,
+            }
+        }
+        CalendarCommand::Import { file } => {
+            let contents = std::fs::read_to_string(&file)
+                .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            let summary = store.import_ical(&contents)?;
+            writeln!(
+                out,
+                "Completed {}, reopened {}, unchanged {}, skipped {}",
+                summary.completed, summary.reopened, summary.unchanged, summary.skipped
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn run_templates(
+    store: &Store,
+    command: TemplatesCommand,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    match command {
+        TemplatesCommand::List => {
+            let list = list_templates()?;
+            let rows: Vec<Vec<String>> = list
+                .templates
+                .into_iter()
+                .map(|template| {
+                    vec![
+                        template.id,
+                        template.name,
+                        template.areas.len().to_string(),
+                        if template.built_in {
+                            "built-in"
+                        } else {
+                            "saved"
+                        }
+                        .to_string(),
+                    ]
+                })
+                .collect();
+            write!(
+                out,
+                "{}",
+                format::table(&["ID", "NAME", "AREAS", ""], &rows)
+            )?;
+            for skipped in list.skipped {
+                writeln!(out, "Skipped {}", skipped)?;
+            }
+        }
+        TemplatesCommand::Show { template } => {
+            let mut template = get_template(&template)?;
+            template.areas.sort_by_key(|area| area.order);
+            writeln!(out, "{}", template.name)?;
+            if let Some(description) = &template.description {
+                writeln!(out, "{}", description)?;
+            }
+            writeln!(out)?;
+            let rows: Vec<Vec<String>> = template
+                .areas
+                .into_iter()
+                .map(|area| vec![area.name, area.color, area.description.unwrap_or_default()])
+                .collect();
+            write!(
+                out,
+                "{}",
+                format::table(&["AREA", "COLOR", "DESCRIPTION"], &rows)
+            )?;
+        }
+        TemplatesCommand::Apply { template } => {
+            let template = get_template(&template)?;
+            let created = store.apply_template(&template)?;
+            writeln!(
+                out,
+                "Added {} of {} areas from {}",
+                created.len(),
+                template.areas.len(),
+                template.name
+            )?;
+        }
+        TemplatesCommand::Save { name, description } => {
+            let areas = store.get_life_areas(false)?;
+            let template = save_template(&name, description, &areas)?;
+            writeln!(
+                out,
+                "Saved {} areas as template {} ({})",
+                template.areas.len(),
+                template.name,
+                template.id
+            )?;
+        }
+        TemplatesCommand::Delete { template } => {
+            delete_template(&template)?;
+            writeln!(out, "Deleted template {}", template)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn run_actions(
+    store: &Store,
+    command: ActionsCommand,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    match command {
+        ActionsCommand::Add {
+            area,
+            title,
+            description,
+        } => {
+            let area = find_area(store, &area)?;
+            let item = store.create_action_item(area.id, title, description)?;
+            writeln!(out, "Added #{} to {}: {}", item.id, area.name, item.title)?;
+        }
+        ActionsCommand::List { area } => {
+            let area_filter = area.map(|area| find_area(store, &area)).transpose()?;
+            let areas = store.get_life_areas(true)?;
+            let items = store.get_all_action_items(area_filter.map(|area| area.id), None)?;
+
+            let rows: Vec<Vec<String>> = items
+                .into_iter()
+                .map(|item| {
+                    let area_name = areas
+                        .iter()
+                        .find(|area| area.id == item.area_id)
+                        .map_or("?".to_string(), |area| area.name.clone());
+                    vec![
+                        item.id.to_string(),
+                        area_name,
+                        item.title,
+                        item.due_at.map_or(String::new(), format::date),
+                    ]
+                })
+                .collect();
+            write!(
+                out,
+                "{}",
+                format::table(&["ID", "AREA", "TITLE", "DUE"], &rows)
+            )?;
+        }
+        ActionsCommand::Archive { id } => match store.archive_action_item(id)? {
+            Some(next) => writeln!(
+                out,
+                "Archived #{}; next occurrence #{} is due {}",
+                id,
+                next.id,
+                next.due_at.map_or("-".to_string(), format::date)
+            )?,
+            None => writeln!(out, "Archived #{}", id)?,
+        },
+        ActionsCommand::Export { output, format } => {
+            let format = task_list_format(format, output.as_deref());
+            let list = store.export_task_list(format)?;
+            match output {
+                Some(path) => std::fs::write(&path, list)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?,
+                None => write!(out, "{}", list)?,
+            }
+        }
+        ActionsCommand::Import { file, format } => {
+            let format = task_list_format(format, Some(&file));
+            let contents = std::fs::read_to_string(&file)
+                .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            let summary = store.import_task_list(&contents, format)?;
+            writeln!(
+                out,
+                "Created {}, updated {}, unchanged {}, skipped {}",
+                summary.created,
+                summary.updated,
+                summary.unchanged,
+                summary.skipped.len()
+            )?;
+            for skipped in summary.skipped {
+                writeln!(out, "  line {}: {}", skipped.line, skipped.reason)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The `--format` given, or the one matching the extension of `path`.
+fn task_list_format(format: Option<String>, path: Option<&Path>) -> String {
+    format.unwrap_or_else(|| {
+        match path.map(TaskListFormat::from_path) {
+            Some(TaskListFormat::Markdown) => "markdown",
+            _ => "todotxt",
+        }
+        .to_string()
+    })
+}
+
+/// Looks up an active area by id or, ignoring case, by name.
+fn find_area(store: &Store, key: &str) -> Result<LifeArea, String> {
+    let areas = store.get_life_areas(false)?;
+
+    if let Ok(id) = key.parse::<i64>() {
+        if let Some(area) = areas.iter().find(|area| area.id == id) {
+            return Ok(area.clone());
+        }
+    }
+
+    let name = key.trim().to_lowercase();
+    areas
+        .into_iter()
+        .find(|area| area.name.to_lowercase() == name)
+        .ok_or_else(|| format!("No active area named \"{}\"", key))
+}
+
+/// Parses `YYYY-MM-DD` (midnight) or `YYYY-MM-DD HH:MM` in local time.
+fn parse_local_time(value: &str) -> Result<i64, String> {
+    let value = value.trim();
+    let naive = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => date.and_hms_opt(0, 0, 0),
+        Err(_) => NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+            .ok(),
+    }
+    .ok_or_else(|| format!("Invalid date \"{}\", expected YYYY-MM-DD [HH:MM]", value))?;
+
+    let timestamp = Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("\"{}\" does not exist in the local time zone", value))?
+        .timestamp();
+    if timestamp > Utc::now().timestamp() {
+        return Err("Date cannot be in the future".to_string());
+    }
+
+    Ok(timestamp)
+}
+
+/// Like `parse_local_time`, but a date without a time means the last second
+/// of that day (or now, for today), so that its scores are included.
+fn parse_as_of_time(value: &str) -> Result<i64, String> {
+    let start = parse_local_time(value)?;
+    let Ok(date) = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d") else {
+        return Ok(start);
+    };
+
+    let next_start = date
+        .succ_opt()
+        .and_then(local_midnight)
+        .ok_or_else(|| format!("Invalid date \"{}\"", value.trim()))?;
+    Ok((next_start - 1).min(Utc::now().timestamp()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn as_of_a_bare_date_means_the_end_of_that_day() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 9).unwrap();
+        let start = local_midnight(day).unwrap();
+        let end = local_midnight(day.succ_opt().unwrap()).unwrap() - 1;
+
+        assert_eq!(parse_local_time("2025-03-09").unwrap(), start);
+        assert_eq!(parse_as_of_time("2025-03-09").unwrap(), end);
+        assert_eq!(
+            parse_as_of_time("2025-03-09 12:30").unwrap(),
+            parse_local_time("2025-03-09 12:30").unwrap()
+        );
+
+        let today = Local::now().date_naive().format("%Y-%m-%d").to_string();
+        assert!(parse_as_of_time(&today).unwrap() <= Utc::now().timestamp());
+    }
+}
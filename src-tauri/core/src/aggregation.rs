@@ -0,0 +1,279 @@
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, TimeZone};
+use serde::Serialize;
+
+use crate::habits::{local_date, week_start};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Granularity {
+    pub fn parse(granularity: &str) -> Result<Self, String> {
+        match granularity.trim().to_lowercase().as_str() {
+            "day" => Ok(Granularity::Day),
+            "week" => Ok(Granularity::Week),
+            "month" => Ok(Granularity::Month),
+            "quarter" => Ok(Granularity::Quarter),
+            "year" => Ok(Granularity::Year),
+            _ => Err(
+                "Granularity must be \"day\", \"week\", \"month\", \"quarter\" or \"year\""
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// First day of the bucket containing `date`. Weeks start on Monday.
+    pub fn bucket_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => date,
+            Granularity::Week => week_start(date),
+            Granularity::Month => date.with_day(1).unwrap_or(date),
+            Granularity::Quarter => {
+                let month = (date.month0() / 3) * 3 + 1;
+                NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap_or(date)
+            }
+            Granularity::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date),
+        }
+    }
+
+    /// First day of the bucket after the one starting at `start`.
+    pub fn next_start(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => start + Duration::days(1),
+            Granularity::Week => start + Duration::weeks(1),
+            Granularity::Month => start + Months::new(1),
+            Granularity::Quarter => start + Months::new(3),
+            Granularity::Year => start + Months::new(12),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GapFill {
+    /// Empty buckets have no average or last value.
+    Null,
+    /// Empty buckets repeat the last value seen before them.
+    CarryForward,
+}
+
+impl GapFill {
+    pub fn parse(fill: &str) -> Result<Self, String> {
+        match fill.trim().to_lowercase().as_str() {
+            "null" => Ok(GapFill::Null),
+            "carry_forward" => Ok(GapFill::CarryForward),
+            _ => Err("Gap fill must be \"null\" or \"carry_forward\"".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScoreBucket {
+    /// Local midnight at the start of the bucket, as a Unix timestamp.
+    pub start: i64,
+    /// First day of the bucket, as `YYYY-MM-DD` in local time.
+    pub date: String,
+    pub average: Option<f64>,
+    pub last: Option<i64>,
+    pub count: i64,
+    /// True if the bucket had no scores and its values were carried forward.
+    pub filled: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AreaScoreBuckets {
+    pub area_id: i64,
+    pub buckets: Vec<ScoreBucket>,
+}
+
+/// Local midnight at the start of `date`. Falls back to the first valid time
+/// of the day when midnight is skipped by a DST change.
+pub fn local_midnight(date: NaiveDate) -> Option<i64> {
+    (0..24).find_map(|hour| {
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, 0, 0)?)
+            .earliest()
+            .map(|dt| dt.timestamp())
+    })
+}
+
+/// The local date of `timestamp` as `YYYY-MM-DD`, as shown in reports,
+/// exports and the CLI.
+pub fn format_local_date(timestamp: i64) -> String {
+    local_date(timestamp)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Starts of every bucket from the one containing `from` to the one
+/// containing `to`, or `None` if there are more than `max_buckets`.
+pub fn bucket_starts(
+    granularity: Granularity,
+    from: NaiveDate,
+    to: NaiveDate,
+    max_buckets: usize,
+) -> Option<Vec<NaiveDate>> {
+    let mut starts = Vec::new();
+    let mut start = granularity.bucket_start(from);
+    while start <= to {
+        if starts.len() == max_buckets {
+            return None;
+        }
+        starts.push(start);
+        start = granularity.next_start(start);
+    }
+    Some(starts)
+}
+
+/// Aggregates `points` (`(recorded_at, value)`, oldest first) into the buckets
+/// starting at `starts`. `carried` is the last value recorded before the first
+/// bucket, used to fill leading gaps.
+pub fn bucket_scores(
+    points: &[(i64, i64)],
+    granularity: Granularity,
+    starts: &[NaiveDate],
+    fill: GapFill,
+    carried: Option<i64>,
+) -> Vec<ScoreBucket> {
+    let mut buckets: Vec<ScoreBucket> = starts
+        .iter()
+        .map(|start| ScoreBucket {
+            start: local_midnight(*start).unwrap_or_default(),
+            date: start.format("%Y-%m-%d").to_string(),
+            average: None,
+            last: None,
+            count: 0,
+            filled: false,
+        })
+        .collect();
+
+    let mut sums = vec![0i64; buckets.len()];
+    for (recorded_at, value) in points {
+        let Some(date) = local_date(*recorded_at) else {
+            continue;
+        };
+        let Ok(index) = starts.binary_search(&granularity.bucket_start(date)) else {
+            continue;
+        };
+        sums[index] += value;
+        buckets[index].count += 1;
+        buckets[index].last = Some(*value);
+    }
+
+    let mut previous = carried;
+    for (bucket, sum) in buckets.iter_mut().zip(sums) {
+        if bucket.count > 0 {
+            bucket.average = Some(sum as f64 / bucket.count as f64);
+            previous = bucket.last;
+        } else if fill == GapFill::CarryForward && previous.is_some() {
+            bucket.average = previous.map(|value| value as f64);
+            bucket.last = previous;
+            bucket.filled = true;
+        }
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    /// A timestamp during the local day `value`.
+    fn during(value: &str) -> i64 {
+        local_midnight(date(value)).unwrap() + 3600
+    }
+
+    #[test]
+    fn buckets_start_on_monday_month_quarter_and_year() {
+        let thursday = date("2024-05-16");
+        assert_eq!(Granularity::Day.bucket_start(thursday), thursday);
+        assert_eq!(Granularity::Week.bucket_start(thursday), date("2024-05-13"));
+        assert_eq!(
+            Granularity::Month.bucket_start(thursday),
+            date("2024-05-01")
+        );
+        assert_eq!(
+            Granularity::Quarter.bucket_start(thursday),
+            date("2024-04-01")
+        );
+        assert_eq!(Granularity::Year.bucket_start(thursday), date("2024-01-01"));
+    }
+
+    #[test]
+    fn bucket_starts_cover_the_range_in_whole_buckets() {
+        let starts = bucket_starts(
+            Granularity::Quarter,
+            date("2024-02-10"),
+            date("2024-08-01"),
+            10,
+        );
+        assert_eq!(
+            starts,
+            Some(vec![
+                date("2024-01-01"),
+                date("2024-04-01"),
+                date("2024-07-01")
+            ])
+        );
+
+        let too_many = bucket_starts(Granularity::Day, date("2024-01-01"), date("2024-01-31"), 30);
+        assert_eq!(too_many, None);
+    }
+
+    #[test]
+    fn empty_buckets_are_null_or_carry_the_last_value_forward() {
+        let starts = vec![date("2024-01-01"), date("2024-02-01"), date("2024-03-01")];
+        let points = [
+            (during("2024-01-05"), 4),
+            (during("2024-01-20"), 6),
+            (during("2024-03-02"), 8),
+        ];
+
+        let buckets = bucket_scores(&points, Granularity::Month, &starts, GapFill::Null, None);
+        assert_eq!(buckets[0].average, Some(5.0));
+        assert_eq!(buckets[0].last, Some(6));
+        assert_eq!(buckets[0].count, 2);
+        assert_eq!(buckets[1].average, None);
+        assert!(!buckets[1].filled);
+        assert_eq!(buckets[2].last, Some(8));
+
+        let buckets = bucket_scores(
+            &points,
+            Granularity::Month,
+            &starts,
+            GapFill::CarryForward,
+            None,
+        );
+        assert_eq!(buckets[1].average, Some(6.0));
+        assert_eq!(buckets[1].last, Some(6));
+        assert_eq!(buckets[1].count, 0);
+        assert!(buckets[1].filled);
+    }
+
+    #[test]
+    fn leading_gaps_carry_the_value_from_before_the_range() {
+        let starts = vec![date("2024-01-01"), date("2024-02-01")];
+        let points = [(during("2024-02-10"), 9)];
+
+        let buckets = bucket_scores(
+            &points,
+            Granularity::Month,
+            &starts,
+            GapFill::CarryForward,
+            Some(3),
+        );
+        assert_eq!(buckets[0].last, Some(3));
+        assert!(buckets[0].filled);
+        assert_eq!(buckets[1].last, Some(9));
+        assert!(!buckets[1].filled);
+    }
+}
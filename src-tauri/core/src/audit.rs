@@ -0,0 +1,324 @@
+use chrono::Utc;
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, OptionalExtension, Params};
+use serde_json::{Map, Value};
+use std::cell::Cell;
+
+pub const ACTION_CREATE: &str = "create";
+pub const ACTION_UPDATE: &str = "update";
+pub const ACTION_DELETE: &str = "delete";
+
+pub const UNDO_STATE_DONE: &str = "done";
+pub const UNDO_STATE_UNDONE: &str = "undone";
+
+/// A row captured as `column -> value`, together with its SQLite rowid.
+#[derive(Debug, Clone)]
+pub struct RowSnapshot {
+    pub rowid: i64,
+    pub values: Map<String, Value>,
+}
+
+/// Captures the rows of `table` matching `filter` (a SQL `WHERE` expression).
+pub fn snapshot<P: Params>(
+    conn: &Connection,
+    table: &str,
+    filter: &str,
+    query_params: P,
+) -> Result<Vec<RowSnapshot>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT rowid, * FROM {} WHERE {} ORDER BY rowid",
+            table, filter
+        ))
+        .map_err(|e| format!("Failed to prepare snapshot of {}: {}", table, e))?;
+
+    let column_names: Vec<String> = stmt
+        .column_names()
+        .into_iter()
+        .skip(1)
+        .map(String::from)
+        .collect();
+
+    let mut rows = stmt
+        .query(query_params)
+        .map_err(|e| format!("Failed to snapshot {}: {}", table, e))?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| format!("Failed to read {} row: {}", table, e))?
+    {
+        let mut values = Map::new();
+        for (index, name) in column_names.iter().enumerate() {
+            let value = row
+                .get_ref(index + 1)
+                .map_err(|e| format!("Failed to read {} column: {}", table, e))?;
+            values.insert(name.clone(), value_to_json(value));
+        }
+        result.push(RowSnapshot {
+            rowid: row
+                .get(0)
+                .map_err(|e| format!("Failed to read {} rowid: {}", table, e))?,
+            values,
+        });
+    }
+
+    Ok(result)
+}
+
+fn value_to_json(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(t) => Value::from(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => Value::from(b.to_vec()),
+    }
+}
+
+/// Reconstructs a row as it was at `at` from its `current` state: the old value
+/// of the first change logged after `at`, or the current state if there is
+/// none. `None` means the row did not exist then.
+pub fn row_as_of(
+    conn: &Connection,
+    table: &str,
+    rowid: i64,
+    current: Option<Map<String, Value>>,
+    at: i64,
+) -> Result<Option<Map<String, Value>>, String> {
+    let next_change: Option<Option<String>> = conn
+        .query_row(
+            "SELECT old_value FROM change_log
+             WHERE entity_type = ?1 AND entity_id = ?2 AND changed_at > ?3
+             ORDER BY id ASC LIMIT 1",
+            params![table, rowid, at],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to query change log: {}", e))?;
+
+    match next_change {
+        None => Ok(current),
+        Some(None) => Ok(None),
+        Some(Some(old_value)) => serde_json::from_str(&old_value)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse change log value: {}", e)),
+    }
+}
+
+/// A named group of changes made by one command. The operation row is only
+/// written once the first change is recorded, so no-op commands leave no trace.
+pub struct Operation {
+    name: &'static str,
+    changed_at: i64,
+    undoable: bool,
+    id: Cell<Option<i64>>,
+}
+
+impl Operation {
+    pub fn new(name: &'static str) -> Self {
+        Operation {
+            name,
+            changed_at: Utc::now().timestamp(),
+            undoable: false,
+            id: Cell::new(None),
+        }
+    }
+
+    /// An operation pushed onto the undo stack. Recording it discards
+    /// everything that could still be redone.
+    pub fn undoable(name: &'static str) -> Self {
+        Operation {
+            undoable: true,
+            ..Operation::new(name)
+        }
+    }
+
+    pub fn id(&self, conn: &Connection) -> Result<i64, String> {
+        if let Some(id) = self.id.get() {
+            return Ok(id);
+        }
+
+        if self.undoable {
+            conn.execute(
+                "UPDATE change_operations SET undo_state = NULL WHERE undo_state = ?1",
+                params![UNDO_STATE_UNDONE],
+            )
+            .map_err(|e| format!("Failed to clear redo stack: {}", e))?;
+        }
+
+        conn.execute(
+            "INSERT INTO change_operations (name, created_at, undo_state) VALUES (?1, ?2, ?3)",
+            params![
+                self.name,
+                self.changed_at,
+                self.undoable.then_some(UNDO_STATE_DONE)
+            ],
+        )
+        .map_err(|e| format!("Failed to record operation: {}", e))?;
+
+        let id = conn.last_insert_rowid();
+        self.id.set(Some(id));
+        Ok(id)
+    }
+
+    fn record(
+        &self,
+        conn: &Connection,
+        table: &str,
+        entity_id: i64,
+        action: &str,
+        old_value: Option<&Map<String, Value>>,
+        new_value: Option<&Map<String, Value>>,
+    ) -> Result<(), String> {
+        let operation_id = self.id(conn)?;
+        let to_text = |value: Option<&Map<String, Value>>| {
+            value.map(|v| Value::Object(v.clone()).to_string())
+        };
+
+        conn.execute(
+            "INSERT INTO change_log (operation_id, entity_type, entity_id, action, old_value, new_value, changed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                operation_id,
+                table,
+                entity_id,
+                action,
+                to_text(old_value),
+                to_text(new_value),
+                self.changed_at
+            ],
+        )
+        .map_err(|e| format!("Failed to record change: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Records every row of `table` matching `filter` as newly created.
+    pub fn created<P: Params>(
+        &self,
+        conn: &Connection,
+        table: &str,
+        filter: &str,
+        query_params: P,
+    ) -> Result<(), String> {
+        for row in snapshot(conn, table, filter, query_params)? {
+            self.record(
+                conn,
+                table,
+                row.rowid,
+                ACTION_CREATE,
+                None,
+                Some(&row.values),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Records the difference between `before` and the current state of the
+    /// same rows. Rows whose values did not change are skipped.
+    pub fn updated(
+        &self,
+        conn: &Connection,
+        table: &str,
+        before: Vec<RowSnapshot>,
+    ) -> Result<(), String> {
+        for old_row in before {
+            let new_row = snapshot(conn, table, "rowid = ?", params![old_row.rowid])?;
+            match new_row.first() {
+                Some(new_row) if new_row.values != old_row.values => self.record(
+                    conn,
+                    table,
+                    old_row.rowid,
+                    ACTION_UPDATE,
+                    Some(&old_row.values),
+                    Some(&new_row.values),
+                )?,
+                Some(_) => {}
+                None => self.record(
+                    conn,
+                    table,
+                    old_row.rowid,
+                    ACTION_DELETE,
+                    Some(&old_row.values),
+                    None,
+                )?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the rows of `table` matching `filter`, recording each of them.
+    pub fn delete_where<P: Params + Copy>(
+        &self,
+        conn: &Connection,
+        table: &str,
+        filter: &str,
+        query_params: P,
+    ) -> Result<usize, String> {
+        let before = snapshot(conn, table, filter, query_params)?;
+
+        conn.execute(
+            &format!("DELETE FROM {} WHERE {}", table, filter),
+            query_params,
+        )
+        .map_err(|e| format!("Failed to delete from {}: {}", table, e))?;
+
+        self.deleted(conn, table, &before)?;
+        Ok(before.len())
+    }
+
+    /// Records `rows`, already removed from `table`, as deleted.
+    pub fn deleted(
+        &self,
+        conn: &Connection,
+        table: &str,
+        rows: &[RowSnapshot],
+    ) -> Result<(), String> {
+        for row in rows {
+            self.record(
+                conn,
+                table,
+                row.rowid,
+                ACTION_DELETE,
+                Some(&row.values),
+                None,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Moves the rows of `table` matching `filter` into the trash, grouped
+    /// under this operation, recording both the deletion and the trash entries.
+    pub fn trash_where<P: Params + Copy>(
+        &self,
+        conn: &Connection,
+        table: &str,
+        filter: &str,
+        query_params: P,
+    ) -> Result<usize, String> {
+        let rows = snapshot(conn, table, filter, query_params)?;
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let batch_id = self.id(conn)?;
+        for row in &rows {
+            conn.execute(
+                "INSERT INTO trash (batch_id, entity_type, entity_id, data, deleted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    batch_id,
+                    table,
+                    row.rowid,
+                    Value::Object(row.values.clone()).to_string(),
+                    self.changed_at
+                ],
+            )
+            .map_err(|e| format!("Failed to move {} row to trash: {}", table, e))?;
+            self.created(conn, "trash", "id = ?", params![conn.last_insert_rowid()])?;
+        }
+
+        self.delete_where(conn, table, filter, query_params)
+    }
+}
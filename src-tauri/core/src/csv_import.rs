@@ -0,0 +1,386 @@
+use crate::aggregation::local_midnight;
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// What the rows of a CSV file become.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvImportKind {
+    Scores,
+    ActionItems,
+}
+
+impl CsvImportKind {
+    pub fn parse(kind: &str) -> Result<Self, String> {
+        match kind.trim().to_lowercase().replace('-', "_").as_str() {
+            "scores" => Ok(CsvImportKind::Scores),
+            "action_items" | "actions" => Ok(CsvImportKind::ActionItems),
+            _ => Err("Kind must be \"scores\" or \"action_items\"".to_string()),
+        }
+    }
+}
+
+/// Which column, by header name, holds each field. Header names are matched
+/// ignoring case and surrounding spaces.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CsvColumnMapping {
+    pub kind: CsvImportKind,
+    pub area: String,
+    /// Score from 0 to 10; required for scores.
+    #[serde(default)]
+    pub value: Option<String>,
+    /// When the score was given or the action item created. Without it rows
+    /// are dated at the time of import.
+    #[serde(default)]
+    pub date: Option<String>,
+    /// chrono format of the date column, `%Y-%m-%d` by default. Dates
+    /// without a time of day are read as local midnight.
+    #[serde(default)]
+    pub date_format: Option<String>,
+    /// Required for action items.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Score note, or action item description.
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Field separator, `,` by default.
+    #[serde(default)]
+    pub delimiter: Option<char>,
+}
+
+/// The outcome of a CSV import. In a dry run nothing is written, but the rows,
+/// errors and new areas are those a real import would have.
+#[derive(Debug, Default, Serialize, Clone)]
+pub struct CsvImportPreview {
+    pub dry_run: bool,
+    /// Rows that are imported, in file order.
+    pub rows: Vec<CsvImportRow>,
+    /// Rows left out, with why.
+    pub errors: Vec<CsvRowError>,
+    /// Area names that matched no active area and are created.
+    pub new_areas: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CsvImportRow {
+    /// Line of the row in the file, counting the header as line 1.
+    pub line: usize,
+    pub area: String,
+    pub value: Option<i64>,
+    pub date: Option<i64>,
+    pub title: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CsvRowError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Column indexes of the mapped fields.
+struct Columns {
+    area: usize,
+    value: Option<usize>,
+    date: Option<usize>,
+    title: Option<usize>,
+    note: Option<usize>,
+}
+
+/// Reads every row of `contents` with `mapping`. Fails only if the file or
+/// the mapping cannot be used at all; problems with single rows are returned
+/// as that row's error.
+pub(crate) fn read_csv(
+    contents: &str,
+    mapping: &CsvColumnMapping,
+) -> Result<Vec<Result<CsvImportRow, CsvRowError>>, String> {
+    let delimiter = mapping.delimiter.unwrap_or(',');
+    if !delimiter.is_ascii() {
+        return Err("The delimiter must be a single ASCII character".to_string());
+    }
+    match mapping.kind {
+        CsvImportKind::Scores if mapping.value.is_none() => {
+            return Err("Map a value column to import scores".to_string())
+        }
+        CsvImportKind::ActionItems if mapping.title.is_none() => {
+            return Err("Map a title column to import action items".to_string())
+        }
+        _ => {}
+    }
+    let date_format = mapping
+        .date_format
+        .as_deref()
+        .unwrap_or(DEFAULT_DATE_FORMAT);
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV header: {}", e))?
+        .iter()
+        .map(|header| header.trim_start_matches('\u{feff}').trim().to_lowercase())
+        .collect();
+    let find = |name: &str| {
+        let wanted = name.trim().to_lowercase();
+        headers
+            .iter()
+            .position(|header| *header == wanted)
+            .ok_or_else(|| format!("No column named \"{}\"", name))
+    };
+    let optional = |name: &Option<String>| name.as_deref().map(find).transpose();
+    let columns = Columns {
+        area: find(&mapping.area)?,
+        value: optional(&mapping.value)?,
+        date: optional(&mapping.date)?,
+        title: optional(&mapping.title)?,
+        note: optional(&mapping.note)?,
+    };
+
+    let mut rows = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let row = match record {
+            Ok(record) => {
+                let line = line_of(contents, record.position(), index);
+                if record.iter().all(|cell| cell.trim().is_empty()) {
+                    continue;
+                }
+                read_row(&record, &columns, date_format, line)
+                    .map_err(|message| CsvRowError { line, message })
+            }
+            Err(e) => Err(CsvRowError {
+                line: line_of(contents, e.position(), index),
+                message: format!("Failed to read row: {}", e),
+            }),
+        };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// The 1-based line a record starts on. Counted from its byte offset, since
+/// the line the csv crate reports is off after blank lines and `\r\n` endings.
+/// The offset may point at blank lines before the record, which are skipped.
+fn line_of(contents: &str, position: Option<&csv::Position>, index: usize) -> usize {
+    let Some(position) = position else {
+        return index + 2;
+    };
+    let bytes = contents.as_bytes();
+    let mut offset = (position.byte() as usize).min(bytes.len());
+    while offset < bytes.len() && matches!(bytes[offset], b'\r' | b'\n') {
+        offset += 1;
+    }
+    bytes[..offset]
+        .iter()
+        .filter(|byte| **byte == b'\n')
+        .count()
+        + 1
+}
+
+fn read_row(
+    record: &csv::StringRecord,
+    columns: &Columns,
+    date_format: &str,
+    line: usize,
+) -> Result<CsvImportRow, String> {
+    let cell = |index: Option<usize>| {
+        index
+            .and_then(|index| record.get(index))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+
+    let area = cell(Some(columns.area)).ok_or("The area is empty")?;
+    let value = columns
+        .value
+        .map(|_| {
+            let value = cell(columns.value).ok_or("The score is empty")?;
+            parse_value(value)
+        })
+        .transpose()?;
+    let date = columns
+        .date
+        .map(|_| {
+            let date = cell(columns.date).ok_or("The date is empty")?;
+            parse_date(date, date_format)
+        })
+        .transpose()?;
+
+    Ok(CsvImportRow {
+        line,
+        area: area.to_string(),
+        value,
+        date,
+        title: cell(columns.title).map(str::to_string),
+        note: cell(columns.note).map(str::to_string),
+    })
+}
+
+/// A whole number from 0 to 10; spreadsheets often write `7` as `7.0`.
+fn parse_value(value: &str) -> Result<i64, String> {
+    let number: f64 = value
+        .replace(',', ".")
+        .parse()
+        .map_err(|_| format!("\"{}\" is not a score", value))?;
+    if number.fract() != 0.0 || !(0.0..=10.0).contains(&number) {
+        return Err("Score must be a whole number between 0 and 10".to_string());
+    }
+    Ok(number as i64)
+}
+
+fn parse_date(value: &str, format: &str) -> Result<i64, String> {
+    let invalid = || {
+        format!(
+            "\"{}\" does not match the date format \"{}\"",
+            value, format
+        )
+    };
+    match NaiveDateTime::parse_from_str(value, format) {
+        Ok(naive) => Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|dt| dt.timestamp())
+            .ok_or_else(|| format!("\"{}\" does not exist in the local time zone", value)),
+        Err(_) => NaiveDate::parse_from_str(value, format)
+            .ok()
+            .and_then(local_midnight)
+            .ok_or_else(invalid),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores_mapping() -> CsvColumnMapping {
+        CsvColumnMapping {
+            kind: CsvImportKind::Scores,
+            area: "Area".to_string(),
+            value: Some("Score".to_string()),
+            date: Some("Date".to_string()),
+            date_format: None,
+            title: None,
+            note: Some("Note".to_string()),
+            delimiter: None,
+        }
+    }
+
+    #[test]
+    fn reads_rows_by_header_ignoring_case_and_bom() {
+        let contents = "\u{feff}area, SCORE ,date,note\nHealth,7,2024-01-10,Ran twice\nCareer,\"6.0\",2024-01-11,\n";
+        let rows = read_csv(contents, &scores_mapping()).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        let health = rows[0].as_ref().unwrap();
+        assert_eq!(health.line, 2);
+        assert_eq!(health.area, "Health");
+        assert_eq!(health.value, Some(7));
+        assert_eq!(
+            health.date,
+            local_midnight(NaiveDate::from_ymd_opt(2024, 1, 10).unwrap())
+        );
+        assert_eq!(health.note.as_deref(), Some("Ran twice"));
+
+        let career = rows[1].as_ref().unwrap();
+        assert_eq!(career.value, Some(6));
+        assert_eq!(career.note, None);
+    }
+
+    #[test]
+    fn semicolon_files_may_use_decimal_commas_and_custom_dates() {
+        let mapping = CsvColumnMapping {
+            date_format: Some("%d/%m/%Y %H:%M".to_string()),
+            delimiter: Some(';'),
+            ..scores_mapping()
+        };
+        let contents = "Area;Score;Date;Note\nHealth;8,0;10/01/2024 18:30;\n";
+        let rows = read_csv(contents, &mapping).unwrap();
+
+        let row = rows[0].as_ref().unwrap();
+        assert_eq!(row.value, Some(8));
+        let expected = NaiveDate::from_ymd_opt(2024, 1, 10)
+            .unwrap()
+            .and_hms_opt(18, 30, 0)
+            .unwrap();
+        assert_eq!(
+            row.date,
+            Some(
+                Local
+                    .from_local_datetime(&expected)
+                    .earliest()
+                    .unwrap()
+                    .timestamp()
+            )
+        );
+    }
+
+    #[test]
+    fn bad_rows_are_reported_with_their_line() {
+        let contents = "Area,Score,Date,Note\n,5,2024-01-10,\nHealth,11,2024-01-10,\nHealth,7,10/01/2024,\nHealth,abc,2024-01-10,\n";
+        let rows = read_csv(contents, &scores_mapping()).unwrap();
+
+        let errors: Vec<(usize, String)> = rows
+            .into_iter()
+            .map(|row| {
+                let error = row.unwrap_err();
+                (error.line, error.message)
+            })
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (2, "The area is empty".to_string()),
+                (
+                    3,
+                    "Score must be a whole number between 0 and 10".to_string()
+                ),
+                (
+                    4,
+                    "\"10/01/2024\" does not match the date format \"%Y-%m-%d\"".to_string()
+                ),
+                (5, "\"abc\" is not a score".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn mapping_must_name_existing_columns_and_required_fields() {
+        let contents = "Area,Score\nHealth,5\n";
+
+        let error = read_csv(contents, &scores_mapping()).unwrap_err();
+        assert_eq!(error, "No column named \"Date\"");
+
+        let mapping = CsvColumnMapping {
+            kind: CsvImportKind::ActionItems,
+            ..scores_mapping()
+        };
+        let error = read_csv(contents, &mapping).unwrap_err();
+        assert_eq!(error, "Map a title column to import action items");
+    }
+
+    #[test]
+    fn lines_are_counted_across_blank_lines_and_crlf() {
+        for newline in ["\n", "\r\n"] {
+            let contents = [
+                "Area,Score,Date,Note",
+                "",
+                "Health,7,2024-01-10,",
+                "",
+                "",
+                "Health,11,2024-01-10,",
+                "",
+            ]
+            .join(newline);
+            let rows = read_csv(&contents, &scores_mapping()).unwrap();
+
+            assert_eq!(rows.len(), 2);
+            assert!(rows[0].is_ok());
+            assert_eq!(rows[1].as_ref().unwrap_err().line, 6, "{:?}", newline);
+        }
+    }
+}
@@ -0,0 +1,518 @@
+use crate::encryption::connect;
+use crate::location;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+pub fn get_db_path() -> Result<PathBuf, String> {
+    Ok(location::resolve()?.path)
+}
+
+/// Opens the current database, creating it and its schema if needed. Fails
+/// with a clear message if it is encrypted and the passphrase is missing or
+/// wrong.
+pub fn init_database() -> Result<Connection, String> {
+    open_database(&get_db_path()?)
+}
+
+/// Opens the database at `db_path`, creating it and its schema if needed.
+pub fn open_database(db_path: &Path) -> Result<Connection, String> {
+    // Create directory if it doesn't exist
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let conn = connect(db_path)?;
+    create_schema(&conn)?;
+
+    Ok(conn)
+}
+
+/// Creates the tables and indexes that do not exist yet and adds the columns
+/// introduced since older databases were created.
+pub(crate) fn create_schema(conn: &Connection) -> Result<(), String> {
+    // Create tables
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS life_areas (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            description TEXT,
+            color TEXT NOT NULL,
+            \"order\" INTEGER NOT NULL,
+            is_active INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            UNIQUE(name, is_active) ON CONFLICT IGNORE
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create life_areas table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS scores (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            area_id INTEGER NOT NULL,
+            value INTEGER NOT NULL CHECK(value >= 0 AND value <= 10),
+            recorded_at INTEGER NOT NULL,
+            note TEXT,
+            FOREIGN KEY(area_id) REFERENCES life_areas(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create scores table: {}", e))?;
+
+    migrate_score_columns(conn)?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS action_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            area_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            archived_at INTEGER,
+            description TEXT,
+            due_at INTEGER,
+            recurrence TEXT,
+            FOREIGN KEY(area_id) REFERENCES life_areas(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create action_items table: {}", e))?;
+
+    migrate_action_items_table(conn)?;
+    migrate_action_item_columns(conn)?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS action_item_links (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action_item_id INTEGER NOT NULL,
+            url TEXT NOT NULL,
+            label TEXT,
+            position INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY(action_item_id) REFERENCES action_items(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create action_item_links table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS action_item_steps (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action_item_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            is_done INTEGER NOT NULL DEFAULT 0,
+            position INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            parent_id INTEGER,
+            FOREIGN KEY(action_item_id) REFERENCES action_items(id),
+            FOREIGN KEY(parent_id) REFERENCES action_item_steps(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create action_item_steps table: {}", e))?;
+
+    migrate_action_item_step_columns(conn)?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS habits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            area_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            frequency TEXT NOT NULL,
+            target_count INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            archived_at INTEGER,
+            FOREIGN KEY(area_id) REFERENCES life_areas(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create habits table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS habit_checkins (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            habit_id INTEGER NOT NULL,
+            checked_at INTEGER NOT NULL,
+            note TEXT,
+            FOREIGN KEY(habit_id) REFERENCES habits(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create habit_checkins table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS goals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            area_id INTEGER NOT NULL,
+            target_score INTEGER NOT NULL CHECK(target_score >= 0 AND target_score <= 10),
+            target_date INTEGER NOT NULL,
+            rationale TEXT,
+            status TEXT NOT NULL DEFAULT 'open',
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            closed_at INTEGER,
+            FOREIGN KEY(area_id) REFERENCES life_areas(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create goals table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS change_operations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            undo_state TEXT,
+            undone_at INTEGER
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create change_operations table: {}", e))?;
+
+    migrate_change_operation_columns(conn)?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS change_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            operation_id INTEGER NOT NULL,
+            entity_type TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            old_value TEXT,
+            new_value TEXT,
+            changed_at INTEGER NOT NULL,
+            FOREIGN KEY(operation_id) REFERENCES change_operations(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create change_log table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS trash (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            batch_id INTEGER NOT NULL,
+            entity_type TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            data TEXT NOT NULL,
+            deleted_at INTEGER NOT NULL,
+            FOREIGN KEY(batch_id) REFERENCES change_operations(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create trash table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create settings table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create tags table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS action_item_tags (
+            action_item_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            PRIMARY KEY(action_item_id, tag_id),
+            FOREIGN KEY(action_item_id) REFERENCES action_items(id),
+            FOREIGN KEY(tag_id) REFERENCES tags(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create action_item_tags table: {}", e))?;
+
+    // Create indexes
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_scores_area_id ON scores(area_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_scores_recorded_at ON scores(recorded_at)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_scores_area_recorded_at ON scores(area_id, recorded_at)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_action_items_area_id ON action_items(area_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_action_items_position ON action_items(position)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_action_items_archived ON action_items(archived_at)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_action_item_tags_tag_id ON action_item_tags(tag_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_action_item_links_item_id ON action_item_links(action_item_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_action_item_steps_item_id ON action_item_steps(action_item_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_habits_area_id ON habits(area_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_habit_checkins_habit_id ON habit_checkins(habit_id, checked_at)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_goals_area_id ON goals(area_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_change_log_entity ON change_log(entity_type, entity_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_change_log_changed_at ON change_log(changed_at)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_change_log_operation_id ON change_log(operation_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_change_operations_undo_state ON change_operations(undo_state)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_trash_batch_id ON trash(batch_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_trash_deleted_at ON trash(deleted_at)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create index: {}", e))?;
+
+    Ok(())
+}
+
+pub fn get_connection() -> Result<Connection, String> {
+    connect(&get_db_path()?)
+}
+
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| format!("Failed to read setting {}: {}", key, e))
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| format!("Failed to inspect {} table: {}", table, e))?;
+
+    let mut column_names = Vec::new();
+    let info_rows = stmt
+        .query_map([], |row| row.get::<usize, String>(1))
+        .map_err(|e| format!("Failed to iterate table info: {}", e))?;
+    for col in info_rows {
+        column_names.push(col.map_err(|e| format!("Failed to read column info: {}", e))?);
+    }
+
+    Ok(column_names)
+}
+
+fn migrate_score_columns(conn: &Connection) -> Result<(), String> {
+    add_missing_columns(conn, "scores", &[("note", "TEXT")])
+}
+
+/// Adds action item columns introduced after the table was first created.
+fn migrate_action_item_columns(conn: &Connection) -> Result<(), String> {
+    add_missing_columns(
+        conn,
+        "action_items",
+        &[
+            ("description", "TEXT"),
+            ("due_at", "INTEGER"),
+            ("recurrence", "TEXT"),
+        ],
+    )
+}
+
+fn migrate_action_item_step_columns(conn: &Connection) -> Result<(), String> {
+    add_missing_columns(conn, "action_item_steps", &[("parent_id", "INTEGER")])
+}
+
+fn migrate_change_operation_columns(conn: &Connection) -> Result<(), String> {
+    add_missing_columns(
+        conn,
+        "change_operations",
+        &[("undo_state", "TEXT"), ("undone_at", "INTEGER")],
+    )
+}
+
+fn add_missing_columns(
+    conn: &Connection,
+    table: &str,
+    columns: &[(&str, &str)],
+) -> Result<(), String> {
+    let column_names = table_columns(conn, table)?;
+
+    for (column, column_type) in columns {
+        if column_names.iter().any(|c| c == column) {
+            continue;
+        }
+
+        conn.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, column_type
+            ),
+            [],
+        )
+        .map_err(|e| format!("Failed to add {} column: {}", column, e))?;
+    }
+
+    Ok(())
+}
+
+fn migrate_action_items_table(conn: &Connection) -> Result<(), String> {
+    let column_names = table_columns(conn, "action_items")?;
+
+    let has_archived_column = column_names.iter().any(|c| c == "archived_at");
+    let has_position_column = column_names.iter().any(|c| c == "position");
+
+    if has_archived_column && has_position_column {
+        return Ok(());
+    }
+
+    conn.execute("ALTER TABLE action_items RENAME TO action_items_old", [])
+        .map_err(|e| format!("Failed to rename action_items table: {}", e))?;
+
+    conn.execute(
+        "CREATE TABLE action_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            area_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            archived_at INTEGER,
+            FOREIGN KEY(area_id) REFERENCES life_areas(id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to recreate action_items table: {}", e))?;
+
+    let select_sql = match (has_position_column, has_archived_column) {
+        (true, true) => {
+            "SELECT id, area_id, title, created_at, position, archived_at FROM action_items_old ORDER BY position, created_at"
+        }
+        (true, false) => {
+            "SELECT id, area_id, title, created_at, position, NULL as archived_at FROM action_items_old ORDER BY position, created_at"
+        }
+        (false, true) => {
+            "SELECT id, area_id, title, created_at, NULL as position, archived_at FROM action_items_old ORDER BY created_at"
+        }
+        (false, false) => {
+            "SELECT id, area_id, title, created_at, NULL as position, NULL as archived_at FROM action_items_old ORDER BY created_at"
+        }
+    };
+
+    let mut select_stmt = conn
+        .prepare(select_sql)
+        .map_err(|e| format!("Failed to prepare migration select: {}", e))?;
+    let select_rows = select_stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, Option<i64>>(4)?,
+                row.get::<_, Option<i64>>(5)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to query migration data: {}", e))?;
+
+    let mut next_position: i64 = 0;
+    for row in select_rows {
+        let (id, area_id, title, created_at, position_opt, archived_at) =
+            row.map_err(|e| format!("Failed to parse migration row: {}", e))?;
+        let position = if let Some(pos) = position_opt {
+            if pos >= next_position {
+                next_position = pos + 1;
+            }
+            pos
+        } else {
+            let assign = next_position;
+            next_position += 1;
+            assign
+        };
+
+        conn.execute(
+            "INSERT INTO action_items (id, area_id, title, created_at, position, archived_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![id, area_id, title, created_at, position, archived_at],
+        )
+        .map_err(|e| format!("Failed to insert migrated action item: {}", e))?;
+    }
+
+    conn.execute("DROP TABLE action_items_old", [])
+        .map_err(|e| format!("Failed to drop old action_items table: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use crate::database::get_db_path;
+use rusqlite::{params, Connection, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Environment variable holding the passphrase of an encrypted database.
+pub const DB_PASSPHRASE_ENV: &str = "RODA_DB_PASSPHRASE";
+
+/// First bytes of every unencrypted SQLite database. SQLCipher encrypts the
+/// whole file, header included.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+/// Suffix of the copy written while encrypting or decrypting.
+const TEMP_SUFFIX: &str = ".rekey";
+
+static PASSPHRASE: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DatabaseEncryption {
+    pub path: PathBuf,
+    pub encrypted: bool,
+    /// Whether the database can be opened: it is not encrypted, or the
+    /// passphrase given opens it.
+    pub unlocked: bool,
+}
+
+/// Uses `passphrase` to open encrypted databases for the rest of the process,
+/// ahead of `RODA_DB_PASSPHRASE`. `None` forgets it.
+pub fn set_db_passphrase(passphrase: Option<String>) {
+    *PASSPHRASE.lock().unwrap_or_else(|e| e.into_inner()) = passphrase;
+}
+
+pub(crate) fn passphrase() -> Option<String> {
+    PASSPHRASE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .or_else(|| std::env::var(DB_PASSPHRASE_ENV).ok())
+        .filter(|passphrase| !passphrase.is_empty())
+}
+
+/// Whether a passphrase has been given, by `set_db_passphrase` or the
+/// environment.
+pub fn has_db_passphrase() -> bool {
+    passphrase().is_some()
+}
+
+/// Whether the file at `path` is an encrypted database. Missing and empty files
+/// are not, since new databases start unencrypted.
+pub fn is_database_encrypted(path: &Path) -> Result<bool, String> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+    file.by_ref()
+        .take(SQLITE_HEADER.len() as u64)
+        .read_to_end(&mut header)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(!header.is_empty() && header != SQLITE_HEADER)
+}
+
+/// Whether the current database is encrypted and can be opened.
+pub fn get_database_encryption() -> Result<DatabaseEncryption, String> {
+    let path = get_db_path()?;
+    let encrypted = is_database_encrypted(&path)?;
+    let unlocked = !encrypted || connect(&path).is_ok();
+    Ok(DatabaseEncryption {
+        path,
+        encrypted,
+        unlocked,
+    })
+}
+
+/// Opens the database at `db_path`, unlocking it with the passphrase if it is
+/// encrypted. Fails with a clear message when the passphrase is missing or
+/// wrong.
+pub(crate) fn connect(db_path: &Path) -> Result<Connection, String> {
+    connect_with(db_path, passphrase().as_deref())
+}
+
+/// Like `connect`, with the passphrase given rather than the process one.
+pub(crate) fn connect_with(db_path: &Path, passphrase: Option<&str>) -> Result<Connection, String> {
+    let encrypted = is_database_encrypted(db_path)?;
+    let conn = Connection::open(db_path).map_err(|e| format!("Failed to open database: {}", e))?;
+    if !encrypted {
+        return Ok(conn);
+    }
+
+    let Some(passphrase) = passphrase.filter(|passphrase| !passphrase.is_empty()) else {
+        return Err(format!(
+            "The database at {} is encrypted; a passphrase is needed to open it",
+            db_path.display()
+        ));
+    };
+    conn.pragma_update(None, "key", passphrase)
+        .map_err(|e| format!("Failed to unlock database: {}", e))?;
+    check_key(&conn)?;
+
+    Ok(conn)
+}
+
+/// SQLCipher only notices a wrong key when the first page is read.
+fn check_key(conn: &Connection) -> Result<(), String> {
+    match conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    }) {
+        Ok(_) => Ok(()),
+        Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == ErrorCode::NotADatabase => {
+            Err("Wrong passphrase for the encrypted database".to_string())
+        }
+        Err(e) => Err(format!("Failed to unlock database: {}", e)),
+    }
+}
+
+/// Encrypts the current database with `passphrase`, which is then used for
+/// the rest of the process.
+pub fn encrypt_database(passphrase: &str) -> Result<(), String> {
+    encrypt_file(&get_db_path()?, passphrase)?;
+    set_db_passphrase(Some(passphrase.to_string()));
+    Ok(())
+}
+
+fn encrypt_file(path: &Path, passphrase: &str) -> Result<(), String> {
+    validate_passphrase(passphrase)?;
+    if !path.exists() {
+        return Err("There is no database to encrypt yet".to_string());
+    }
+    if is_database_encrypted(path)? {
+        return Err("The database is already encrypted".to_string());
+    }
+
+    replace_with_export(path, None, passphrase)
+}
+
+/// Stores the current database unencrypted again. It must be unlocked.
+pub fn decrypt_database() -> Result<(), String> {
+    decrypt_file(&get_db_path()?, passphrase().as_deref())?;
+    set_db_passphrase(None);
+    Ok(())
+}
+
+fn decrypt_file(path: &Path, passphrase: Option<&str>) -> Result<(), String> {
+    if !is_database_encrypted(path)? {
+        return Err("The database is not encrypted".to_string());
+    }
+
+    replace_with_export(path, passphrase, "")
+}
+
+/// Re-encrypts the current database, which must be unlocked, with a new
+/// passphrase.
+pub fn change_db_passphrase(new_passphrase: &str) -> Result<(), String> {
+    rekey_file(&get_db_path()?, passphrase().as_deref(), new_passphrase)?;
+    set_db_passphrase(Some(new_passphrase.to_string()));
+    Ok(())
+}
+
+fn rekey_file(path: &Path, passphrase: Option<&str>, new_passphrase: &str) -> Result<(), String> {
+    validate_passphrase(new_passphrase)?;
+    if !is_database_encrypted(path)? {
+        return Err("The database is not encrypted".to_string());
+    }
+
+    let conn = connect_with(path, passphrase)?;
+    conn.pragma_update(None, "rekey", new_passphrase)
+        .map_err(|e| format!("Failed to change passphrase: {}", e))?;
+    drop(conn);
+
+    connect_with(path, Some(new_passphrase)).map(|_| ())
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.is_empty() {
+        return Err("Passphrase cannot be empty".to_string());
+    }
+    Ok(())
+}
+
+/// Writes a copy of the database at `path`, opened with `passphrase`,
+/// encrypted with `key`, or unencrypted if it is empty, checks it and moves
+/// it over the original. SQLCipher cannot change whether a database is
+/// encrypted in place.
+fn replace_with_export(path: &Path, passphrase: Option<&str>, key: &str) -> Result<(), String> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(TEMP_SUFFIX);
+    let temp = PathBuf::from(temp);
+    if temp.exists() {
+        std::fs::remove_file(&temp)
+            .map_err(|e| format!("Failed to remove {}: {}", temp.display(), e))?;
+    }
+
+    let conn = connect_with(path, passphrase)?;
+    let export = || -> Result<(), String> {
+        conn.execute(
+            "ATTACH DATABASE ?1 AS export KEY ?2",
+            params![temp.to_string_lossy(), key],
+        )
+        .map_err(|e| format!("Failed to create database copy: {}", e))?;
+        let exported = conn
+            .query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()))
+            .map_err(|e| format!("Failed to copy database: {}", e));
+        conn.execute("DETACH DATABASE export", [])
+            .map_err(|e| format!("Failed to close database copy: {}", e))?;
+        exported
+    };
+    let exported = export();
+    drop(conn);
+    if let Err(e) = exported.and_then(|()| check_copy(&temp, key)) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    std::fs::rename(&temp, path).map_err(|e| format!("Failed to replace database: {}", e))
+}
+
+fn check_copy(path: &Path, key: &str) -> Result<(), String> {
+    let copy =
+        Connection::open(path).map_err(|e| format!("Failed to open database copy: {}", e))?;
+    if !key.is_empty() {
+        copy.pragma_update(None, "key", key)
+            .map_err(|e| format!("Failed to unlock database copy: {}", e))?;
+    }
+    let integrity: String = copy
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to check database copy: {}", e))?;
+    if integrity != "ok" {
+        return Err(format!("Database copy is corrupt: {}", integrity));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::open_database;
+
+    fn database_with_area(dir: &Path) -> PathBuf {
+        let path = dir.join("data.db");
+        let conn = open_database(&path).unwrap();
+        conn.execute(
+            "INSERT INTO life_areas (name, color, \"order\", created_at, updated_at)
+             VALUES ('Health', '#fff', 0, 0, 0)",
+            [],
+        )
+        .unwrap();
+        path
+    }
+
+    fn area_names(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn.prepare("SELECT name FROM life_areas").unwrap();
+        let names = stmt.query_map([], |row| row.get(0)).unwrap();
+        names.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn encrypted_database_reopens_with_its_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = database_with_area(dir.path());
+
+        encrypt_file(&path, "correct horse").unwrap();
+
+        assert!(is_database_encrypted(&path).unwrap());
+        let conn = connect_with(&path, Some("correct horse")).unwrap();
+        assert_eq!(area_names(&conn), ["Health"]);
+    }
+
+    #[test]
+    fn wrong_or_missing_passphrase_is_reported_clearly() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = database_with_area(dir.path());
+        encrypt_file(&path, "correct horse").unwrap();
+
+        let wrong = connect_with(&path, Some("battery staple")).unwrap_err();
+        assert_eq!(wrong, "Wrong passphrase for the encrypted database");
+        let missing = connect_with(&path, None).unwrap_err();
+        assert!(missing.contains("a passphrase is needed"), "{}", missing);
+    }
+
+    #[test]
+    fn rekeyed_database_opens_only_with_the_new_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = database_with_area(dir.path());
+        encrypt_file(&path, "correct horse").unwrap();
+
+        rekey_file(&path, Some("correct horse"), "battery staple").unwrap();
+
+        assert!(connect_with(&path, Some("correct horse")).is_err());
+        let conn = connect_with(&path, Some("battery staple")).unwrap();
+        assert_eq!(area_names(&conn), ["Health"]);
+    }
+
+    #[test]
+    fn decrypted_database_opens_without_a_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = database_with_area(dir.path());
+        encrypt_file(&path, "correct horse").unwrap();
+
+        assert!(decrypt_file(&path, Some("battery staple")).is_err());
+        decrypt_file(&path, Some("correct horse")).unwrap();
+
+        assert!(!is_database_encrypted(&path).unwrap());
+        let conn = connect_with(&path, None).unwrap();
+        assert_eq!(area_names(&conn), ["Health"]);
+    }
+}
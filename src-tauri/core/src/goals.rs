@@ -0,0 +1,168 @@
+use serde::Serialize;
+
+pub const STATUS_OPEN: &str = "open";
+pub const STATUS_ACHIEVED: &str = "achieved";
+pub const STATUS_ABANDONED: &str = "abandoned";
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GoalProgress {
+    pub goal_id: i64,
+    pub target_score: i64,
+    pub target_date: i64,
+    /// Latest score recorded when the goal was created, or the first one after.
+    pub baseline_score: Option<i64>,
+    pub current_score: Option<i64>,
+    /// Fraction of the distance from baseline to target covered so far (0-1).
+    pub progress: f64,
+    pub reached: bool,
+    /// Least-squares slope of the recent scores, in points per day.
+    pub trend_per_day: Option<f64>,
+    /// When the target is reached if the recent trend continues.
+    pub projected_date: Option<i64>,
+    pub on_track: bool,
+}
+
+pub fn parse_close_status(status: &str) -> Result<&'static str, String> {
+    match status.trim().to_lowercase().as_str() {
+        STATUS_ACHIEVED => Ok(STATUS_ACHIEVED),
+        STATUS_ABANDONED => Ok(STATUS_ABANDONED),
+        _ => Err(format!(
+            "Status must be \"{}\" or \"{}\"",
+            STATUS_ACHIEVED, STATUS_ABANDONED
+        )),
+    }
+}
+
+/// Least-squares slope of `(timestamp, value)` points, in value units per day.
+pub fn trend_per_day(points: &[(i64, i64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(t, _)| *t as f64).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, v)| *v as f64).sum::<f64>() / n;
+
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    for (t, v) in points {
+        let dx = *t as f64 - mean_x;
+        numerator += dx * (*v as f64 - mean_y);
+        denominator += dx * dx;
+    }
+
+    if denominator == 0.0 {
+        return None;
+    }
+
+    Some(numerator / denominator * SECONDS_PER_DAY)
+}
+
+pub fn compute_progress(
+    goal_id: i64,
+    target_score: i64,
+    target_date: i64,
+    baseline_score: Option<i64>,
+    current_score: Option<i64>,
+    recent_points: &[(i64, i64)],
+    now: i64,
+) -> GoalProgress {
+    let reached = current_score.is_some_and(|current| current >= target_score);
+
+    let progress = match (baseline_score, current_score) {
+        _ if reached => 1.0,
+        (Some(baseline), Some(current)) if target_score > baseline => {
+            ((current - baseline) as f64 / (target_score - baseline) as f64).clamp(0.0, 1.0)
+        }
+        _ => 0.0,
+    };
+
+    let trend = trend_per_day(recent_points);
+    let projected_date = match (current_score, trend) {
+        _ if reached => None,
+        (Some(current), Some(slope)) if slope > 0.0 => {
+            let days = (target_score - current) as f64 / slope;
+            Some(now + (days * SECONDS_PER_DAY).round() as i64)
+        }
+        _ => None,
+    };
+
+    let on_track = reached || projected_date.is_some_and(|date| date <= target_date);
+
+    GoalProgress {
+        goal_id,
+        target_score,
+        target_date,
+        baseline_score,
+        current_score,
+        progress,
+        reached,
+        trend_per_day: trend,
+        projected_date,
+        on_track,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    #[test]
+    fn trend_is_the_slope_in_points_per_day() {
+        let points = [(0, 4), (DAY, 5), (2 * DAY, 6)];
+        assert_eq!(trend_per_day(&points), Some(1.0));
+    }
+
+    #[test]
+    fn trend_needs_two_points_at_different_times() {
+        assert_eq!(trend_per_day(&[]), None);
+        assert_eq!(trend_per_day(&[(DAY, 5)]), None);
+        assert_eq!(trend_per_day(&[(DAY, 5), (DAY, 7)]), None);
+    }
+
+    #[test]
+    fn progress_covers_the_distance_from_baseline_to_target() {
+        let points = [(0, 4), (DAY, 5), (2 * DAY, 6)];
+        let progress = compute_progress(1, 8, 10 * DAY, Some(4), Some(6), &points, 2 * DAY);
+
+        assert_eq!(progress.progress, 0.5);
+        assert!(!progress.reached);
+        assert_eq!(progress.projected_date, Some(4 * DAY));
+        assert!(progress.on_track);
+    }
+
+    #[test]
+    fn falling_scores_are_neither_negative_progress_nor_on_track() {
+        let points = [(0, 5), (DAY, 3)];
+        let progress = compute_progress(1, 8, 10 * DAY, Some(5), Some(3), &points, DAY);
+
+        assert_eq!(progress.progress, 0.0);
+        assert_eq!(progress.projected_date, None);
+        assert!(!progress.on_track);
+    }
+
+    #[test]
+    fn target_equal_to_baseline_does_not_divide_by_zero() {
+        let progress = compute_progress(1, 6, 10 * DAY, Some(6), Some(5), &[], 0);
+        assert_eq!(progress.progress, 0.0);
+        assert!(!progress.reached);
+
+        let progress = compute_progress(1, 6, 10 * DAY, Some(6), Some(6), &[], 0);
+        assert_eq!(progress.progress, 1.0);
+        assert!(progress.reached && progress.on_track);
+    }
+
+    #[test]
+    fn scores_without_elapsed_time_give_no_projection() {
+        let points = [(DAY, 4), (DAY, 6)];
+        let progress = compute_progress(1, 8, 10 * DAY, Some(4), Some(6), &points, DAY);
+
+        assert_eq!(progress.trend_per_day, None);
+        assert_eq!(progress.projected_date, None);
+        assert!(!progress.on_track);
+    }
+}
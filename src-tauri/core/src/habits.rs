@@ -0,0 +1,272 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub const FREQUENCY_DAILY: &str = "daily";
+pub const FREQUENCY_WEEKLY: &str = "weekly";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HabitFrequency {
+    Daily,
+    Weekly,
+}
+
+impl HabitFrequency {
+    pub fn parse(frequency: &str) -> Result<Self, String> {
+        match frequency.trim().to_lowercase().as_str() {
+            FREQUENCY_DAILY => Ok(HabitFrequency::Daily),
+            FREQUENCY_WEEKLY => Ok(HabitFrequency::Weekly),
+            _ => Err(format!(
+                "Frequency must be \"{}\" or \"{}\"",
+                FREQUENCY_DAILY, FREQUENCY_WEEKLY
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HabitFrequency::Daily => FREQUENCY_DAILY,
+            HabitFrequency::Weekly => FREQUENCY_WEEKLY,
+        }
+    }
+
+    fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            HabitFrequency::Daily => date,
+            HabitFrequency::Weekly => week_start(date),
+        }
+    }
+
+    fn period_length(&self) -> Duration {
+        match self {
+            HabitFrequency::Daily => Duration::days(1),
+            HabitFrequency::Weekly => Duration::weeks(1),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WeeklyCompletion {
+    /// Monday of the week, as `YYYY-MM-DD` in local time.
+    pub week_start: String,
+    pub checkins: i64,
+    pub target: i64,
+    pub rate: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HabitStats {
+    pub habit_id: i64,
+    /// Consecutive periods (days or weeks) meeting the target, ending with the
+    /// current period or, if it is still in progress, the one before it.
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    pub total_checkins: i64,
+    pub weekly_completion: Vec<WeeklyCompletion>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HeatmapDay {
+    pub date: String,
+    pub count: i64,
+}
+
+pub fn local_date(timestamp: i64) -> Option<NaiveDate> {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.date_naive())
+}
+
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn counts_by_date(dates: &[NaiveDate]) -> BTreeMap<NaiveDate, i64> {
+    let mut counts = BTreeMap::new();
+    for date in dates {
+        *counts.entry(*date).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn counts_by_period(dates: &[NaiveDate], frequency: HabitFrequency) -> BTreeMap<NaiveDate, i64> {
+    let mut counts = BTreeMap::new();
+    for date in dates {
+        *counts.entry(frequency.period_start(*date)).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Returns `(current_streak, longest_streak)` in periods of the habit's frequency.
+pub fn compute_streaks(
+    dates: &[NaiveDate],
+    frequency: HabitFrequency,
+    target: i64,
+    today: NaiveDate,
+) -> (i64, i64) {
+    let met: Vec<NaiveDate> = counts_by_period(dates, frequency)
+        .into_iter()
+        .filter(|(_, count)| *count >= target)
+        .map(|(period, _)| period)
+        .collect();
+
+    let step = frequency.period_length();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for period in &met {
+        run = match previous {
+            Some(prev) if *period - prev == step => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*period);
+    }
+
+    let mut cursor = frequency.period_start(today);
+    if met.binary_search(&cursor).is_err() {
+        cursor -= step;
+    }
+    let mut current = 0;
+    while met.binary_search(&cursor).is_ok() {
+        current += 1;
+        cursor -= step;
+    }
+
+    (current, longest)
+}
+
+/// Completion for the `weeks` most recent weeks, oldest first. Daily habits
+/// count at most `target` check-ins per day towards the weekly total.
+pub fn weekly_completion(
+    dates: &[NaiveDate],
+    frequency: HabitFrequency,
+    target: i64,
+    weeks: i64,
+    today: NaiveDate,
+) -> Vec<WeeklyCompletion> {
+    let daily_counts = counts_by_date(dates);
+    let expected = match frequency {
+        HabitFrequency::Daily => target * 7,
+        HabitFrequency::Weekly => target,
+    };
+    let current_week = week_start(today);
+
+    (0..weeks)
+        .rev()
+        .map(|offset| {
+            let start = current_week - Duration::weeks(offset);
+            let end = start + Duration::weeks(1);
+            let checkins: i64 = daily_counts
+                .range(start..end)
+                .map(|(_, count)| match frequency {
+                    HabitFrequency::Daily => (*count).min(target),
+                    HabitFrequency::Weekly => *count,
+                })
+                .sum();
+            let rate = if expected > 0 {
+                (checkins as f64 / expected as f64).min(1.0)
+            } else {
+                0.0
+            };
+            WeeklyCompletion {
+                week_start: start.format("%Y-%m-%d").to_string(),
+                checkins,
+                target: expected,
+                rate,
+            }
+        })
+        .collect()
+}
+
+/// Check-in counts for every day in `from..=to`, including days without any.
+pub fn heatmap(dates: &[NaiveDate], from: NaiveDate, to: NaiveDate) -> Vec<HeatmapDay> {
+    let daily_counts = counts_by_date(dates);
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| HeatmapDay {
+            date: date.format("%Y-%m-%d").to_string(),
+            count: daily_counts.get(&date).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn dates(values: &[&str]) -> Vec<NaiveDate> {
+        values.iter().map(|value| date(value)).collect()
+    }
+
+    #[test]
+    fn daily_streak_continues_while_today_is_open() {
+        let checkins = dates(&["2024-03-07", "2024-03-08", "2024-03-09"]);
+        let streaks = compute_streaks(&checkins, HabitFrequency::Daily, 1, date("2024-03-10"));
+        assert_eq!(streaks, (3, 3));
+    }
+
+    #[test]
+    fn daily_streak_restarts_after_a_missed_day() {
+        let checkins = dates(&[
+            "2024-03-01",
+            "2024-03-02",
+            "2024-03-03",
+            "2024-03-05",
+            "2024-03-06",
+        ]);
+        let streaks = compute_streaks(&checkins, HabitFrequency::Daily, 1, date("2024-03-06"));
+        assert_eq!(streaks, (2, 3));
+
+        let streaks = compute_streaks(&checkins, HabitFrequency::Daily, 1, date("2024-03-08"));
+        assert_eq!(streaks, (0, 3));
+    }
+
+    #[test]
+    fn weekly_streak_needs_the_target_within_each_week() {
+        // Mondays 2024-03-04, 03-11 and 03-18
+        let checkins = dates(&[
+            "2024-03-04",
+            "2024-03-10",
+            "2024-03-12",
+            "2024-03-18",
+            "2024-03-19",
+        ]);
+        let streaks = compute_streaks(&checkins, HabitFrequency::Weekly, 2, date("2024-03-20"));
+        assert_eq!(streaks, (1, 1));
+
+        let streaks = compute_streaks(&checkins, HabitFrequency::Weekly, 1, date("2024-03-20"));
+        assert_eq!(streaks, (3, 3));
+    }
+
+    #[test]
+    fn weekly_completion_caps_daily_checkins_at_the_target() {
+        let checkins = dates(&["2024-03-05", "2024-03-05", "2024-03-05", "2024-03-06"]);
+        let weeks = weekly_completion(&checkins, HabitFrequency::Daily, 1, 2, date("2024-03-07"));
+
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].week_start, "2024-02-26");
+        assert_eq!(weeks[0].checkins, 0);
+        assert_eq!(weeks[1].week_start, "2024-03-04");
+        assert_eq!(weeks[1].checkins, 2);
+        assert_eq!(weeks[1].target, 7);
+    }
+
+    #[test]
+    fn heatmap_includes_days_without_checkins() {
+        let checkins = dates(&["2024-03-02", "2024-03-02"]);
+        let days = heatmap(&checkins, date("2024-03-01"), date("2024-03-03"));
+
+        let counts: Vec<(&str, i64)> = days.iter().map(|d| (d.date.as_str(), d.count)).collect();
+        assert_eq!(
+            counts,
+            [("2024-03-01", 0), ("2024-03-02", 2), ("2024-03-03", 0)]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use crate::models::ActionItem;
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use serde::Serialize;
+
+const PRODUCT_ID: &str = "-//Roda da Vida//Action items//EN";
+const UID_PREFIX: &str = "action-item-";
+const UID_DOMAIN: &str = "@roda-da-vida";
+/// Longest content line allowed by RFC 5545, in bytes, before folding.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// How action items appear in the calendar. Recurring items are written
+/// without an `RRULE`: every occurrence is its own action item, so the next
+/// one shows up in the calendar once the current one is done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcalComponent {
+    /// Tasks with due dates and completion status, archived items included.
+    Todo,
+    /// All-day events on the due date, or the created date without one, for
+    /// calendars that do not show tasks. Archived items are left out.
+    Event,
+}
+
+impl IcalComponent {
+    pub fn parse(component: &str) -> Result<Self, String> {
+        match component.trim().to_lowercase().as_str() {
+            "todo" | "vtodo" => Ok(IcalComponent::Todo),
+            "event" | "vevent" => Ok(IcalComponent::Event),
+            _ => Err("Component must be \"todo\" or \"event\"".to_string()),
+        }
+    }
+}
+
+/// What importing an `.ics` file changed.
+#[derive(Debug, Default, Serialize, Clone)]
+pub struct IcalImportSummary {
+    /// Open items archived because the calendar marks them completed.
+    pub completed: usize,
+    /// Archived items reopened because the calendar marks them as not done.
+    pub reopened: usize,
+    pub unchanged: usize,
+    /// Tasks that were not exported from this database or no longer exist.
+    pub skipped: usize,
+}
+
+/// Completion status of an exported task, as read back from a calendar.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TaskStatus {
+    pub action_item_id: Option<i64>,
+    pub completed: bool,
+}
+
+/// `items` pairs each action item with the name of its area.
+pub(crate) fn write_calendar(
+    items: &[(ActionItem, String)],
+    component: IcalComponent,
+    now: i64,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODUCT_ID),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for (item, area_name) in items {
+        match component {
+            IcalComponent::Todo => {
+                lines.push("BEGIN:VTODO".to_string());
+                push_common(&mut lines, item, area_name, now);
+                lines.push(format!("DTSTART:{}", utc_date_time(item.created_at)));
+                if let Some(due_at) = item.due_at {
+                    lines.push(format!("DUE:{}", utc_date_time(due_at)));
+                }
+                match item.archived_at {
+                    Some(archived_at) => {
+                        lines.push("STATUS:COMPLETED".to_string());
+                        lines.push(format!("COMPLETED:{}", utc_date_time(archived_at)));
+                        lines.push("PERCENT-COMPLETE:100".to_string());
+                    }
+                    None => lines.push("STATUS:NEEDS-ACTION".to_string()),
+                }
+                lines.push("END:VTODO".to_string());
+            }
+            IcalComponent::Event => {
+                if item.archived_at.is_some() {
+                    continue;
+                }
+                let Some(day) = Local
+                    .timestamp_opt(item.due_at.unwrap_or(item.created_at), 0)
+                    .single()
+                    .map(|dt| dt.date_naive())
+                else {
+                    continue;
+                };
+                lines.push("BEGIN:VEVENT".to_string());
+                push_common(&mut lines, item, area_name, now);
+                lines.push(format!("DTSTART;VALUE=DATE:{}", day.format("%Y%m%d")));
+                lines.push(format!(
+                    "DTEND;VALUE=DATE:{}",
+                    (day + Duration::days(1)).format("%Y%m%d")
+                ));
+                lines.push("TRANSP:TRANSPARENT".to_string());
+                lines.push("END:VEVENT".to_string());
+            }
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+    }
+    out
+}
+
+fn push_common(lines: &mut Vec<String>, item: &ActionItem, area_name: &str, now: i64) {
+    lines.push(format!("UID:{}{}{}", UID_PREFIX, item.id, UID_DOMAIN));
+    lines.push(format!("DTSTAMP:{}", utc_date_time(now)));
+    lines.push(format!("CREATED:{}", utc_date_time(item.created_at)));
+    lines.push(format!("SUMMARY:{}", escape_text(&item.title)));
+    if let Some(description) = &item.description {
+        lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    lines.push(format!("CATEGORIES:{}", escape_text(area_name)));
+}
+
+/// The status of every `VTODO` in `contents`. Tasks whose UID was not written
+/// by `write_calendar` have no action item id.
+pub(crate) fn read_task_statuses(contents: &str) -> Result<Vec<TaskStatus>, String> {
+    if !contents.trim_start().starts_with("BEGIN:VCALENDAR") {
+        return Err("Not an iCalendar file".to_string());
+    }
+
+    let mut statuses = Vec::new();
+    let mut current: Option<(Option<i64>, Option<String>, bool)> = None;
+
+    for line in unfold(contents) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Drop parameters such as `;TZID=...`
+        let name = name.split(';').next().unwrap_or(name).to_uppercase();
+
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VTODO") => {
+                current = Some((None, None, false));
+            }
+            ("END", Some((id, status, has_completed))) if value.eq_ignore_ascii_case("VTODO") => {
+                let completed = match status.as_deref() {
+                    Some(status) => status.eq_ignore_ascii_case("COMPLETED"),
+                    None => *has_completed,
+                };
+                statuses.push(TaskStatus {
+                    action_item_id: *id,
+                    completed,
+                });
+                current = None;
+            }
+            ("UID", Some((id, _, _))) => *id = parse_uid(value),
+            ("STATUS", Some((_, status, _))) => *status = Some(value.trim().to_string()),
+            ("COMPLETED", Some((_, _, has_completed))) => *has_completed = true,
+            _ => {}
+        }
+    }
+
+    Ok(statuses)
+}
+
+fn parse_uid(uid: &str) -> Option<i64> {
+    uid.trim()
+        .strip_prefix(UID_PREFIX)?
+        .strip_suffix(UID_DOMAIN)?
+        .parse()
+        .ok()
+}
+
+fn utc_date_time(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .unwrap_or(DateTime::UNIX_EPOCH)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Splits a content line into lines of at most 75 bytes, each continuation
+/// starting with a space, without breaking UTF-8 characters.
+fn fold_line(line: &str) -> String {
+    let mut out = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            out.push_str("\r\n ");
+            length = 1;
+        }
+        out.push(c);
+        length += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// Joins folded lines back together.
+fn unfold(contents: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: i64, title: &str, archived_at: Option<i64>) -> ActionItem {
+        ActionItem {
+            id,
+            area_id: 1,
+            title: title.to_string(),
+            created_at: 1_704_067_200,
+            position: 0,
+            archived_at,
+            description: None,
+            due_at: Some(1_704_153_600),
+            recurrence: None,
+        }
+    }
+
+    #[test]
+    fn exported_tasks_are_read_back_with_their_status() {
+        let items = vec![
+            (item(3, "Run a 10k", None), "Health".to_string()),
+            (
+                item(
+                    4,
+                    "Update résumé, cover letter; portfolio",
+                    Some(1_704_100_000),
+                ),
+                "Career".to_string(),
+            ),
+        ];
+        let calendar = write_calendar(&items, IcalComponent::Todo, 1_704_200_000);
+
+        assert!(calendar
+            .lines()
+            .all(|line| line.len() <= MAX_LINE_LENGTH + 1));
+        let statuses = read_task_statuses(&calendar).unwrap();
+        let statuses: Vec<(Option<i64>, bool)> = statuses
+            .iter()
+            .map(|status| (status.action_item_id, status.completed))
+            .collect();
+        assert_eq!(statuses, [(Some(3), false), (Some(4), true)]);
+    }
+
+    #[test]
+    fn recurring_items_are_exported_as_single_occurrences() {
+        let mut open = item(5, "Weekly review", None);
+        open.recurrence = Some("FREQ=WEEKLY".to_string());
+        let mut done = item(4, "Weekly review", Some(1_704_100_000));
+        done.recurrence = open.recurrence.clone();
+        let items = vec![(done, "Career".to_string()), (open, "Career".to_string())];
+
+        for component in [IcalComponent::Todo, IcalComponent::Event] {
+            let calendar = write_calendar(&items, component, 1_704_200_000);
+            assert!(!calendar.contains("RRULE"), "{}", calendar);
+        }
+    }
+
+    #[test]
+    fn reads_folded_lines_and_completion_without_status() {
+        let contents = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VTODO\r\n\
+            UID:action-item-\r\n 12@roda-da-vida\r\n\
+            COMPLETED:20240105T100000Z\r\n\
+            END:VTODO\r\n\
+            BEGIN:VTODO\r\n\
+            UID:someone-else@example.com\r\n\
+            STATUS:NEEDS-ACTION\r\n\
+            END:VTODO\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:action-item-13@roda-da-vida\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let statuses = read_task_statuses(contents).unwrap();
+
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].action_item_id, Some(12));
+        assert!(statuses[0].completed);
+        assert_eq!(statuses[1].action_item_id, None);
+        assert!(!statuses[1].completed);
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_calendars() {
+        assert!(read_task_statuses("Subject,Done\n").is_err());
+    }
+
+    #[test]
+    fn folding_keeps_multibyte_characters_whole() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold_line(&line);
+
+        assert!(folded
+            .split("\r\n")
+            .all(|part| part.len() <= MAX_LINE_LENGTH));
+        assert_eq!(unfold(&folded), [line]);
+    }
+}
@@ -0,0 +1,62 @@
+//! Domain logic and persistence for Roda da Vida, independent of any UI.
+
+mod aggregation;
+mod audit;
+mod csv_import;
+mod database;
+mod encryption;
+mod goals;
+mod habits;
+mod ical;
+mod location;
+pub mod models;
+mod pdf;
+mod profiles;
+mod recurrence;
+mod render;
+mod report;
+mod store;
+mod tasks;
+mod team;
+mod templates;
+mod trash;
+mod undo;
+mod vault;
+
+pub use aggregation::{format_local_date, local_midnight, AreaScoreBuckets, ScoreBucket};
+pub use csv_import::{
+    CsvColumnMapping, CsvImportKind, CsvImportPreview, CsvImportRow, CsvRowError,
+};
+pub use database::{get_db_path, init_database};
+pub use encryption::{
+    change_db_passphrase, decrypt_database, encrypt_database, get_database_encryption,
+    has_db_passphrase, is_database_encrypted, set_db_passphrase, DatabaseEncryption,
+    DB_PASSPHRASE_ENV,
+};
+pub use goals::GoalProgress;
+pub use habits::{HabitStats, HeatmapDay, WeeklyCompletion};
+pub use ical::{IcalComponent, IcalImportSummary};
+pub use location::{
+    move_database, resolve as resolve_db_location, set_db_path_override, DatabaseLocation,
+    LocationSource, DB_PATH_ENV, PORTABLE_MARKER,
+};
+pub use pdf::{PrintableArea, PrintableWheel};
+pub use profiles::{
+    create_profile, delete_profile, list_profiles, rename_profile, switch_profile, Profile,
+    DEFAULT_PROFILE_NAME,
+};
+pub use render::{
+    save_wheel_image, wheel_png, wheel_svg, ImageFormat, WheelImageOptions, WheelTheme,
+};
+pub use report::{AreaReview, ReportFormat, ReviewReport};
+pub use store::{Store, EXPORT_VERSION};
+pub use tasks::{SkippedTask, TaskImportSummary, TaskListFormat};
+pub use team::{
+    build_team_wheel, load_export, load_team_wheel, ContributorScore, TeamAreaStats,
+    TeamContributor, TeamWheel,
+};
+pub use templates::{
+    delete_template, get_template, list_templates, save_template, TemplateArea, TemplateList,
+    WheelTemplate,
+};
+pub use vault::{MarkdownVault, VaultActionItem, VaultArea};
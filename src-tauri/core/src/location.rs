@@ -0,0 +1,289 @@
+use crate::encryption::{connect_with, is_database_encrypted, passphrase};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable pointing at the database file.
+pub const DB_PATH_ENV: &str = "RODA_DB_PATH";
+/// File next to the executable that switches on portable mode.
+pub const PORTABLE_MARKER: &str = "portable";
+
+const APP_DIR: &str = ".roda-da-vida";
+const PORTABLE_DATA_DIR: &str = "data";
+pub(crate) const DB_FILE_NAME: &str = "data.db";
+const CONFIG_FILE_NAME: &str = "config.json";
+
+static PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Where the database path came from, highest precedence first.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationSource {
+    Flag,
+    Environment,
+    Portable,
+    Config,
+    Default,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DatabaseLocation {
+    pub path: PathBuf,
+    pub source: LocationSource,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<ProfileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ProfileEntry {
+    pub name: String,
+    /// Relative paths are resolved against the data directory.
+    pub path: PathBuf,
+}
+
+impl Config {
+    /// The database of the active profile, falling back to `database_path`.
+    pub(crate) fn active_path(&self) -> Option<&Path> {
+        self.active_profile
+            .as_ref()
+            .and_then(|name| self.profiles.iter().find(|p| &p.name == name))
+            .map(|profile| profile.path.as_path())
+            .or(self.database_path.as_deref())
+    }
+
+    fn set_active_path(&mut self, path: PathBuf) {
+        let active = self.active_profile.clone();
+        match self
+            .profiles
+            .iter_mut()
+            .find(|p| Some(&p.name) == active.as_ref())
+        {
+            Some(profile) => profile.path = path,
+            None => self.database_path = Some(path),
+        }
+    }
+}
+
+/// Uses `path` for the rest of the process, ahead of every other setting.
+/// Meant for a `--db` command-line flag; only the first call has an effect.
+pub fn set_db_path_override(path: PathBuf) {
+    let _ = PATH_OVERRIDE.set(path);
+}
+
+/// Resolves the database path from, in order: the command-line override,
+/// `RODA_DB_PATH`, the active profile or `database_path` in the config file,
+/// and `data.db` in the data directory.
+pub fn resolve() -> Result<DatabaseLocation, String> {
+    if let Some(path) = PATH_OVERRIDE.get() {
+        return Ok(DatabaseLocation {
+            path: path.clone(),
+            source: LocationSource::Flag,
+        });
+    }
+
+    if let Some(path) = std::env::var_os(DB_PATH_ENV).filter(|path| !path.is_empty()) {
+        return Ok(DatabaseLocation {
+            path: PathBuf::from(path),
+            source: LocationSource::Environment,
+        });
+    }
+
+    let (data_dir, portable) = data_dir()?;
+    if let Some(path) = read_config()?.active_path() {
+        return Ok(DatabaseLocation {
+            path: data_dir.join(path),
+            source: LocationSource::Config,
+        });
+    }
+
+    Ok(DatabaseLocation {
+        path: data_dir.join(DB_FILE_NAME),
+        source: if portable {
+            LocationSource::Portable
+        } else {
+            LocationSource::Default
+        },
+    })
+}
+
+/// The directory of the executable if it contains the portable marker file.
+fn portable_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    dir.join(PORTABLE_MARKER)
+        .is_file()
+        .then(|| dir.to_path_buf())
+}
+
+/// Where the config file and new databases go: `data` next to the executable
+/// in portable mode, `~/.roda-da-vida` otherwise. The flag is set in portable mode.
+pub(crate) fn data_dir() -> Result<(PathBuf, bool), String> {
+    if let Some(dir) = portable_dir() {
+        return Ok((dir.join(PORTABLE_DATA_DIR), true));
+    }
+
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok((home.join(APP_DIR), false))
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.0.join(CONFIG_FILE_NAME))
+}
+
+pub(crate) fn read_config() -> Result<Config, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+pub(crate) fn write_config(config: &Config) -> Result<(), String> {
+    let path = config_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    std::fs::write(&path, contents + "\n")
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Copies the database to `target` (a file, or a directory to put `data.db`
+/// in), checks the copy, points the config file (or the active profile) at it
+/// and only then removes the original. Refused when the location comes from a
+/// flag or `RODA_DB_PATH`, since the config file would not take effect.
+pub fn move_database(target: &Path) -> Result<DatabaseLocation, String> {
+    let current = resolve()?;
+    if !current.path.exists() {
+        return Err(format!("No database at {}", current.path.display()));
+    }
+    match current.source {
+        LocationSource::Config | LocationSource::Default | LocationSource::Portable => {}
+        LocationSource::Flag => {
+            return Err("The database location is set by a command-line flag".to_string())
+        }
+        LocationSource::Environment => {
+            return Err(format!("The database location is set by {}", DB_PATH_ENV))
+        }
+    }
+    let target = if target.is_dir() {
+        target.join(DB_FILE_NAME)
+    } else {
+        target.to_path_buf()
+    };
+
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let target = std::path::absolute(&target)
+        .map_err(|e| format!("Failed to resolve {}: {}", target.display(), e))?;
+
+    copy_database(&current.path, &target, passphrase().as_deref())?;
+
+    let mut config = read_config()?;
+    config.set_active_path(target.clone());
+    write_config(&config)?;
+
+    remove_database_files(&current.path)?;
+
+    Ok(DatabaseLocation {
+        path: target,
+        source: LocationSource::Config,
+    })
+}
+
+/// Writes a consistent copy of the database at `source`, opened with
+/// `passphrase`, to `target`, even if another process has it open, and checks
+/// that the copy opens with the same passphrase and is intact. The copy is
+/// removed if it is not.
+fn copy_database(source: &Path, target: &Path, passphrase: Option<&str>) -> Result<(), String> {
+    let conn = connect_with(source, passphrase)?;
+    conn.execute("VACUUM INTO ?", [target.to_string_lossy()])
+        .map_err(|e| format!("Failed to copy database: {}", e))?;
+    drop(conn);
+
+    let checked = check_copy(source, target, passphrase);
+    if checked.is_err() {
+        let _ = remove_database_files(target);
+    }
+    checked
+}
+
+fn check_copy(source: &Path, target: &Path, passphrase: Option<&str>) -> Result<(), String> {
+    if is_database_encrypted(target)? != is_database_encrypted(source)? {
+        return Err("Copied database is not encrypted like the original".to_string());
+    }
+
+    let copy = connect_with(target, passphrase)?;
+    let integrity: String = copy
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to check copied database: {}", e))?;
+    if integrity != "ok" {
+        return Err(format!("Copied database is corrupt: {}", integrity));
+    }
+
+    Ok(())
+}
+
+/// Removes a database file together with its WAL and shared-memory files.
+pub(crate) fn remove_database_files(path: &Path) -> Result<(), String> {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut file = path.as_os_str().to_owned();
+        file.push(suffix);
+        let file = PathBuf::from(file);
+        if file.exists() {
+            std::fs::remove_file(&file)
+                .map_err(|e| format!("Failed to remove {}: {}", file.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    #[test]
+    fn copy_of_an_encrypted_database_keeps_its_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("data.db");
+        let target = dir.path().join("moved.db");
+
+        let conn = Connection::open(&source).unwrap();
+        conn.pragma_update(None, "key", "correct horse").unwrap();
+        conn.execute_batch("CREATE TABLE notes (body TEXT); INSERT INTO notes VALUES ('hi');")
+            .unwrap();
+        drop(conn);
+
+        copy_database(&source, &target, Some("correct horse")).unwrap();
+
+        assert!(is_database_encrypted(&target).unwrap());
+        let copy = Connection::open(&target).unwrap();
+        copy.pragma_update(None, "key", "correct horse").unwrap();
+        let body: String = copy
+            .query_row("SELECT body FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(body, "hi");
+    }
+}
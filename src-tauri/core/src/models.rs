@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LifeArea {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: String,
+    pub order: i64,
+    pub is_active: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Score {
+    pub id: i64,
+    pub area_id: i64,
+    pub value: i64,
+    pub recorded_at: i64,
+    pub note: Option<String>,
+}
+
+/// Position after the last score of a page, in the page's sort order.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct ScoreCursor {
+    pub recorded_at: i64,
+    pub id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScorePage {
+    pub scores: Vec<Score>,
+    /// Pass back to fetch the next page; `None` on the last page.
+    pub next_cursor: Option<ScoreCursor>,
+}
+
+/// An area as it was at some point in time, with its latest score back then.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WheelEntry {
+    pub area: LifeArea,
+    pub score: Option<Score>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActionItem {
+    pub id: i64,
+    pub area_id: i64,
+    pub title: String,
+    pub created_at: i64,
+    pub position: i64,
+    pub archived_at: Option<i64>,
+    pub description: Option<String>,
+    pub due_at: Option<i64>,
+    pub recurrence: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActionItemLink {
+    pub id: i64,
+    pub action_item_id: i64,
+    pub url: String,
+    pub label: Option<String>,
+    pub position: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActionItemStep {
+    pub id: i64,
+    pub action_item_id: i64,
+    pub title: String,
+    pub is_done: bool,
+    /// Position among the steps with the same parent.
+    pub position: i64,
+    pub created_at: i64,
+    /// The step this one is nested under, or `None` at the top level.
+    #[serde(default)]
+    pub parent_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Habit {
+    pub id: i64,
+    pub area_id: i64,
+    pub name: String,
+    pub frequency: String,
+    pub target_count: i64,
+    pub created_at: i64,
+    pub archived_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HabitCheckin {
+    pub id: i64,
+    pub habit_id: i64,
+    pub checked_at: i64,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Goal {
+    pub id: i64,
+    pub area_id: i64,
+    pub target_score: i64,
+    pub target_date: i64,
+    pub rationale: Option<String>,
+    pub status: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub closed_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChangeLogEntry {
+    pub id: i64,
+    pub operation_id: i64,
+    pub operation: String,
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub action: String,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    pub changed_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UndoableOperation {
+    pub id: i64,
+    pub name: String,
+    pub created_at: i64,
+    pub undone_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UndoStatus {
+    pub undo: Option<UndoableOperation>,
+    pub redo: Option<UndoableOperation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoredChange {
+    pub entity_type: String,
+    pub entity_id: i64,
+    /// What undo or redo did to the row: `create`, `update` or `delete`.
+    pub action: String,
+    /// The row as it is now, or `None` if it was removed.
+    pub value: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UndoResult {
+    pub operation: UndoableOperation,
+    pub changes: Vec<RestoredChange>,
+}
+
+/// Rows moved to the trash by one command, restored or purged together.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashBatch {
+    pub id: i64,
+    pub operation: String,
+    pub deleted_at: i64,
+    /// When the batch is purged at startup, or `None` if it never expires.
+    pub purge_at: Option<i64>,
+    /// Number of rows per table.
+    pub counts: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashEntry {
+    pub id: i64,
+    pub batch_id: i64,
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub data: Value,
+    pub deleted_at: i64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TagFilter {
+    /// Items must carry every one of these tags.
+    pub all_of: Vec<i64>,
+    /// Items must carry at least one of these tags.
+    pub any_of: Vec<i64>,
+    /// Items must carry none of these tags.
+    pub none_of: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReorderUpdate {
+    pub id: i64,
+    pub position: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActionItemTag {
+    pub action_item_id: i64,
+    pub tag_id: i64,
+}
+
+/// Every row of the wheel, archived and closed ones included.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WheelExport {
+    pub version: i64,
+    pub exported_at: i64,
+    pub life_areas: Vec<LifeArea>,
+    pub scores: Vec<Score>,
+    pub action_items: Vec<ActionItem>,
+    pub action_item_links: Vec<ActionItemLink>,
+    pub action_item_steps: Vec<ActionItemStep>,
+    pub tags: Vec<Tag>,
+    pub action_item_tags: Vec<ActionItemTag>,
+    pub habits: Vec<Habit>,
+    pub habit_checkins: Vec<HabitCheckin>,
+    pub goals: Vec<Goal>,
+}
@@ -0,0 +1,600 @@
+use crate::aggregation::format_local_date;
+use crate::models::{ActionItem, LifeArea, Score};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use serde::Serialize;
+use std::f32::consts::{FRAC_PI_2, TAU};
+use std::path::Path;
+
+// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+
+const WHEEL_RADIUS: f32 = 150.0;
+const CHART_HEIGHT: f32 = 140.0;
+const CHART_GAP: f32 = 20.0;
+const LINE_HEIGHT: f32 = 13.0;
+
+const GRID_GRAY: f32 = 0.85;
+const MUTED_GRAY: f32 = 0.45;
+
+/// What the printable PDF shows, gathered by `Store::get_printable_wheel`.
+#[derive(Debug, Serialize, Clone)]
+pub struct PrintableWheel {
+    pub generated_at: i64,
+    /// Start of the score history drawn in the charts.
+    pub history_start: i64,
+    /// Active areas in wheel order.
+    pub areas: Vec<PrintableArea>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PrintableArea {
+    pub area: LifeArea,
+    pub score: Option<Score>,
+    /// Scores since `history_start`, oldest first.
+    pub history: Vec<Score>,
+    /// Open action items in list order.
+    pub action_items: Vec<ActionItem>,
+    /// The most recent scores with a note, newest first.
+    pub notes: Vec<Score>,
+}
+
+impl PrintableWheel {
+    /// An A4 document: the wheel with a score table, a chart of each area's
+    /// scores, and the open action items with checkboxes and recent notes.
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut pages = Pages::new();
+        self.write_wheel(&mut pages);
+        pages.new_page();
+        self.write_history(&mut pages);
+        pages.new_page();
+        self.write_action_plan(&mut pages);
+
+        let catalog_id = Ref::new(1);
+        let page_tree_id = Ref::new(2);
+        let regular_id = Ref::new(3);
+        let bold_id = Ref::new(4);
+        let info_id = Ref::new(5);
+        // Each page is followed by its content stream
+        let page_ids: Vec<Ref> = (0..pages.pages.len())
+            .map(|i| Ref::new(6 + 2 * i as i32))
+            .collect();
+
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.document_info(info_id)
+            .title(TextStr("Wheel of Life"))
+            .creator(TextStr("Roda da Vida"));
+        pdf.pages(page_tree_id)
+            .kids(page_ids.iter().copied())
+            .count(page_ids.len() as i32);
+
+        for (page_id, content) in page_ids.iter().zip(pages.pages) {
+            let content_id = Ref::new(page_id.get() + 1);
+            let mut page = pdf.page(*page_id);
+            page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+                .parent(page_tree_id)
+                .contents(content_id);
+            page.resources()
+                .fonts()
+                .pair(REGULAR, regular_id)
+                .pair(BOLD, bold_id);
+            page.finish();
+            pdf.stream(content_id, &content.finish());
+        }
+
+        pdf.type1_font(regular_id)
+            .base_font(Name(b"Helvetica"))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+        pdf.type1_font(bold_id)
+            .base_font(Name(b"Helvetica-Bold"))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+        pdf.finish()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_pdf())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    fn write_wheel(&self, pages: &mut Pages) {
+        pages.heading("Wheel of Life");
+        let subtitle = format!("Printed {}", format_local_date(self.generated_at));
+        let y = pages.y;
+        let content = pages.current();
+        content.set_fill_gray(MUTED_GRAY);
+        draw_text(content, MARGIN, y, REGULAR, 10.0, &subtitle);
+        content.set_fill_gray(0.0);
+
+        let cx = PAGE_WIDTH / 2.0;
+        let cy = pages.y - 40.0 - WHEEL_RADIUS;
+        self.draw_wheel(pages.current(), cx, cy);
+        pages.y = cy - WHEEL_RADIUS - 60.0;
+
+        for area in &self.areas {
+            pages.reserve(18.0);
+            let y = pages.y;
+            let content = pages.current();
+            set_fill(content, hex_rgb(&area.area.color));
+            content.rect(MARGIN, y, 8.0, 8.0).fill_nonzero();
+            content.set_fill_gray(0.0);
+            draw_text(content, MARGIN + 14.0, y, REGULAR, 10.0, &area.area.name);
+
+            let bar_x = MARGIN + 220.0;
+            let bar_width = 200.0;
+            content.set_fill_gray(0.93);
+            content.rect(bar_x, y, bar_width, 8.0).fill_nonzero();
+            match &area.score {
+                Some(score) => {
+                    set_fill(content, hex_rgb(&area.area.color));
+                    let filled = bar_width * score_ratio(score.value);
+                    content.rect(bar_x, y, filled, 8.0).fill_nonzero();
+                    content.set_fill_gray(0.0);
+                    draw_text(
+                        content,
+                        bar_x + bar_width + 12.0,
+                        y,
+                        BOLD,
+                        10.0,
+                        &score.value.to_string(),
+                    );
+                }
+                None => {
+                    content.set_fill_gray(MUTED_GRAY);
+                    draw_text(content, bar_x + bar_width + 12.0, y, REGULAR, 10.0, "-");
+                    content.set_fill_gray(0.0);
+                }
+            }
+            pages.y -= 18.0;
+        }
+
+        let values: Vec<i64> = self
+            .areas
+            .iter()
+            .filter_map(|area| area.score.as_ref().map(|score| score.value))
+            .collect();
+        if !values.is_empty() {
+            let average = values.iter().sum::<i64>() as f32 / values.len() as f32;
+            pages.reserve(24.0);
+            pages.y -= 6.0;
+            let y = pages.y;
+            draw_text(
+                pages.current(),
+                MARGIN,
+                y,
+                BOLD,
+                10.0,
+                &format!("Average {:.1}", average),
+            );
+        }
+    }
+
+    fn draw_wheel(&self, content: &mut Content, cx: f32, cy: f32) {
+        let count = self.areas.len();
+        let slice = if count == 0 { 0.0 } else { TAU / count as f32 };
+
+        for (i, area) in self.areas.iter().enumerate() {
+            let Some(score) = &area.score else {
+                continue;
+            };
+            let r = WHEEL_RADIUS * score_ratio(score.value);
+            if r <= 0.0 {
+                continue;
+            }
+            let start = i as f32 * slice;
+            let color = hex_rgb(&area.area.color);
+            set_fill(content, lighten(color, 0.25));
+            set_stroke(content, color);
+            content.move_to(cx, cy);
+            let (x, y) = polar(cx, cy, r, start);
+            content.line_to(x, y);
+            arc(content, cx, cy, r, start, start + slice);
+            content.close_path().fill_nonzero_and_stroke();
+        }
+
+        content.set_stroke_gray(GRID_GRAY).set_line_width(0.75);
+        for step in (2..=10).step_by(2) {
+            let r = WHEEL_RADIUS * step as f32 / 10.0;
+            let (x, y) = polar(cx, cy, r, 0.0);
+            content.move_to(x, y);
+            arc(content, cx, cy, r, 0.0, TAU);
+            content.close_path().stroke();
+        }
+        if count > 1 {
+            for i in 0..count {
+                let (x, y) = polar(cx, cy, WHEEL_RADIUS, i as f32 * slice);
+                content.move_to(cx, cy).line_to(x, y).stroke();
+            }
+        }
+        content.set_line_width(1.0).set_fill_gray(0.2);
+
+        for (i, area) in self.areas.iter().enumerate() {
+            let middle = (i as f32 + 0.5) * slice;
+            let (x, y) = polar(cx, cy, WHEEL_RADIUS + 14.0, middle);
+            let lines = wrap_text(&area.area.name, 9.0, 90.0);
+            let top = y + (lines.len() as f32 - 1.0) * 11.0 / 2.0 - 3.0;
+            for (line_index, line) in lines.iter().enumerate() {
+                let width = text_width(line, 9.0);
+                let line_x = if middle.sin() > 0.2 {
+                    x
+                } else if middle.sin() < -0.2 {
+                    x - width
+                } else {
+                    x - width / 2.0
+                };
+                draw_text(
+                    content,
+                    line_x,
+                    top - line_index as f32 * 11.0,
+                    REGULAR,
+                    9.0,
+                    line,
+                );
+            }
+        }
+        content.set_fill_gray(0.0);
+    }
+
+    fn write_history(&self, pages: &mut Pages) {
+        pages.heading("Score history");
+        let chart_width = (CONTENT_WIDTH - CHART_GAP) / 2.0;
+
+        for (i, area) in self.areas.iter().enumerate() {
+            let column = i % 2;
+            if column == 0 {
+                pages.reserve(CHART_HEIGHT);
+            }
+            let x = MARGIN + column as f32 * (chart_width + CHART_GAP);
+            let top = pages.y;
+            self.draw_chart(pages.current(), area, x, top, chart_width);
+            if column == 1 || i == self.areas.len() - 1 {
+                pages.y -= CHART_HEIGHT + CHART_GAP;
+            }
+        }
+    }
+
+    /// A line chart of one area's scores from `history_start` to now.
+    fn draw_chart(
+        &self,
+        content: &mut Content,
+        area: &PrintableArea,
+        x: f32,
+        top: f32,
+        width: f32,
+    ) {
+        let color = hex_rgb(&area.area.color);
+        set_fill(content, color);
+        content.rect(x, top, 8.0, 8.0).fill_nonzero();
+        content.set_fill_gray(0.0);
+        draw_text(content, x + 12.0, top, BOLD, 10.0, &area.area.name);
+
+        let left = x + 16.0;
+        let right = x + width;
+        let plot_top = top - 12.0;
+        let bottom = top - CHART_HEIGHT + 14.0;
+        let plot_height = plot_top - bottom;
+
+        content.set_stroke_gray(GRID_GRAY).set_line_width(0.5);
+        content.set_fill_gray(MUTED_GRAY);
+        for value in [0, 5, 10] {
+            let y = bottom + plot_height * value as f32 / 10.0;
+            content.move_to(left, y).line_to(right, y).stroke();
+            draw_text(content, x, y - 2.5, REGULAR, 7.0, &value.to_string());
+        }
+        draw_text(
+            content,
+            left,
+            bottom - 10.0,
+            REGULAR,
+            7.0,
+            &format_local_date(self.history_start),
+        );
+        let end_label = format_local_date(self.generated_at);
+        draw_text(
+            content,
+            right - text_width(&end_label, 7.0),
+            bottom - 10.0,
+            REGULAR,
+            7.0,
+            &end_label,
+        );
+
+        if area.history.is_empty() {
+            draw_text(
+                content,
+                left + 8.0,
+                bottom + plot_height / 2.0 - 3.0,
+                REGULAR,
+                9.0,
+                "No scores in this period",
+            );
+            content.set_fill_gray(0.0);
+            return;
+        }
+
+        let span = (self.generated_at - self.history_start).max(1) as f32;
+        let points: Vec<(f32, f32)> = area
+            .history
+            .iter()
+            .map(|score| {
+                let offset = (score.recorded_at - self.history_start) as f32 / span;
+                (
+                    left + (right - left) * offset.clamp(0.0, 1.0),
+                    bottom + plot_height * score_ratio(score.value),
+                )
+            })
+            .collect();
+
+        set_stroke(content, color);
+        content.set_line_width(1.5);
+        content.move_to(points[0].0, points[0].1);
+        for (px, py) in &points[1..] {
+            content.line_to(*px, *py);
+        }
+        content.stroke();
+        set_fill(content, color);
+        for (px, py) in &points {
+            content.rect(px - 1.5, py - 1.5, 3.0, 3.0).fill_nonzero();
+        }
+        content.set_line_width(1.0).set_fill_gray(0.0);
+    }
+
+    fn write_action_plan(&self, pages: &mut Pages) {
+        pages.heading("Action plan");
+
+        for area in &self.areas {
+            pages.reserve(40.0);
+            let y = pages.y;
+            let content = pages.current();
+            set_fill(content, hex_rgb(&area.area.color));
+            content.rect(MARGIN, y, 10.0, 10.0).fill_nonzero();
+            content.set_fill_gray(0.0);
+            let title = match &area.score {
+                Some(score) => format!("{} ({})", area.area.name, score.value),
+                None => area.area.name.clone(),
+            };
+            draw_text(content, MARGIN + 16.0, y, BOLD, 13.0, &title);
+            pages.y -= 20.0;
+
+            if area.action_items.is_empty() {
+                pages.muted_lines(&["No open actions.".to_string()], MARGIN + 16.0);
+            }
+            for item in &area.action_items {
+                let text = match item.due_at {
+                    Some(due_at) => format!("{} (due {})", item.title, format_local_date(due_at)),
+                    None => item.title.clone(),
+                };
+                let lines = wrap_text(&text, 10.0, CONTENT_WIDTH - 16.0);
+                pages.reserve(lines.len() as f32 * LINE_HEIGHT);
+                let y = pages.y;
+                let content = pages.current();
+                content.rect(MARGIN, y - 1.0, 9.0, 9.0).stroke();
+                for (i, line) in lines.iter().enumerate() {
+                    draw_text(
+                        content,
+                        MARGIN + 16.0,
+                        y - i as f32 * LINE_HEIGHT,
+                        REGULAR,
+                        10.0,
+                        line,
+                    );
+                }
+                pages.y -= lines.len() as f32 * LINE_HEIGHT + 3.0;
+            }
+
+            if !area.notes.is_empty() {
+                pages.reserve(LINE_HEIGHT * 2.0);
+                pages.y -= 4.0;
+                let y = pages.y;
+                draw_text(pages.current(), MARGIN, y, BOLD, 10.0, "Notes");
+                pages.y -= LINE_HEIGHT + 2.0;
+                for score in &area.notes {
+                    let text = format!(
+                        "{} ({}): {}",
+                        format_local_date(score.recorded_at),
+                        score.value,
+                        score.note.as_deref().unwrap_or_default()
+                    );
+                    pages.muted_lines(&wrap_text(&text, 10.0, CONTENT_WIDTH), MARGIN);
+                }
+            }
+
+            pages.y -= 14.0;
+        }
+    }
+}
+
+/// Content streams of the pages written so far and the baseline of the next
+/// line on the last one. PDF coordinates start at the bottom left.
+struct Pages {
+    pages: Vec<Content>,
+    y: f32,
+}
+
+impl Pages {
+    fn new() -> Self {
+        Pages {
+            pages: vec![Content::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn current(&mut self) -> &mut Content {
+        self.pages.last_mut().expect("a page is always open")
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Content::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Starts a new page unless `height` more points fit on this one.
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.y -= 22.0;
+        let y = self.y;
+        draw_text(self.current(), MARGIN, y, BOLD, 22.0, text);
+        self.y -= 24.0;
+    }
+
+    fn muted_lines(&mut self, lines: &[String], x: f32) {
+        for line in lines {
+            self.reserve(LINE_HEIGHT);
+            let y = self.y;
+            let content = self.current();
+            content.set_fill_gray(MUTED_GRAY);
+            draw_text(content, x, y, REGULAR, 10.0, line);
+            content.set_fill_gray(0.0);
+            self.y -= LINE_HEIGHT;
+        }
+    }
+}
+
+fn draw_text(content: &mut Content, x: f32, y: f32, font: Name, size: f32, text: &str) {
+    content
+        .begin_text()
+        .set_font(font, size)
+        .next_line(x, y)
+        .show(Str(&win_ansi(text)))
+        .end_text();
+}
+
+/// Point at `angle` radians clockwise from the top of a circle.
+fn polar(cx: f32, cy: f32, r: f32, angle: f32) -> (f32, f32) {
+    (cx + r * angle.sin(), cy + r * angle.cos())
+}
+
+/// Continues the current path clockwise along a circle from `start` to `end`,
+/// in Bézier segments of at most a quarter turn.
+fn arc(content: &mut Content, cx: f32, cy: f32, r: f32, start: f32, end: f32) {
+    let segments = ((end - start) / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = (end - start) / segments as f32;
+    let handle = 4.0 / 3.0 * (step / 4.0).tan() * r;
+
+    for i in 0..segments {
+        let a0 = start + i as f32 * step;
+        let a1 = a0 + step;
+        let (x0, y0) = polar(cx, cy, r, a0);
+        let (x1, y1) = polar(cx, cy, r, a1);
+        content.cubic_to(
+            x0 + handle * a0.cos(),
+            y0 - handle * a0.sin(),
+            x1 - handle * a1.cos(),
+            y1 + handle * a1.sin(),
+            x1,
+            y1,
+        );
+    }
+}
+
+fn score_ratio(value: i64) -> f32 {
+    value.clamp(0, 10) as f32 / 10.0
+}
+
+/// `#rrggbb` or `#rgb` as RGB fractions; gray for anything else.
+fn hex_rgb(color: &str) -> (f32, f32, f32) {
+    let hex = color.trim().trim_start_matches('#');
+    let hex = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        _ => hex.to_string(),
+    };
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .map(|c| c as f32 / 255.0)
+    };
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => (r, g, b),
+        _ => (0.6, 0.6, 0.6),
+    }
+}
+
+/// Mixes a color with white, keeping `amount` of the white out.
+fn lighten((r, g, b): (f32, f32, f32), amount: f32) -> (f32, f32, f32) {
+    let mix = |c: f32| c + (1.0 - c) * amount;
+    (mix(r), mix(g), mix(b))
+}
+
+fn set_fill(content: &mut Content, (r, g, b): (f32, f32, f32)) {
+    content.set_fill_rgb(r, g, b);
+}
+
+fn set_stroke(content: &mut Content, (r, g, b): (f32, f32, f32)) {
+    content.set_stroke_rgb(r, g, b);
+}
+
+/// Helvetica advance widths of the printable ASCII characters, in thousandths
+/// of the font size.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Approximate width of `text` in points; characters outside ASCII count as
+/// an average letter. Bold text runs slightly wider.
+fn text_width(text: &str, size: f32) -> f32 {
+    let units: u32 = text
+        .chars()
+        .map(|c| {
+            (c as usize)
+                .checked_sub(32)
+                .and_then(|i| HELVETICA_WIDTHS.get(i))
+                .copied()
+                .unwrap_or(556) as u32
+        })
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+/// Splits `text` at spaces into lines no wider than `max_width`. Single words
+/// that are too wide get a line of their own.
+fn wrap_text(text: &str, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if text_width(&format!("{} {}", line, word), size) <= max_width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// Encodes `text` for the standard fonts' WinAnsi encoding, which matches
+/// Latin-1 apart from a few punctuation marks. Other characters become `?`.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            _ => b'?',
+        })
+        .collect()
+}
@@ -0,0 +1,266 @@
+use crate::database::open_database;
+use crate::location::{
+    self, data_dir, read_config, remove_database_files, write_config, Config, LocationSource,
+    ProfileEntry, DB_FILE_NAME, DB_PATH_ENV,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Name given to the database that existed before any profile was created.
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+const MAX_PROFILE_NAME_LENGTH: usize = 40;
+const PROFILES_DIR: &str = "profiles";
+
+/// An independent wheel with its own database file.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Profile {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_active: bool,
+}
+
+pub fn list_profiles() -> Result<Vec<Profile>, String> {
+    let config = profile_config()?;
+    to_profiles(&config)
+}
+
+/// Creates a profile with an empty database. The active profile is unchanged.
+pub fn create_profile(name: &str) -> Result<Profile, String> {
+    let mut config = profile_config()?;
+    let name = validate_name(&config, name, None)?;
+
+    let (data_dir, _) = data_dir()?;
+    let slug = slugify(&name);
+    let mut relative = Path::new(PROFILES_DIR).join(format!("{}.db", slug));
+    let mut suffix = 2;
+    while data_dir.join(&relative).exists() || config.profiles.iter().any(|p| p.path == relative) {
+        relative = Path::new(PROFILES_DIR).join(format!("{}-{}.db", slug, suffix));
+        suffix += 1;
+    }
+
+    let path = data_dir.join(&relative);
+    open_database(&path)?;
+
+    config.profiles.push(ProfileEntry {
+        name: name.clone(),
+        path: relative,
+    });
+    write_config(&config)?;
+
+    Ok(Profile {
+        name,
+        path,
+        is_active: false,
+    })
+}
+
+/// Renames a profile; its database file stays where it is.
+pub fn rename_profile(name: &str, new_name: &str) -> Result<Profile, String> {
+    let mut config = profile_config()?;
+    let index = find_profile(&config, name)?;
+    let new_name = validate_name(&config, new_name, Some(index))?;
+
+    if config.active_profile.as_deref() == Some(config.profiles[index].name.as_str()) {
+        config.active_profile = Some(new_name.clone());
+    }
+    config.profiles[index].name = new_name.clone();
+    write_config(&config)?;
+
+    profile_named(&config, &new_name)
+}
+
+/// Makes `name` the profile every later read and write goes to.
+pub fn switch_profile(name: &str) -> Result<Profile, String> {
+    let mut config = profile_config()?;
+    activate(&mut config, name)?;
+    write_config(&config)?;
+
+    profile_named(&config, name)
+}
+
+/// Deletes a profile and its database file. The active profile cannot be deleted.
+pub fn delete_profile(name: &str) -> Result<(), String> {
+    let mut config = profile_config()?;
+    let index = find_profile(&config, name)?;
+    if config.active_profile.as_deref() == Some(config.profiles[index].name.as_str()) {
+        return Err("Switch to another profile before deleting this one".to_string());
+    }
+
+    let profile = config.profiles.remove(index);
+    write_config(&config)?;
+
+    let (data_dir, _) = data_dir()?;
+    remove_database_files(&data_dir.join(profile.path))
+}
+
+/// The config with the pre-profile database registered as the default
+/// profile. Fails when a flag or `RODA_DB_PATH` overrides the config file,
+/// since switching profiles would have no effect.
+fn profile_config() -> Result<Config, String> {
+    match location::resolve()?.source {
+        LocationSource::Flag => {
+            return Err("Profiles are unavailable while --db is set".to_string())
+        }
+        LocationSource::Environment => {
+            return Err(format!(
+                "Profiles are unavailable while {} is set",
+                DB_PATH_ENV
+            ))
+        }
+        LocationSource::Config | LocationSource::Default | LocationSource::Portable => {}
+    }
+
+    Ok(with_default_profile(read_config()?))
+}
+
+/// Registers the database used before any profile existed as the active
+/// default profile, unless profiles already exist.
+fn with_default_profile(mut config: Config) -> Config {
+    if config.profiles.is_empty() {
+        config.profiles.push(ProfileEntry {
+            name: DEFAULT_PROFILE_NAME.to_string(),
+            path: config
+                .database_path
+                .take()
+                .unwrap_or_else(|| PathBuf::from(DB_FILE_NAME)),
+        });
+        config.active_profile = Some(DEFAULT_PROFILE_NAME.to_string());
+    }
+
+    config
+}
+
+fn activate(config: &mut Config, name: &str) -> Result<(), String> {
+    let index = find_profile(config, name)?;
+    config.active_profile = Some(config.profiles[index].name.clone());
+    Ok(())
+}
+
+fn to_profiles(config: &Config) -> Result<Vec<Profile>, String> {
+    let (data_dir, _) = data_dir()?;
+
+    Ok(config
+        .profiles
+        .iter()
+        .map(|profile| Profile {
+            name: profile.name.clone(),
+            path: data_dir.join(&profile.path),
+            is_active: config.active_profile.as_deref() == Some(profile.name.as_str()),
+        })
+        .collect())
+}
+
+fn profile_named(config: &Config, name: &str) -> Result<Profile, String> {
+    let index = find_profile(config, name)?;
+    to_profiles(config)?
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| format!("No profile named \"{}\"", name))
+}
+
+/// Index of the profile called `name`, ignoring case.
+fn find_profile(config: &Config, name: &str) -> Result<usize, String> {
+    let lower = name.trim().to_lowercase();
+    config
+        .profiles
+        .iter()
+        .position(|p| p.name.to_lowercase() == lower)
+        .ok_or_else(|| format!("No profile named \"{}\"", name.trim()))
+}
+
+fn validate_name(config: &Config, name: &str, current: Option<usize>) -> Result<String, String> {
+    let clean_name = name.trim();
+    if clean_name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if clean_name.chars().count() > MAX_PROFILE_NAME_LENGTH {
+        return Err(format!(
+            "Profile name cannot exceed {} characters",
+            MAX_PROFILE_NAME_LENGTH
+        ));
+    }
+
+    let lower = clean_name.to_lowercase();
+    let taken = config
+        .profiles
+        .iter()
+        .enumerate()
+        .any(|(i, p)| Some(i) != current && p.name.to_lowercase() == lower);
+    if taken {
+        return Err("A profile with this name already exists".to_string());
+    }
+
+    Ok(clean_name.to_string())
+}
+
+/// A file name for the profile: lowercase ASCII letters, digits and dashes.
+pub(crate) fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "profile".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, path: &str) -> ProfileEntry {
+        ProfileEntry {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn database_from_before_profiles_becomes_the_default_profile() {
+        let config = with_default_profile(Config {
+            database_path: Some(PathBuf::from("/data/wheel.db")),
+            ..Config::default()
+        });
+
+        assert_eq!(config.database_path, None);
+        assert_eq!(config.active_profile.as_deref(), Some(DEFAULT_PROFILE_NAME));
+        assert_eq!(config.active_path(), Some(Path::new("/data/wheel.db")));
+
+        let config = with_default_profile(Config::default());
+        assert_eq!(config.active_path(), Some(Path::new(DB_FILE_NAME)));
+    }
+
+    #[test]
+    fn switching_resolves_to_the_profile_database() {
+        let mut config = with_default_profile(Config::default());
+        config.profiles.push(entry("Work", "profiles/work.db"));
+
+        activate(&mut config, " work ").unwrap();
+        assert_eq!(config.active_profile.as_deref(), Some("Work"));
+        assert_eq!(config.active_path(), Some(Path::new("profiles/work.db")));
+
+        assert!(activate(&mut config, "Family").is_err());
+        assert_eq!(config.active_profile.as_deref(), Some("Work"));
+    }
+
+    #[test]
+    fn profile_names_are_unique_ignoring_case() {
+        let config = Config {
+            profiles: vec![entry("Default", DB_FILE_NAME), entry("Work", "work.db")],
+            ..Config::default()
+        };
+
+        assert!(validate_name(&config, "WORK", None).is_err());
+        assert_eq!(validate_name(&config, " WORK ", Some(1)).unwrap(), "WORK");
+        assert!(validate_name(&config, "  ", None).is_err());
+        assert_eq!(slugify("Família & Amigos!"), "fam-lia-amigos");
+    }
+}
@@ -0,0 +1,390 @@
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, TimeZone, Weekday};
+
+const MAX_INTERVAL: u32 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// The subset of RFC 5545 RRULE supported for action items:
+/// `FREQ=DAILY|WEEKLY|MONTHLY`, `INTERVAL=n`, `BYDAY=MO,TU,...` (weekly only)
+/// and `BYMONTHDAY=d` (monthly only).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub weekdays: Vec<Weekday>,
+    pub month_day: Option<u32>,
+}
+
+impl RecurrenceRule {
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut weekdays = Vec::new();
+        let mut month_day = None;
+
+        for part in rule.split(';').filter(|p| !p.trim().is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid recurrence rule part: {}", part))?;
+            match key.trim().to_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.trim().to_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        other => {
+                            return Err(format!("Unsupported recurrence frequency: {}", other))
+                        }
+                    });
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .trim()
+                        .parse::<u32>()
+                        .map_err(|_| format!("Invalid recurrence interval: {}", value))?;
+                    if interval == 0 || interval > MAX_INTERVAL {
+                        return Err(format!(
+                            "Recurrence interval must be between 1 and {}",
+                            MAX_INTERVAL
+                        ));
+                    }
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        let weekday = parse_weekday(day.trim())?;
+                        if !weekdays.contains(&weekday) {
+                            weekdays.push(weekday);
+                        }
+                    }
+                }
+                "BYMONTHDAY" => {
+                    let day = value
+                        .trim()
+                        .parse::<u32>()
+                        .map_err(|_| format!("Invalid recurrence month day: {}", value))?;
+                    if !(1..=31).contains(&day) {
+                        return Err("Recurrence month day must be between 1 and 31".to_string());
+                    }
+                    month_day = Some(day);
+                }
+                other => return Err(format!("Unsupported recurrence rule part: {}", other)),
+            }
+        }
+
+        let frequency = frequency.ok_or("Recurrence rule must specify FREQ")?;
+        if !weekdays.is_empty() && frequency != Frequency::Weekly {
+            return Err("BYDAY is only supported for weekly recurrence".to_string());
+        }
+        if month_day.is_some() && frequency != Frequency::Monthly {
+            return Err("BYMONTHDAY is only supported for monthly recurrence".to_string());
+        }
+        weekdays.sort_by_key(|d| d.num_days_from_monday());
+
+        Ok(RecurrenceRule {
+            frequency,
+            interval,
+            weekdays,
+            month_day,
+        })
+    }
+
+    /// Canonical RRULE string, as stored in the database.
+    pub fn to_rrule(&self) -> String {
+        let mut parts = vec![format!(
+            "FREQ={}",
+            match self.frequency {
+                Frequency::Daily => "DAILY",
+                Frequency::Weekly => "WEEKLY",
+                Frequency::Monthly => "MONTHLY",
+            }
+        )];
+        if self.interval != 1 {
+            parts.push(format!("INTERVAL={}", self.interval));
+        }
+        if !self.weekdays.is_empty() {
+            let days: Vec<&str> = self.weekdays.iter().map(|d| weekday_code(*d)).collect();
+            parts.push(format!("BYDAY={}", days.join(",")));
+        }
+        if let Some(day) = self.month_day {
+            parts.push(format!("BYMONTHDAY={}", day));
+        }
+        parts.join(";")
+    }
+
+    /// Monthly rules without BYMONTHDAY repeat on the day of `due`. Returns
+    /// the rule with that day made explicit, so later occurrences keep the
+    /// 31st after passing through a shorter month.
+    pub fn anchored_to<Tz: TimeZone>(&self, due: &DateTime<Tz>) -> Self {
+        let mut rule = self.clone();
+        if rule.frequency == Frequency::Monthly && rule.month_day.is_none() {
+            rule.month_day = Some(due.day());
+        }
+        rule
+    }
+
+    /// First occurrence after `due` that also lies after `now`, so overdue
+    /// recurring items do not spawn a backlog of past occurrences. The local
+    /// wall-clock time of `due` is kept.
+    pub fn next_occurrence<Tz: TimeZone>(
+        &self,
+        due: DateTime<Tz>,
+        now: DateTime<Tz>,
+    ) -> Option<DateTime<Tz>> {
+        let rule = self.anchored_to(&due);
+        let mut date = due.date_naive();
+        loop {
+            date = rule.next_date(date)?;
+            let next = at_wall_time(&due.timezone(), date, due.time())?;
+            if next > now {
+                return Some(next);
+            }
+        }
+    }
+
+    fn next_date(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let next_date = match self.frequency {
+            Frequency::Daily => date + Duration::days(self.interval as i64),
+            Frequency::Weekly if self.weekdays.is_empty() => {
+                date + Duration::weeks(self.interval as i64)
+            }
+            Frequency::Weekly => {
+                let first_week = week_start(date);
+                (1..=7 * self.interval as i64 + 7)
+                    .map(|offset| date + Duration::days(offset))
+                    .find(|candidate| {
+                        let weeks = (week_start(*candidate) - first_week).num_weeks();
+                        weeks % self.interval as i64 == 0
+                            && self.weekdays.contains(&candidate.weekday())
+                    })?
+            }
+            Frequency::Monthly => {
+                let first_of_month = date.with_day(1)?;
+                let day = self.month_day.unwrap_or(date.day());
+                let this_month = first_of_month.with_day(day.min(days_in_month(first_of_month)))?;
+                if this_month > date {
+                    this_month
+                } else {
+                    let target = first_of_month.checked_add_months(Months::new(self.interval))?;
+                    target.with_day(day.min(days_in_month(target)))?
+                }
+            }
+        };
+        Some(next_date)
+    }
+}
+
+/// `date` at the wall-clock `time` in `tz`. A time skipped by a daylight
+/// saving change is moved forward by an hour, out of the gap.
+fn at_wall_time<Tz: TimeZone>(tz: &Tz, date: NaiveDate, time: NaiveTime) -> Option<DateTime<Tz>> {
+    let local = date.and_time(time);
+    tz.from_local_datetime(&local).earliest().or_else(|| {
+        tz.from_local_datetime(&(local + Duration::hours(1)))
+            .earliest()
+    })
+}
+
+fn parse_weekday(code: &str) -> Result<Weekday, String> {
+    match code.to_uppercase().as_str() {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        _ => Err(format!("Invalid weekday in recurrence rule: {}", code)),
+    }
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn days_in_month(first_of_month: NaiveDate) -> u32 {
+    first_of_month
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(28)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use chrono_tz::{America::Sao_Paulo, Europe::Berlin};
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn monthly_occurrences() {
+        let cases = [
+            // rule, from, expected
+            ("FREQ=MONTHLY", utc(2025, 1, 31, 9), utc(2025, 2, 28, 9)),
+            ("FREQ=MONTHLY", utc(2024, 1, 31, 9), utc(2024, 2, 29, 9)),
+            ("FREQ=MONTHLY", utc(2025, 3, 15, 9), utc(2025, 4, 15, 9)),
+            ("FREQ=MONTHLY", utc(2025, 12, 31, 9), utc(2026, 1, 31, 9)),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=20",
+                utc(2025, 1, 5, 9),
+                utc(2025, 1, 20, 9),
+            ),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=20",
+                utc(2025, 1, 20, 9),
+                utc(2025, 2, 20, 9),
+            ),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=20",
+                utc(2025, 1, 25, 9),
+                utc(2025, 2, 20, 9),
+            ),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=31",
+                utc(2025, 2, 28, 9),
+                utc(2025, 3, 31, 9),
+            ),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=31",
+                utc(2025, 4, 10, 9),
+                utc(2025, 4, 30, 9),
+            ),
+            (
+                "FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=5",
+                utc(2025, 1, 10, 9),
+                utc(2025, 3, 5, 9),
+            ),
+        ];
+
+        for (rule, from, expected) in cases {
+            let rule = RecurrenceRule::parse(rule).unwrap();
+            assert_eq!(
+                rule.next_occurrence(from, from),
+                Some(expected),
+                "{:?} after {}",
+                rule,
+                from
+            );
+        }
+    }
+
+    #[test]
+    fn monthly_rule_keeps_the_original_day_after_a_short_month() {
+        let rule = RecurrenceRule::parse("FREQ=MONTHLY").unwrap();
+        let due = utc(2025, 1, 31, 9);
+
+        let anchored = rule.anchored_to(&due);
+        assert_eq!(anchored.to_rrule(), "FREQ=MONTHLY;BYMONTHDAY=31");
+
+        let february = anchored.next_occurrence(due, due).unwrap();
+        assert_eq!(february, utc(2025, 2, 28, 9));
+        assert_eq!(
+            anchored.next_occurrence(february, february),
+            Some(utc(2025, 3, 31, 9))
+        );
+        assert_eq!(
+            rule.next_occurrence(due, utc(2025, 3, 1, 0)),
+            Some(utc(2025, 3, 31, 9))
+        );
+    }
+
+    #[test]
+    fn other_frequencies() {
+        let cases = [
+            ("FREQ=DAILY", utc(2025, 2, 28, 9), utc(2025, 3, 1, 9)),
+            (
+                "FREQ=DAILY;INTERVAL=3",
+                utc(2025, 12, 30, 9),
+                utc(2026, 1, 2, 9),
+            ),
+            ("FREQ=WEEKLY", utc(2025, 1, 1, 9), utc(2025, 1, 8, 9)),
+            // 2025-01-01 is a Wednesday.
+            (
+                "FREQ=WEEKLY;BYDAY=MO,FR",
+                utc(2025, 1, 1, 9),
+                utc(2025, 1, 3, 9),
+            ),
+            (
+                "FREQ=WEEKLY;BYDAY=MO,FR",
+                utc(2025, 1, 3, 9),
+                utc(2025, 1, 6, 9),
+            ),
+            (
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO",
+                utc(2025, 1, 6, 9),
+                utc(2025, 1, 20, 9),
+            ),
+        ];
+
+        for (rule, from, expected) in cases {
+            let rule = RecurrenceRule::parse(rule).unwrap();
+            assert_eq!(
+                rule.next_occurrence(from, from),
+                Some(expected),
+                "{:?} after {}",
+                rule,
+                from
+            );
+        }
+    }
+
+    #[test]
+    fn occurrence_in_a_daylight_saving_gap_moves_forward() {
+        let rule = RecurrenceRule::parse("FREQ=DAILY").unwrap();
+
+        // Clocks in Berlin jump from 02:00 to 03:00 on 2025-03-30.
+        let from = Berlin.with_ymd_and_hms(2025, 3, 29, 2, 30, 0).unwrap();
+        let next = rule.next_occurrence(from, from).unwrap();
+        assert_eq!(
+            next,
+            Berlin.with_ymd_and_hms(2025, 3, 30, 3, 30, 0).unwrap()
+        );
+
+        // The time of day returns to normal once past the gap.
+        let now = Berlin.with_ymd_and_hms(2025, 3, 30, 12, 0, 0).unwrap();
+        assert_eq!(
+            rule.next_occurrence(from, now),
+            Some(Berlin.with_ymd_and_hms(2025, 3, 31, 2, 30, 0).unwrap())
+        );
+
+        // São Paulo used to start daylight saving at midnight.
+        let from = Sao_Paulo.with_ymd_and_hms(2018, 11, 3, 0, 0, 0).unwrap();
+        assert_eq!(
+            rule.next_occurrence(from, from),
+            Some(Sao_Paulo.with_ymd_and_hms(2018, 11, 4, 1, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn ambiguous_time_uses_the_first_occurrence() {
+        let rule = RecurrenceRule::parse("FREQ=DAILY").unwrap();
+
+        // Clocks in Berlin fall back from 03:00 to 02:00 on 2025-10-26.
+        let from = Berlin.with_ymd_and_hms(2025, 10, 25, 2, 30, 0).unwrap();
+        let next = rule.next_occurrence(from, from).unwrap();
+        assert_eq!(next.naive_local().to_string(), "2025-10-26 02:30:00");
+        assert_eq!(next.offset().to_string(), "CEST");
+    }
+}
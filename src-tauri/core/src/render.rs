@@ -0,0 +1,308 @@
+use crate::models::WheelEntry;
+use resvg::{tiny_skia, usvg};
+use serde::Deserialize;
+use std::f64::consts::{FRAC_PI_2, TAU};
+use std::fmt::Write;
+use std::path::Path;
+
+const MAX_SCORE: f64 = 10.0;
+const DEFAULT_IMAGE_SIZE: u32 = 600;
+const MIN_IMAGE_SIZE: u32 = 128;
+const MAX_IMAGE_SIZE: u32 = 4096;
+const LABEL_LINE_LENGTH: usize = 14;
+const LINE_HEIGHT: f64 = 1.2;
+/// Generic names first; the rest help rasterizing where they are not mapped.
+const FONT_FAMILY: &str = "sans-serif, DejaVu Sans, Arial, Helvetica";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WheelTheme {
+    Light,
+    Dark,
+}
+
+impl WheelTheme {
+    pub fn parse(theme: &str) -> Result<Self, String> {
+        match theme.trim().to_lowercase().as_str() {
+            "light" => Ok(WheelTheme::Light),
+            "dark" => Ok(WheelTheme::Dark),
+            _ => Err("Theme must be \"light\" or \"dark\"".to_string()),
+        }
+    }
+
+    /// `(background, grid, text)` colors.
+    fn colors(&self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            WheelTheme::Light => ("#ffffff", "#e5e7eb", "#374151"),
+            WheelTheme::Dark => ("#111827", "#374151", "#e5e7eb"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct WheelImageOptions {
+    /// Width and height in pixels.
+    pub size: u32,
+    pub theme: WheelTheme,
+}
+
+impl Default for WheelImageOptions {
+    fn default() -> Self {
+        WheelImageOptions {
+            size: DEFAULT_IMAGE_SIZE,
+            theme: WheelTheme::Light,
+        }
+    }
+}
+
+impl WheelImageOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_IMAGE_SIZE..=MAX_IMAGE_SIZE).contains(&self.size) {
+            return Err(format!(
+                "Image size must be between {} and {} pixels",
+                MIN_IMAGE_SIZE, MAX_IMAGE_SIZE
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+impl ImageFormat {
+    /// The format matching the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .as_deref()
+        {
+            Some("svg") => Ok(ImageFormat::Svg),
+            Some("png") => Ok(ImageFormat::Png),
+            _ => Err(format!(
+                "Cannot tell the image format of {}; use a .svg or .png file",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// The wheel as a standalone SVG image `options.size` pixels square: one wedge per
+/// area in wheel order, starting at the top and reaching out to the area's
+/// score. Areas without a score leave their slice empty.
+pub fn wheel_svg(entries: &[WheelEntry], options: &WheelImageOptions) -> String {
+    let (background, grid, text) = options.theme.colors();
+    let size = f64::from(options.size);
+    let center = size / 2.0;
+    // Leaves room around the wheel for the area names
+    let radius = size * 0.3;
+    let font_size = size / 36.0;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" \
+         viewBox=\"0 0 {size} {size}\" font-family=\"{FONT_FAMILY}\" font-size=\"{font_size:.1}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{background}\"/>\n"
+    );
+
+    for step in (2..=10).step_by(2) {
+        let _ = writeln!(
+            svg,
+            "  <circle cx=\"{center}\" cy=\"{center}\" r=\"{:.2}\" fill=\"none\" stroke=\"{grid}\"/>",
+            radius * f64::from(step) / MAX_SCORE
+        );
+    }
+
+    let count = entries.len();
+    let slice = if count == 0 { 0.0 } else { TAU / count as f64 };
+    let point = |angle: f64, r: f64| (center + r * angle.cos(), center + r * angle.sin());
+
+    for (i, entry) in entries.iter().enumerate() {
+        let start = i as f64 * slice - FRAC_PI_2;
+        let end = start + slice;
+        let color = escape_xml(&entry.area.color);
+
+        if let Some(score) = &entry.score {
+            let r = radius * (score.value as f64).clamp(0.0, MAX_SCORE) / MAX_SCORE;
+            if count == 1 {
+                let _ = writeln!(
+                    svg,
+                    "  <circle cx=\"{center}\" cy=\"{center}\" r=\"{r:.2}\" fill=\"{color}\" fill-opacity=\"0.75\"/>"
+                );
+            } else if r > 0.0 {
+                let (x1, y1) = point(start, r);
+                let (x2, y2) = point(end, r);
+                let large_arc = u8::from(slice > std::f64::consts::PI);
+                let _ = writeln!(
+                    svg,
+                    "  <path d=\"M {center} {center} L {x1:.2} {y1:.2} A {r:.2} {r:.2} 0 {large_arc} 1 {x2:.2} {y2:.2} Z\" \
+                     fill=\"{color}\" fill-opacity=\"0.75\" stroke=\"{color}\"/>"
+                );
+            }
+        }
+
+        if count > 1 {
+            let (x, y) = point(start, radius);
+            let _ = writeln!(
+                svg,
+                "  <line x1=\"{center}\" y1=\"{center}\" x2=\"{x:.2}\" y2=\"{y:.2}\" stroke=\"{grid}\"/>"
+            );
+        }
+
+        let middle = start + slice / 2.0;
+        let (x, y) = point(middle, radius + font_size);
+        let anchor = if middle.cos().abs() < 0.2 {
+            "middle"
+        } else if middle.cos() > 0.0 {
+            "start"
+        } else {
+            "end"
+        };
+        let label = match &entry.score {
+            Some(score) => format!("{} ({})", entry.area.name, score.value),
+            None => entry.area.name.clone(),
+        };
+        let lines = wrap_label(&label);
+        let _ = write!(
+            svg,
+            "  <text x=\"{x:.2}\" y=\"{y:.2}\" text-anchor=\"{anchor}\" dominant-baseline=\"middle\" fill=\"{text}\">"
+        );
+        for (i, line) in lines.iter().enumerate() {
+            // Centers the block of lines on the label position
+            let dy = if i == 0 {
+                -(lines.len() as f64 - 1.0) / 2.0 * LINE_HEIGHT
+            } else {
+                LINE_HEIGHT
+            };
+            let _ = write!(
+                svg,
+                "<tspan x=\"{x:.2}\" dy=\"{dy:.2}em\">{}</tspan>",
+                escape_xml(line)
+            );
+        }
+        svg.push_str("</text>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Splits a label at spaces into lines of about `LABEL_LINE_LENGTH` characters.
+fn wrap_label(label: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in label.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= LABEL_LINE_LENGTH => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+/// The wheel rasterized to a PNG image, with text set in the system fonts.
+pub fn wheel_png(entries: &[WheelEntry], options: &WheelImageOptions) -> Result<Vec<u8>, String> {
+    options.validate()?;
+
+    let mut usvg_options = usvg::Options::default();
+    usvg_options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(&wheel_svg(entries, options), &usvg_options)
+        .map_err(|e| format!("Failed to parse wheel image: {}", e))?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(options.size, options.size)
+        .ok_or("Failed to allocate wheel image")?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap
+        .encode_png()
+        .map_err(|e| format!("Failed to encode wheel image: {}", e))
+}
+
+/// Writes the wheel to `path` as SVG or PNG, depending on its extension.
+pub fn save_wheel_image(
+    entries: &[WheelEntry],
+    path: &Path,
+    options: &WheelImageOptions,
+) -> Result<(), String> {
+    options.validate()?;
+
+    let contents = match ImageFormat::from_path(path)? {
+        ImageFormat::Svg => wheel_svg(entries, options).into_bytes(),
+        ImageFormat::Png => wheel_png(entries, options)?,
+    };
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Escapes text for use in XML and HTML content and attribute values.
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LifeArea, Score};
+
+    fn entry(id: i64, name: &str, score: Option<i64>) -> WheelEntry {
+        WheelEntry {
+            area: LifeArea {
+                id,
+                name: name.to_string(),
+                description: None,
+                color: "#10b981".to_string(),
+                order: id,
+                is_active: true,
+                created_at: 0,
+                updated_at: 0,
+            },
+            score: score.map(|value| Score {
+                id,
+                area_id: id,
+                value,
+                recorded_at: 0,
+                note: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn wheel_svg_is_valid_with_a_slice_per_scored_area() {
+        let entries = [
+            entry(1, "Health", Some(7)),
+            entry(2, "Friends & Family", Some(4)),
+            entry(3, "Career", None),
+        ];
+        let options = WheelImageOptions::default();
+        let svg = wheel_svg(&entries, &options);
+
+        assert!(usvg::Tree::from_str(&svg, &usvg::Options::default()).is_ok());
+        assert!(svg.contains(&format!("width=\"{}\"", options.size)));
+        assert_eq!(svg.matches("<path ").count(), 2);
+        assert!(svg.contains(">Friends &amp;</tspan>"));
+        assert!(svg.contains(">Career</tspan>"));
+    }
+
+    #[test]
+    fn long_labels_wrap_at_spaces() {
+        let lines = wrap_label("Personal growth and lifelong learning (8)");
+        assert!(lines.len() > 1);
+        assert_eq!(lines.join(" "), "Personal growth and lifelong learning (8)");
+    }
+}
@@ -0,0 +1,342 @@
+use crate::aggregation::{format_local_date, Granularity};
+use crate::models::{ActionItem, LifeArea, Score, WheelEntry};
+use crate::render::{escape_xml, wheel_svg, WheelImageOptions};
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+
+const LARGEST_CHANGES: usize = 3;
+const HTML_WHEEL_SIZE: u32 = 480;
+
+/// Everything a periodic review covers, gathered by `Store::get_review_report`.
+#[derive(Debug, Serialize, Clone)]
+pub struct ReviewReport {
+    pub title: String,
+    pub period_start: i64,
+    /// Last second of the period, or when the report was generated if the
+    /// period is still running.
+    pub period_end: i64,
+    /// Start of the period the scores are compared with; it ends right before
+    /// `period_start`.
+    pub previous_start: i64,
+    pub generated_at: i64,
+    /// Areas active at the end of the period, in wheel order.
+    pub areas: Vec<AreaReview>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AreaReview {
+    pub area: LifeArea,
+    /// Latest score at the end of the period.
+    pub score: Option<Score>,
+    /// Latest score at the end of the previous period.
+    pub previous_score: Option<Score>,
+    pub change: Option<i64>,
+    /// Scores recorded during the period that have a note, oldest first.
+    pub notes: Vec<Score>,
+    /// Action items archived during the period.
+    pub completed: Vec<ActionItem>,
+    /// Action items still open at the end of the period.
+    pub open: Vec<ActionItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.trim().to_lowercase().as_str() {
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err("Format must be \"markdown\" or \"html\"".to_string()),
+        }
+    }
+
+    /// The format matching the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .as_deref()
+        {
+            Some("md" | "markdown") => Ok(ReportFormat::Markdown),
+            Some("html" | "htm") => Ok(ReportFormat::Html),
+            _ => Err(format!(
+                "Cannot tell the report format of {}; use a .md or .html file",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// "Review of October 2026" and the like for the period starting at `start`.
+pub(crate) fn review_title(granularity: Granularity, start: NaiveDate) -> String {
+    let period = match granularity {
+        Granularity::Day => start.format("%Y-%m-%d").to_string(),
+        Granularity::Week => format!("the week of {}", start.format("%Y-%m-%d")),
+        Granularity::Month => start.format("%B %Y").to_string(),
+        Granularity::Quarter => format!("Q{} {}", start.month0() / 3 + 1, start.year()),
+        Granularity::Year => start.format("%Y").to_string(),
+    };
+    format!("Review of {}", period)
+}
+
+impl ReviewReport {
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+        }
+    }
+
+    /// Writes the report to `path` in the format its extension names.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let format = ReportFormat::from_path(path)?;
+        std::fs::write(path, self.render(format))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n{}\n\n## Wheel\n\n", self.title, self.period_text());
+
+        out.push_str("| Area | Score | Previous | Change |\n| --- | --- | --- | --- |\n");
+        for review in &self.areas {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                review.area.name.replace('|', "\\|"),
+                score_text(&review.score),
+                score_text(&review.previous_score),
+                change_text(review.change)
+            );
+        }
+
+        out.push_str("\n## Largest changes\n\n");
+        let changes = self.largest_changes();
+        if changes.is_empty() {
+            out.push_str("No score changed.\n");
+        }
+        for review in changes {
+            let _ = writeln!(out, "- {}", change_line(review));
+        }
+
+        for review in &self.areas {
+            let _ = write!(out, "\n## {}\n\n", review.area.name);
+            match &review.score {
+                Some(score) => {
+                    let _ = writeln!(
+                        out,
+                        "Score {} ({}).",
+                        score.value,
+                        change_text(review.change)
+                    );
+                }
+                None => out.push_str("Not scored yet.\n"),
+            }
+
+            if !review.notes.is_empty() {
+                out.push_str("\n### Notes\n\n");
+                for score in &review.notes {
+                    let _ = writeln!(out, "- {}", note_line(score));
+                }
+            }
+            if !review.completed.is_empty() {
+                out.push_str("\n### Completed actions\n\n");
+                for item in &review.completed {
+                    let _ = writeln!(out, "- [x] {}", item.title);
+                }
+            }
+            if !review.open.is_empty() {
+                out.push_str("\n### Open actions\n\n");
+                for item in &review.open {
+                    let _ = writeln!(out, "- [ ] {}", action_line(item));
+                }
+            }
+        }
+
+        out
+    }
+
+    /// A single HTML file with its styles and the wheel inlined.
+    pub fn to_html(&self) -> String {
+        let title = escape_xml(&self.title);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n<p class=\"period\">{}</p>\n",
+            escape_xml(&self.period_text())
+        );
+
+        let wheel: Vec<WheelEntry> = self
+            .areas
+            .iter()
+            .map(|review| WheelEntry {
+                area: review.area.clone(),
+                score: review.score.clone(),
+            })
+            .collect();
+        let _ = write!(
+            out,
+            "<figure>\n{}</figure>\n",
+            wheel_svg(
+                &wheel,
+                &WheelImageOptions {
+                    size: HTML_WHEEL_SIZE,
+                    ..Default::default()
+                }
+            )
+        );
+
+        out.push_str(
+            "<table>\n<tr><th>Area</th><th>Score</th><th>Previous</th><th>Change</th></tr>\n",
+        );
+        for review in &self.areas {
+            let _ = writeln!(
+                out,
+                "<tr><td><span class=\"swatch\" style=\"background: {}\"></span>{}</td>\
+                 <td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_xml(&review.area.color),
+                escape_xml(&review.area.name),
+                score_text(&review.score),
+                score_text(&review.previous_score),
+                change_text(review.change)
+            );
+        }
+        out.push_str("</table>\n<h2>Largest changes</h2>\n");
+
+        let changes = self.largest_changes();
+        if changes.is_empty() {
+            out.push_str("<p>No score changed.</p>\n");
+        } else {
+            out.push_str("<ul>\n");
+            for review in changes {
+                let _ = writeln!(out, "<li>{}</li>", escape_xml(&change_line(review)));
+            }
+            out.push_str("</ul>\n");
+        }
+
+        for review in &self.areas {
+            let _ = write!(
+                out,
+                "<section>\n<h2><span class=\"swatch\" style=\"background: {}\"></span>{}</h2>\n",
+                escape_xml(&review.area.color),
+                escape_xml(&review.area.name)
+            );
+            match &review.score {
+                Some(score) => {
+                    let _ = writeln!(
+                        out,
+                        "<p>Score {} ({}).</p>",
+                        score.value,
+                        change_text(review.change)
+                    );
+                }
+                None => out.push_str("<p>Not scored yet.</p>\n"),
+            }
+
+            if !review.notes.is_empty() {
+                out.push_str("<h3>Notes</h3>\n<ul>\n");
+                for score in &review.notes {
+                    let _ = writeln!(out, "<li>{}</li>", escape_xml(&note_line(score)));
+                }
+                out.push_str("</ul>\n");
+            }
+            if !review.completed.is_empty() {
+                out.push_str("<h3>Completed actions</h3>\n<ul class=\"actions\">\n");
+                for item in &review.completed {
+                    let _ = writeln!(out, "<li>&#9745; {}</li>", escape_xml(&item.title));
+                }
+                out.push_str("</ul>\n");
+            }
+            if !review.open.is_empty() {
+                out.push_str("<h3>Open actions</h3>\n<ul class=\"actions\">\n");
+                for item in &review.open {
+                    let _ = writeln!(out, "<li>&#9744; {}</li>", escape_xml(&action_line(item)));
+                }
+                out.push_str("</ul>\n");
+            }
+            out.push_str("</section>\n");
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn period_text(&self) -> String {
+        format!(
+            "{} to {}, compared with {} to {}.",
+            format_local_date(self.period_start),
+            format_local_date(self.period_end),
+            format_local_date(self.previous_start),
+            format_local_date(self.period_start - 1)
+        )
+    }
+
+    /// Areas whose score changed the most, in either direction.
+    fn largest_changes(&self) -> Vec<&AreaReview> {
+        let mut changed: Vec<&AreaReview> = self
+            .areas
+            .iter()
+            .filter(|review| review.change.is_some_and(|change| change != 0))
+            .collect();
+        changed.sort_by_key(|review| std::cmp::Reverse(review.change.map(i64::abs)));
+        changed.truncate(LARGEST_CHANGES);
+        changed
+    }
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; color: #1f2937; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; }
+.period { color: #6b7280; }
+figure { text-align: center; margin: 1rem 0; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.35rem 0.5rem; border-bottom: 1px solid #e5e7eb; }
+.swatch { display: inline-block; width: 0.75em; height: 0.75em; border-radius: 50%; margin-right: 0.4em; }
+.actions { list-style: none; padding-left: 0.5rem; }
+section { margin-top: 1.5rem; }
+";
+
+fn score_text(score: &Option<Score>) -> String {
+    score
+        .as_ref()
+        .map_or("-".to_string(), |score| score.value.to_string())
+}
+
+fn change_text(change: Option<i64>) -> String {
+    match change {
+        Some(change) if change > 0 => format!("+{}", change),
+        Some(change) => change.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn change_line(review: &AreaReview) -> String {
+    format!(
+        "{}: {} to {} ({})",
+        review.area.name,
+        score_text(&review.previous_score),
+        score_text(&review.score),
+        change_text(review.change)
+    )
+}
+
+fn note_line(score: &Score) -> String {
+    format!(
+        "{} ({}): {}",
+        format_local_date(score.recorded_at),
+        score.value,
+        score.note.as_deref().unwrap_or_default()
+    )
+}
+
+fn action_line(item: &ActionItem) -> String {
+    match item.due_at {
+        Some(due_at) => format!("{} (due {})", item.title, format_local_date(due_at)),
+        None => item.title.clone(),
+    }
+}
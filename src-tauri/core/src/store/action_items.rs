@@ -0,0 +1,478 @@
+use super::{sql_placeholders, Store};
+use crate::audit::{self, Operation};
+use crate::models::{ActionItem, ReorderUpdate, TagFilter};
+use crate::recurrence::RecurrenceRule;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use rusqlite::{params, params_from_iter, Connection, Params, Row};
+use std::collections::HashMap;
+
+const MAX_ACTION_ITEM_TITLE_LENGTH: usize = 80;
+const MAX_ACTION_ITEM_DESCRIPTION_LENGTH: usize = 10_000;
+
+impl Store {
+    pub fn create_action_item(
+        &self,
+        area_id: i64,
+        title: String,
+        description: Option<String>,
+    ) -> Result<ActionItem, String> {
+        let clean_title = normalize_action_item_title(&title)?;
+        let clean_description = normalize_action_item_description(description)?;
+
+        self.with_operation("create_action_item", |conn, op| {
+            let now = Utc::now().timestamp();
+            let next_position: i64 = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(position), -1) + 1 FROM action_items",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+
+            conn.execute(
+                "INSERT INTO action_items (area_id, title, created_at, position, description)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![area_id, clean_title, now, next_position, clean_description],
+            )
+            .map_err(|e| format!("Failed to insert action item: {}", e))?;
+
+            let id = conn.last_insert_rowid();
+            op.created(conn, "action_items", "id = ?", params![id])?;
+
+            Ok(ActionItem {
+                id,
+                area_id,
+                title: clean_title,
+                created_at: now,
+                position: next_position,
+                archived_at: None,
+                description: clean_description,
+                due_at: None,
+                recurrence: None,
+            })
+        })
+    }
+
+    pub fn get_action_items_by_area(&self, area_id: i64) -> Result<Vec<ActionItem>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, area_id, title, created_at, position, archived_at, description, due_at, recurrence
+             FROM action_items
+             WHERE area_id = ? AND archived_at IS NULL
+             ORDER BY position ASC, created_at ASC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![area_id], action_item_from_row)
+            .map_err(|e| format!("Failed to query action items: {}", e))?;
+
+        let mut result = Vec::new();
+        for item in rows {
+            result.push(item.map_err(|e| format!("Failed to parse action item: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_all_action_items(
+        &self,
+        area_filter: Option<i64>,
+        tag_filter: Option<TagFilter>,
+    ) -> Result<Vec<ActionItem>, String> {
+        let conn = &self.conn;
+
+        let mut query = String::from(
+            "SELECT id, area_id, title, created_at, position, archived_at, description, due_at, recurrence
+             FROM action_items
+             WHERE archived_at IS NULL",
+        );
+        let mut query_params: Vec<i64> = Vec::new();
+
+        if let Some(area_id) = area_filter {
+            query.push_str(" AND area_id = ?");
+            query_params.push(area_id);
+        }
+
+        if let Some(filter) = tag_filter {
+            for tag_id in filter.all_of {
+                query.push_str(
+                    " AND EXISTS (SELECT 1 FROM action_item_tags ait
+                      WHERE ait.action_item_id = action_items.id AND ait.tag_id = ?)",
+                );
+                query_params.push(tag_id);
+            }
+
+            if !filter.any_of.is_empty() {
+                query.push_str(&format!(
+                    " AND EXISTS (SELECT 1 FROM action_item_tags ait
+                      WHERE ait.action_item_id = action_items.id AND ait.tag_id IN ({}))",
+                    sql_placeholders(filter.any_of.len())
+                ));
+                query_params.extend(filter.any_of);
+            }
+
+            if !filter.none_of.is_empty() {
+                query.push_str(&format!(
+                    " AND NOT EXISTS (SELECT 1 FROM action_item_tags ait
+                      WHERE ait.action_item_id = action_items.id AND ait.tag_id IN ({}))",
+                    sql_placeholders(filter.none_of.len())
+                ));
+                query_params.extend(filter.none_of);
+            }
+        }
+
+        query.push_str(" ORDER BY position ASC, created_at ASC");
+
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params_from_iter(query_params), action_item_from_row)
+            .map_err(|e| format!("Failed to query action items: {}", e))?;
+
+        let mut result = Vec::new();
+        for item in rows {
+            result.push(item.map_err(|e| format!("Failed to parse action item: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn update_action_item(&self, id: i64, title: String) -> Result<ActionItem, String> {
+        let clean_title = normalize_action_item_title(&title)?;
+
+        self.with_operation("update_action_item", |conn, op| {
+            let before = audit::snapshot(conn, "action_items", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE action_items SET title = ?1 WHERE id = ?2",
+                params![clean_title, id],
+            )
+            .map_err(|e| format!("Failed to update action item: {}", e))?;
+            op.updated(conn, "action_items", before)?;
+
+            get_action_item(conn, id)
+        })
+    }
+
+    pub fn update_action_item_area(&self, id: i64, area_id: i64) -> Result<ActionItem, String> {
+        self.with_operation("update_action_item_area", |conn, op| {
+            // Verify area exists
+            conn.query_row(
+                "SELECT id FROM life_areas WHERE id = ? AND is_active = 1",
+                params![area_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|_| "Area not found or inactive".to_string())?;
+
+            let before = audit::snapshot(conn, "action_items", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE action_items SET area_id = ?1 WHERE id = ?2",
+                params![area_id, id],
+            )
+            .map_err(|e| format!("Failed to update action item area: {}", e))?;
+            op.updated(conn, "action_items", before)?;
+
+            get_action_item(conn, id)
+        })
+    }
+
+    pub fn update_action_item_description(
+        &self,
+        id: i64,
+        description: Option<String>,
+    ) -> Result<ActionItem, String> {
+        let clean_description = normalize_action_item_description(description)?;
+
+        self.with_operation("update_action_item_description", |conn, op| {
+            let before = audit::snapshot(conn, "action_items", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE action_items SET description = ?1 WHERE id = ?2",
+                params![clean_description, id],
+            )
+            .map_err(|e| format!("Failed to update action item description: {}", e))?;
+            op.updated(conn, "action_items", before)?;
+
+            get_action_item(conn, id)
+        })
+    }
+
+    pub fn set_action_item_due_date(
+        &self,
+        id: i64,
+        due_at: Option<i64>,
+    ) -> Result<ActionItem, String> {
+        self.with_operation("set_action_item_due_date", |conn, op| {
+            let before = audit::snapshot(conn, "action_items", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE action_items SET due_at = ?1 WHERE id = ?2",
+                params![due_at, id],
+            )
+            .map_err(|e| format!("Failed to update action item due date: {}", e))?;
+            op.updated(conn, "action_items", before)?;
+
+            get_action_item(conn, id)
+        })
+    }
+
+    pub fn set_action_item_recurrence(
+        &self,
+        id: i64,
+        recurrence: Option<String>,
+    ) -> Result<ActionItem, String> {
+        let rule = match recurrence.as_deref().map(str::trim) {
+            Some(rule) if !rule.is_empty() => Some(RecurrenceRule::parse(rule)?.to_rrule()),
+            _ => None,
+        };
+
+        self.with_operation("set_action_item_recurrence", |conn, op| {
+            let before = audit::snapshot(conn, "action_items", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE action_items SET recurrence = ?1 WHERE id = ?2",
+                params![rule, id],
+            )
+            .map_err(|e| format!("Failed to update action item recurrence: {}", e))?;
+            op.updated(conn, "action_items", before)?;
+
+            get_action_item(conn, id)
+        })
+    }
+
+    /// Archives the item. For recurring items the next occurrence is created in
+    /// the same position and returned.
+    pub fn archive_action_item(&self, id: i64) -> Result<Option<ActionItem>, String> {
+        self.with_operation("archive_action_item", |conn, op| {
+            archive_action_item(conn, op, id, Utc::now())
+        })
+    }
+
+    pub fn delete_action_item(&self, id: i64) -> Result<(), String> {
+        self.with_operation("delete_action_item", |conn, op| {
+            trash_action_item_dependents(conn, op, "SELECT ?", params![id])?;
+            op.trash_where(conn, "action_items", "id = ?", params![id])?;
+
+            Ok(())
+        })
+    }
+
+    pub fn reorder_action_items(&self, updates: Vec<ReorderUpdate>) -> Result<(), String> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        self.with_operation("reorder_action_items", |conn, op| {
+            for update in updates {
+                let before = audit::snapshot(conn, "action_items", "id = ?", params![update.id])?;
+                conn.execute(
+                    "UPDATE action_items SET position = ?1 WHERE id = ?2",
+                    params![update.position, update.id],
+                )
+                .map_err(|e| format!("Failed to update action item position: {}", e))?;
+                op.updated(conn, "action_items", before)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+pub(super) fn normalize_action_item_title(title: &str) -> Result<String, String> {
+    let clean_title = title.trim();
+    if clean_title.is_empty() {
+        return Err("Title cannot be empty".to_string());
+    }
+    if clean_title.chars().count() > MAX_ACTION_ITEM_TITLE_LENGTH {
+        return Err(format!(
+            "Title cannot exceed {} characters",
+            MAX_ACTION_ITEM_TITLE_LENGTH
+        ));
+    }
+    Ok(clean_title.to_string())
+}
+
+pub(super) fn normalize_action_item_description(
+    description: Option<String>,
+) -> Result<Option<String>, String> {
+    let Some(description) = description else {
+        return Ok(None);
+    };
+    let clean_description = description.trim();
+    if clean_description.is_empty() {
+        return Ok(None);
+    }
+    if clean_description.chars().count() > MAX_ACTION_ITEM_DESCRIPTION_LENGTH {
+        return Err(format!(
+            "Description cannot exceed {} characters",
+            MAX_ACTION_ITEM_DESCRIPTION_LENGTH
+        ));
+    }
+    Ok(Some(clean_description.to_string()))
+}
+
+fn get_action_item(conn: &Connection, id: i64) -> Result<ActionItem, String> {
+    conn.query_row(
+        "SELECT id, area_id, title, created_at, position, archived_at, description, due_at, recurrence
+         FROM action_items WHERE id = ?",
+        params![id],
+        action_item_from_row,
+    )
+    .map_err(|e| format!("Failed to get action item: {}", e))
+}
+
+/// Archives the item as of `now` within `op`, creating the next occurrence of
+/// recurring items.
+pub(super) fn archive_action_item(
+    conn: &Connection,
+    op: &Operation,
+    id: i64,
+    now: DateTime<Utc>,
+) -> Result<Option<ActionItem>, String> {
+    let item = get_action_item(conn, id)?;
+
+    let before = audit::snapshot(conn, "action_items", "id = ?", params![id])?;
+    conn.execute(
+        "UPDATE action_items SET archived_at = ?1 WHERE id = ?2",
+        params![now.timestamp(), id],
+    )
+    .map_err(|e| format!("Failed to archive action item: {}", e))?;
+    op.updated(conn, "action_items", before)?;
+
+    match (&item.recurrence, item.archived_at) {
+        (Some(rule), None) => Ok(Some(spawn_next_occurrence(conn, op, &item, rule, now)?)),
+        _ => Ok(None),
+    }
+}
+
+/// Clears the archived date of the item within `op`.
+pub(super) fn reopen_action_item(conn: &Connection, op: &Operation, id: i64) -> Result<(), String> {
+    let before = audit::snapshot(conn, "action_items", "id = ?", params![id])?;
+    conn.execute(
+        "UPDATE action_items SET archived_at = NULL WHERE id = ?",
+        params![id],
+    )
+    .map_err(|e| format!("Failed to reopen action item: {}", e))?;
+    op.updated(conn, "action_items", before)
+}
+
+fn spawn_next_occurrence(
+    conn: &Connection,
+    op: &Operation,
+    item: &ActionItem,
+    rule: &str,
+    now: DateTime<Utc>,
+) -> Result<ActionItem, String> {
+    let now_local = now.with_timezone(&Local);
+    let base = item
+        .due_at
+        .and_then(|due_at| Local.timestamp_opt(due_at, 0).single())
+        .unwrap_or(now_local);
+    let rule = RecurrenceRule::parse(rule)?.anchored_to(&base);
+    let next_due = rule
+        .next_occurrence(base, now_local)
+        .ok_or("Failed to compute next occurrence")?;
+
+    conn.execute(
+        "INSERT INTO action_items (area_id, title, created_at, position, description, due_at, recurrence)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            item.area_id,
+            item.title,
+            now.timestamp(),
+            item.position,
+            item.description,
+            next_due.timestamp(),
+            rule.to_rrule()
+        ],
+    )
+    .map_err(|e| format!("Failed to insert next occurrence: {}", e))?;
+
+    let next_id = conn.last_insert_rowid();
+
+    conn.execute(
+        "INSERT INTO action_item_tags (action_item_id, tag_id)
+         SELECT ?1, tag_id FROM action_item_tags WHERE action_item_id = ?2",
+        params![next_id, item.id],
+    )
+    .map_err(|e| format!("Failed to copy tags: {}", e))?;
+
+    conn.execute(
+        "INSERT INTO action_item_links (action_item_id, url, label, position, created_at)
+         SELECT ?1, url, label, position, ?3 FROM action_item_links WHERE action_item_id = ?2",
+        params![next_id, item.id, now.timestamp()],
+    )
+    .map_err(|e| format!("Failed to copy links: {}", e))?;
+
+    // Steps are copied one by one, parents first, so nested steps can point
+    // at the copies of their parents.
+    let steps: Vec<(i64, String, i64, Option<i64>)> = conn
+        .prepare(
+            "SELECT id, title, position, parent_id FROM action_item_steps
+             WHERE action_item_id = ? ORDER BY id",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![item.id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect()
+        })
+        .map_err(|e| format!("Failed to load steps: {}", e))?;
+    let mut copied_steps = HashMap::new();
+    for (step_id, title, position, parent_id) in steps {
+        conn.execute(
+            "INSERT INTO action_item_steps (action_item_id, title, is_done, position, created_at, parent_id)
+             VALUES (?1, ?2, 0, ?3, ?4, ?5)",
+            params![
+                next_id,
+                title,
+                position,
+                now.timestamp(),
+                parent_id.and_then(|parent_id| copied_steps.get(&parent_id).copied())
+            ],
+        )
+        .map_err(|e| format!("Failed to copy steps: {}", e))?;
+        copied_steps.insert(step_id, conn.last_insert_rowid());
+    }
+
+    op.created(conn, "action_items", "id = ?", params![next_id])?;
+    for table in ["action_item_tags", "action_item_links", "action_item_steps"] {
+        op.created(conn, table, "action_item_id = ?", params![next_id])?;
+    }
+
+    get_action_item(conn, next_id)
+}
+
+/// Moves the tags, links and checklist steps of the action items selected by
+/// `items_query`, which must be a `SELECT` returning action item ids, to the trash.
+pub(super) fn trash_action_item_dependents<P: Params + Copy>(
+    conn: &Connection,
+    op: &Operation,
+    items_query: &str,
+    query_params: P,
+) -> Result<(), String> {
+    for table in ["action_item_tags", "action_item_links", "action_item_steps"] {
+        op.trash_where(
+            conn,
+            table,
+            &format!("action_item_id IN ({})", items_query),
+            query_params,
+        )?;
+    }
+
+    Ok(())
+}
+
+pub(super) fn action_item_from_row(row: &Row) -> rusqlite::Result<ActionItem> {
+    Ok(ActionItem {
+        id: row.get(0)?,
+        area_id: row.get(1)?,
+        title: row.get(2)?,
+        created_at: row.get(3)?,
+        position: row.get(4)?,
+        archived_at: row.get(5)?,
+        description: row.get(6)?,
+        due_at: row.get(7)?,
+        recurrence: row.get(8)?,
+    })
+}
@@ -0,0 +1,197 @@
+use super::Store;
+use crate::audit::{self, Operation};
+use crate::models::LifeArea;
+use chrono::Utc;
+use rusqlite::{params, Connection, Row};
+
+impl Store {
+    pub fn create_life_area(
+        &self,
+        name: String,
+        description: Option<String>,
+        color: String,
+        order: i64,
+    ) -> Result<LifeArea, String> {
+        self.with_operation("create_life_area", |conn, op| {
+            insert_life_area(conn, op, &name, description.as_deref(), &color, order)
+        })
+    }
+
+    pub fn get_life_areas(&self, include_archived: bool) -> Result<Vec<LifeArea>, String> {
+        let conn = &self.conn;
+        let mut stmt = if include_archived {
+            conn.prepare("SELECT id, name, description, color, \"order\", is_active, created_at, updated_at FROM life_areas ORDER BY \"order\", name")
+        } else {
+            conn.prepare("SELECT id, name, description, color, \"order\", is_active, created_at, updated_at FROM life_areas WHERE is_active = 1 ORDER BY \"order\", name")
+        }.map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let areas = stmt
+            .query_map([], life_area_from_row)
+            .map_err(|e| format!("Failed to query life areas: {}", e))?;
+
+        let mut result = Vec::new();
+        for area in areas {
+            result.push(area.map_err(|e| format!("Failed to parse area: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_life_area(&self, id: i64) -> Result<LifeArea, String> {
+        let conn = &self.conn;
+        query_life_area(conn, id)
+    }
+
+    pub fn update_life_area(
+        &self,
+        id: i64,
+        name: String,
+        description: Option<String>,
+        color: String,
+        order: i64,
+    ) -> Result<LifeArea, String> {
+        self.with_operation("update_life_area", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            // Check if name already exists for active areas (excluding current)
+            let mut stmt = conn
+                .prepare("SELECT id FROM life_areas WHERE name = ? AND is_active = 1 AND id != ?")
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            if stmt
+                .exists(params![name, id])
+                .map_err(|e| format!("Failed to check name: {}", e))?
+            {
+                return Err("An active area with this name already exists".to_string());
+            }
+
+            let before = audit::snapshot(conn, "life_areas", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE life_areas SET name = ?1, description = ?2, color = ?3, \"order\" = ?4, updated_at = ?5 WHERE id = ?6",
+                params![name, description, color, order, now, id],
+            ).map_err(|e| format!("Failed to update life area: {}", e))?;
+            op.updated(conn, "life_areas", before)?;
+
+            query_life_area(conn, id)
+        })
+    }
+
+    pub fn soft_delete_life_area(&self, id: i64) -> Result<(), String> {
+        self.with_operation("soft_delete_life_area", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            let before = audit::snapshot(conn, "life_areas", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE life_areas SET is_active = 0, updated_at = ? WHERE id = ?",
+                params![now, id],
+            )
+            .map_err(|e| format!("Failed to delete life area: {}", e))?;
+            op.updated(conn, "life_areas", before)?;
+
+            Ok(())
+        })
+    }
+
+    pub fn restore_life_area(&self, id: i64) -> Result<LifeArea, String> {
+        self.with_operation("restore_life_area", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            // Check if name conflicts with active area
+            let area = query_life_area(conn, id)?;
+            let mut stmt = conn
+                .prepare("SELECT id FROM life_areas WHERE name = ? AND is_active = 1 AND id != ?")
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            if stmt
+                .exists(params![area.name, id])
+                .map_err(|e| format!("Failed to check name: {}", e))?
+            {
+                return Err("An active area with this name already exists".to_string());
+            }
+
+            let before = audit::snapshot(conn, "life_areas", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE life_areas SET is_active = 1, updated_at = ? WHERE id = ?",
+                params![now, id],
+            )
+            .map_err(|e| format!("Failed to restore life area: {}", e))?;
+            op.updated(conn, "life_areas", before)?;
+
+            query_life_area(conn, id)
+        })
+    }
+}
+
+pub(super) fn query_active_life_areas(conn: &Connection) -> Result<Vec<LifeArea>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, name, description, color, \"order\", is_active, created_at, updated_at FROM life_areas WHERE is_active = 1 ORDER BY \"order\", name")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let areas = stmt
+        .query_map([], life_area_from_row)
+        .map_err(|e| format!("Failed to query life areas: {}", e))?;
+
+    let mut result = Vec::new();
+    for area in areas {
+        result.push(area.map_err(|e| format!("Failed to parse area: {}", e))?);
+    }
+
+    Ok(result)
+}
+
+/// Adds an active area within `op`, unless an active area already has the name.
+pub(super) fn insert_life_area(
+    conn: &Connection,
+    op: &Operation,
+    name: &str,
+    description: Option<&str>,
+    color: &str,
+    order: i64,
+) -> Result<LifeArea, String> {
+    let now = Utc::now().timestamp();
+
+    // Check if name already exists for active areas
+    let mut stmt = conn
+        .prepare("SELECT id FROM life_areas WHERE name = ? AND is_active = 1")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    if stmt
+        .exists(params![name])
+        .map_err(|e| format!("Failed to check name: {}", e))?
+    {
+        return Err("An active area with this name already exists".to_string());
+    }
+
+    conn.execute(
+        "INSERT INTO life_areas (name, description, color, \"order\", is_active, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, 1, ?5, ?5)",
+        params![name, description, color, order, now],
+    )
+    .map_err(|e| format!("Failed to insert life area: {}", e))?;
+
+    let id = conn.last_insert_rowid();
+    op.created(conn, "life_areas", "id = ?", params![id])?;
+
+    query_life_area(conn, id)
+}
+
+pub(super) fn query_life_area(conn: &Connection, id: i64) -> Result<LifeArea, String> {
+    conn.query_row(
+        "SELECT id, name, description, color, \"order\", is_active, created_at, updated_at FROM life_areas WHERE id = ?",
+        params![id],
+        life_area_from_row,
+    ).map_err(|e| format!("Failed to get life area: {}", e))
+}
+
+pub(super) fn life_area_from_row(row: &Row) -> rusqlite::Result<LifeArea> {
+    Ok(LifeArea {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        color: row.get(3)?,
+        order: row.get(4)?,
+        is_active: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
@@ -0,0 +1,146 @@
+use super::action_items::{normalize_action_item_description, normalize_action_item_title};
+use super::areas::{insert_life_area, query_active_life_areas};
+use super::Store;
+use crate::csv_import::{read_csv, CsvColumnMapping, CsvImportKind, CsvImportPreview, CsvRowError};
+use crate::templates::area_color;
+use chrono::Utc;
+use rusqlite::params;
+use std::collections::HashMap;
+
+impl Store {
+    /// Imports the rows of a CSV file as scores or action items, in one
+    /// operation. Areas are matched to active areas by name, ignoring case, and
+    /// created when none matches. Rows with errors are left out. With
+    /// `dry_run` nothing is written and the preview of the import is returned.
+    pub fn import_csv(
+        &self,
+        contents: &str,
+        mapping: &CsvColumnMapping,
+        dry_run: bool,
+    ) -> Result<CsvImportPreview, String> {
+        let rows = read_csv(contents, mapping)?;
+
+        self.with_operation("import_csv", |conn, op| {
+            let areas = query_active_life_areas(conn)?;
+            let mut area_ids: HashMap<String, Option<i64>> = areas
+                .iter()
+                .map(|area| (area.name.to_lowercase(), Some(area.id)))
+                .collect();
+            let mut preview = CsvImportPreview {
+                dry_run,
+                ..CsvImportPreview::default()
+            };
+
+            for row in rows {
+                let mut row = match row {
+                    Ok(row) => row,
+                    Err(error) => {
+                        preview.errors.push(error);
+                        continue;
+                    }
+                };
+
+                if mapping.kind == CsvImportKind::ActionItems {
+                    let cleaned = normalize_action_item_title(row.title.as_deref().unwrap_or(""))
+                        .and_then(|title| {
+                            Ok((title, normalize_action_item_description(row.note.clone())?))
+                        });
+                    match cleaned {
+                        Ok((title, note)) => {
+                            row.title = Some(title);
+                            row.note = note;
+                        }
+                        Err(message) => {
+                            preview.errors.push(CsvRowError {
+                                line: row.line,
+                                message,
+                            });
+                            continue;
+                        }
+                    }
+                }
+
+                area_ids
+                    .entry(row.area.to_lowercase())
+                    .or_insert_with(|| {
+                        preview.new_areas.push(row.area.clone());
+                        None
+                    });
+                preview.rows.push(row);
+            }
+
+            if dry_run {
+                return Ok(preview);
+            }
+
+            let next_order: i64 = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(\"order\"), -1) + 1 FROM life_areas WHERE is_active = 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("Failed to query area order: {}", e))?;
+            for (index, name) in preview.new_areas.iter().enumerate() {
+                let area = insert_life_area(
+                    conn,
+                    op,
+                    name,
+                    None,
+                    area_color(areas.len() + index),
+                    next_order + index as i64,
+                )?;
+                area_ids.insert(name.to_lowercase(), Some(area.id));
+            }
+
+            let now = Utc::now().timestamp();
+            let mut next_position: i64 = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(position), -1) + 1 FROM action_items",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+            for row in &preview.rows {
+                let area_id = area_ids
+                    .get(&row.area.to_lowercase())
+                    .copied()
+                    .flatten()
+                    .ok_or("Area not found")?;
+
+                match mapping.kind {
+                    CsvImportKind::Scores => {
+                        conn.execute(
+                            "INSERT INTO scores (area_id, value, recorded_at, note) VALUES (?1, ?2, ?3, ?4)",
+                            params![area_id, row.value, row.date.unwrap_or(now), row.note],
+                        )
+                        .map_err(|e| format!("Failed to insert score: {}", e))?;
+                        op.created(conn, "scores", "id = ?", params![conn.last_insert_rowid()])?;
+                    }
+                    CsvImportKind::ActionItems => {
+                        conn.execute(
+                            "INSERT INTO action_items (area_id, title, created_at, position, description)
+                             VALUES (?1, ?2, ?3, ?4, ?5)",
+                            params![
+                                area_id,
+                                row.title,
+                                row.date.unwrap_or(now),
+                                next_position,
+                                row.note
+                            ],
+                        )
+                        .map_err(|e| format!("Failed to insert action item: {}", e))?;
+                        op.created(
+                            conn,
+                            "action_items",
+                            "id = ?",
+                            params![conn.last_insert_rowid()],
+                        )?;
+                        next_position += 1;
+                    }
+                }
+            }
+
+            Ok(preview)
+        })
+    }
+}
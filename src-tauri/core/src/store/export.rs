@@ -0,0 +1,106 @@
+use super::action_items::action_item_from_row;
+use super::areas::life_area_from_row;
+use super::goals::goal_from_row;
+use super::habits::{habit_checkin_from_row, habit_from_row};
+use super::links::action_item_link_from_row;
+use super::scores::score_from_row;
+use super::steps::action_item_step_from_row;
+use super::tags::tag_from_row;
+use super::Store;
+use crate::models::{ActionItemTag, WheelExport};
+use chrono::Utc;
+use rusqlite::{Connection, Row};
+
+/// Bumped whenever the shape of `WheelExport` changes incompatibly.
+pub const EXPORT_VERSION: i64 = 1;
+
+impl Store {
+    /// A snapshot of every row, archived and closed ones included, for backups
+    /// and other tools.
+    pub fn export(&self) -> Result<WheelExport, String> {
+        let conn = &self.conn;
+
+        Ok(WheelExport {
+            version: EXPORT_VERSION,
+            exported_at: Utc::now().timestamp(),
+            life_areas: query_all(
+                conn,
+                "SELECT id, name, description, color, \"order\", is_active, created_at, updated_at
+                 FROM life_areas ORDER BY \"order\", name",
+                life_area_from_row,
+            )?,
+            scores: query_all(
+                conn,
+                "SELECT id, area_id, value, recorded_at, note FROM scores ORDER BY recorded_at, id",
+                score_from_row,
+            )?,
+            action_items: query_all(
+                conn,
+                "SELECT id, area_id, title, created_at, position, archived_at, description, due_at, recurrence
+                 FROM action_items ORDER BY position, created_at",
+                action_item_from_row,
+            )?,
+            action_item_links: query_all(
+                conn,
+                "SELECT id, action_item_id, url, label, position, created_at
+                 FROM action_item_links ORDER BY action_item_id, position",
+                action_item_link_from_row,
+            )?,
+            action_item_steps: query_all(
+                conn,
+                "SELECT id, action_item_id, title, is_done, position, created_at, parent_id
+                 FROM action_item_steps ORDER BY action_item_id, id",
+                action_item_step_from_row,
+            )?,
+            tags: query_all(
+                conn,
+                "SELECT id, name, created_at FROM tags ORDER BY name",
+                tag_from_row,
+            )?,
+            action_item_tags: query_all(
+                conn,
+                "SELECT action_item_id, tag_id FROM action_item_tags ORDER BY action_item_id, tag_id",
+                |row| {
+                    Ok(ActionItemTag {
+                        action_item_id: row.get(0)?,
+                        tag_id: row.get(1)?,
+                    })
+                },
+            )?,
+            habits: query_all(
+                conn,
+                "SELECT id, area_id, name, frequency, target_count, created_at, archived_at
+                 FROM habits ORDER BY created_at",
+                habit_from_row,
+            )?,
+            habit_checkins: query_all(
+                conn,
+                "SELECT id, habit_id, checked_at, note FROM habit_checkins ORDER BY checked_at",
+                habit_checkin_from_row,
+            )?,
+            goals: query_all(
+                conn,
+                "SELECT id, area_id, target_score, target_date, rationale, status, created_at, updated_at, closed_at
+                 FROM goals ORDER BY target_date",
+                goal_from_row,
+            )?,
+        })
+    }
+}
+
+fn query_all<T>(
+    conn: &Connection,
+    sql: &str,
+    from_row: impl FnMut(&Row) -> rusqlite::Result<T>,
+) -> Result<Vec<T>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let rows = stmt
+        .query_map([], from_row)
+        .map_err(|e| format!("Failed to query export: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to parse export row: {}", e))
+}
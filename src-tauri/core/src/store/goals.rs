@@ -0,0 +1,273 @@
+use super::Store;
+use crate::audit;
+use crate::goals::{self, GoalProgress};
+use crate::models::Goal;
+use chrono::Utc;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+
+const MAX_GOAL_RATIONALE_LENGTH: usize = 2000;
+const GOAL_TREND_WINDOW_DAYS: i64 = 90;
+
+impl Store {
+    pub fn get_goal(&self, id: i64) -> Result<Goal, String> {
+        let conn = &self.conn;
+        query_goal(conn, id)
+    }
+
+    pub fn create_goal(
+        &self,
+        area_id: i64,
+        target_score: i64,
+        target_date: i64,
+        rationale: Option<String>,
+    ) -> Result<Goal, String> {
+        let clean_rationale = validate_goal(target_score, target_date, rationale)?;
+
+        self.with_operation("create_goal", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            // Verify area exists
+            conn.query_row(
+                "SELECT id FROM life_areas WHERE id = ? AND is_active = 1",
+                params![area_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|_| "Area not found or inactive".to_string())?;
+
+            conn.execute(
+                "INSERT INTO goals (area_id, target_score, target_date, rationale, status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
+                params![
+                    area_id,
+                    target_score,
+                    target_date,
+                    clean_rationale,
+                    goals::STATUS_OPEN,
+                    now
+                ],
+            )
+            .map_err(|e| format!("Failed to insert goal: {}", e))?;
+
+            let id = conn.last_insert_rowid();
+            op.created(conn, "goals", "id = ?", params![id])?;
+
+            query_goal(conn, id)
+        })
+    }
+
+    pub fn get_goals(
+        &self,
+        area_id: Option<i64>,
+        include_closed: bool,
+    ) -> Result<Vec<Goal>, String> {
+        let conn = &self.conn;
+
+        let mut query = String::from(
+            "SELECT id, area_id, target_score, target_date, rationale, status, created_at, updated_at, closed_at
+             FROM goals
+             WHERE 1 = 1",
+        );
+        let mut query_params: Vec<i64> = Vec::new();
+
+        if let Some(area_id) = area_id {
+            query.push_str(" AND area_id = ?");
+            query_params.push(area_id);
+        }
+        if !include_closed {
+            query.push_str(" AND closed_at IS NULL");
+        }
+        query.push_str(" ORDER BY target_date ASC, created_at ASC");
+
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params_from_iter(query_params), goal_from_row)
+            .map_err(|e| format!("Failed to query goals: {}", e))?;
+
+        let mut result = Vec::new();
+        for goal in rows {
+            result.push(goal.map_err(|e| format!("Failed to parse goal: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn update_goal(
+        &self,
+        id: i64,
+        target_score: i64,
+        target_date: i64,
+        rationale: Option<String>,
+    ) -> Result<Goal, String> {
+        let clean_rationale = validate_goal(target_score, target_date, rationale)?;
+
+        self.with_operation("update_goal", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            let before = audit::snapshot(conn, "goals", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE goals SET target_score = ?1, target_date = ?2, rationale = ?3, updated_at = ?4 WHERE id = ?5",
+                params![target_score, target_date, clean_rationale, now, id],
+            )
+            .map_err(|e| format!("Failed to update goal: {}", e))?;
+            op.updated(conn, "goals", before)?;
+
+            query_goal(conn, id)
+        })
+    }
+
+    pub fn close_goal(&self, id: i64, status: String) -> Result<Goal, String> {
+        let status = goals::parse_close_status(&status)?;
+
+        self.with_operation("close_goal", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            let before = audit::snapshot(conn, "goals", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE goals SET status = ?1, closed_at = ?2, updated_at = ?2 WHERE id = ?3",
+                params![status, now, id],
+            )
+            .map_err(|e| format!("Failed to close goal: {}", e))?;
+            op.updated(conn, "goals", before)?;
+
+            query_goal(conn, id)
+        })
+    }
+
+    pub fn reopen_goal(&self, id: i64) -> Result<Goal, String> {
+        self.with_operation("reopen_goal", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            let before = audit::snapshot(conn, "goals", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE goals SET status = ?1, closed_at = NULL, updated_at = ?2 WHERE id = ?3",
+                params![goals::STATUS_OPEN, now, id],
+            )
+            .map_err(|e| format!("Failed to reopen goal: {}", e))?;
+            op.updated(conn, "goals", before)?;
+
+            query_goal(conn, id)
+        })
+    }
+
+    pub fn delete_goal(&self, id: i64) -> Result<(), String> {
+        self.with_operation("delete_goal", |conn, op| {
+            op.delete_where(conn, "goals", "id = ?", params![id])?;
+            Ok(())
+        })
+    }
+
+    pub fn get_goal_progress(&self, id: i64) -> Result<GoalProgress, String> {
+        let goal = self.get_goal(id)?;
+        let conn = &self.conn;
+        let now = Utc::now().timestamp();
+
+        let baseline_before: Option<i64> = conn
+            .query_row(
+                "SELECT value FROM scores WHERE area_id = ?1 AND recorded_at <= ?2
+                 ORDER BY recorded_at DESC LIMIT 1",
+                params![goal.area_id, goal.created_at],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to query baseline score: {}", e))?;
+
+        let baseline_score = match baseline_before {
+            Some(value) => Some(value),
+            None => conn
+                .query_row(
+                    "SELECT value FROM scores WHERE area_id = ?1 AND recorded_at > ?2
+                     ORDER BY recorded_at ASC LIMIT 1",
+                    params![goal.area_id, goal.created_at],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to query baseline score: {}", e))?,
+        };
+
+        let current_score = self
+            .get_latest_score(goal.area_id)?
+            .map(|score| score.value);
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT recorded_at, value FROM scores
+                 WHERE area_id = ?1 AND recorded_at >= ?2
+                 ORDER BY recorded_at ASC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(
+                params![goal.area_id, now - GOAL_TREND_WINDOW_DAYS * 86_400],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .map_err(|e| format!("Failed to query scores: {}", e))?;
+
+        let mut recent_points = Vec::new();
+        for point in rows {
+            recent_points.push(point.map_err(|e| format!("Failed to parse score: {}", e))?);
+        }
+
+        Ok(goals::compute_progress(
+            goal.id,
+            goal.target_score,
+            goal.target_date,
+            baseline_score,
+            current_score,
+            &recent_points,
+            now,
+        ))
+    }
+}
+
+fn validate_goal(
+    target_score: i64,
+    target_date: i64,
+    rationale: Option<String>,
+) -> Result<Option<String>, String> {
+    if !(0..=10).contains(&target_score) {
+        return Err("Target score must be between 0 and 10".to_string());
+    }
+    if target_date <= 0 {
+        return Err("Target date is required".to_string());
+    }
+    let clean_rationale = rationale
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    if let Some(rationale) = &clean_rationale {
+        if rationale.chars().count() > MAX_GOAL_RATIONALE_LENGTH {
+            return Err(format!(
+                "Rationale cannot exceed {} characters",
+                MAX_GOAL_RATIONALE_LENGTH
+            ));
+        }
+    }
+    Ok(clean_rationale)
+}
+
+fn query_goal(conn: &Connection, id: i64) -> Result<Goal, String> {
+    conn.query_row(
+        "SELECT id, area_id, target_score, target_date, rationale, status, created_at, updated_at, closed_at
+         FROM goals WHERE id = ?",
+        params![id],
+        goal_from_row,
+    )
+    .map_err(|e| format!("Failed to get goal: {}", e))
+}
+
+pub(super) fn goal_from_row(row: &Row) -> rusqlite::Result<Goal> {
+    Ok(Goal {
+        id: row.get(0)?,
+        area_id: row.get(1)?,
+        target_score: row.get(2)?,
+        target_date: row.get(3)?,
+        rationale: row.get(4)?,
+        status: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        closed_at: row.get(8)?,
+    })
+}
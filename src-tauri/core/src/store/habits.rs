@@ -0,0 +1,345 @@
+use super::Store;
+use crate::audit;
+use crate::habits::{self, HabitFrequency, HabitStats, HeatmapDay};
+use crate::models::{Habit, HabitCheckin};
+use chrono::{Local, NaiveDate, Utc};
+use rusqlite::{params, params_from_iter, Connection, Row};
+
+const MAX_HABIT_NAME_LENGTH: usize = 80;
+const MAX_HABIT_TARGET_COUNT: i64 = 100;
+const DEFAULT_HABIT_STATS_WEEKS: i64 = 12;
+const MAX_HABIT_STATS_WEEKS: i64 = 104;
+const MAX_HABIT_HEATMAP_DAYS: i64 = 3 * 366;
+
+impl Store {
+    pub fn create_habit(
+        &self,
+        area_id: i64,
+        name: String,
+        frequency: String,
+        target_count: i64,
+    ) -> Result<Habit, String> {
+        let (clean_name, frequency) = validate_habit(&name, &frequency, target_count)?;
+
+        self.with_operation("create_habit", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            // Verify area exists
+            conn.query_row(
+                "SELECT id FROM life_areas WHERE id = ? AND is_active = 1",
+                params![area_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|_| "Area not found or inactive".to_string())?;
+
+            conn.execute(
+                "INSERT INTO habits (area_id, name, frequency, target_count, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![area_id, clean_name, frequency.as_str(), target_count, now],
+            )
+            .map_err(|e| format!("Failed to insert habit: {}", e))?;
+
+            let id = conn.last_insert_rowid();
+            op.created(conn, "habits", "id = ?", params![id])?;
+
+            Ok(Habit {
+                id,
+                area_id,
+                name: clean_name,
+                frequency: frequency.as_str().to_string(),
+                target_count,
+                created_at: now,
+                archived_at: None,
+            })
+        })
+    }
+
+    pub fn get_habits(
+        &self,
+        area_id: Option<i64>,
+        include_archived: bool,
+    ) -> Result<Vec<Habit>, String> {
+        let conn = &self.conn;
+
+        let mut query = String::from(
+            "SELECT id, area_id, name, frequency, target_count, created_at, archived_at
+             FROM habits
+             WHERE 1 = 1",
+        );
+        let mut query_params: Vec<i64> = Vec::new();
+
+        if let Some(area_id) = area_id {
+            query.push_str(" AND area_id = ?");
+            query_params.push(area_id);
+        }
+        if !include_archived {
+            query.push_str(" AND archived_at IS NULL");
+        }
+        query.push_str(" ORDER BY created_at ASC");
+
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params_from_iter(query_params), habit_from_row)
+            .map_err(|e| format!("Failed to query habits: {}", e))?;
+
+        let mut result = Vec::new();
+        for habit in rows {
+            result.push(habit.map_err(|e| format!("Failed to parse habit: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn update_habit(
+        &self,
+        id: i64,
+        name: String,
+        frequency: String,
+        target_count: i64,
+    ) -> Result<Habit, String> {
+        let (clean_name, frequency) = validate_habit(&name, &frequency, target_count)?;
+
+        self.with_operation("update_habit", |conn, op| {
+            let before = audit::snapshot(conn, "habits", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE habits SET name = ?1, frequency = ?2, target_count = ?3 WHERE id = ?4",
+                params![clean_name, frequency.as_str(), target_count, id],
+            )
+            .map_err(|e| format!("Failed to update habit: {}", e))?;
+            op.updated(conn, "habits", before)?;
+
+            get_habit(conn, id)
+        })
+    }
+
+    pub fn archive_habit(&self, id: i64) -> Result<(), String> {
+        self.with_operation("archive_habit", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            let before = audit::snapshot(conn, "habits", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE habits SET archived_at = ?1 WHERE id = ?2",
+                params![now, id],
+            )
+            .map_err(|e| format!("Failed to archive habit: {}", e))?;
+            op.updated(conn, "habits", before)?;
+
+            Ok(())
+        })
+    }
+
+    pub fn delete_habit(&self, id: i64) -> Result<(), String> {
+        self.with_operation("delete_habit", |conn, op| {
+            op.delete_where(conn, "habit_checkins", "habit_id = ?", params![id])?;
+            op.delete_where(conn, "habits", "id = ?", params![id])?;
+
+            Ok(())
+        })
+    }
+
+    pub fn check_in_habit(
+        &self,
+        habit_id: i64,
+        checked_at: Option<i64>,
+        note: Option<String>,
+    ) -> Result<HabitCheckin, String> {
+        let checked_at = checked_at.unwrap_or_else(|| Utc::now().timestamp());
+        let clean_note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+        self.with_operation("check_in_habit", |conn, op| {
+            let habit = get_habit(conn, habit_id)?;
+            if habit.archived_at.is_some() {
+                return Err("Cannot check in to an archived habit".to_string());
+            }
+
+            conn.execute(
+                "INSERT INTO habit_checkins (habit_id, checked_at, note) VALUES (?1, ?2, ?3)",
+                params![habit_id, checked_at, clean_note],
+            )
+            .map_err(|e| format!("Failed to insert check-in: {}", e))?;
+
+            let id = conn.last_insert_rowid();
+            op.created(conn, "habit_checkins", "id = ?", params![id])?;
+
+            Ok(HabitCheckin {
+                id,
+                habit_id,
+                checked_at,
+                note: clean_note,
+            })
+        })
+    }
+
+    pub fn delete_habit_checkin(&self, id: i64) -> Result<(), String> {
+        self.with_operation("delete_habit_checkin", |conn, op| {
+            op.delete_where(conn, "habit_checkins", "id = ?", params![id])?;
+            Ok(())
+        })
+    }
+
+    pub fn get_habit_checkins(
+        &self,
+        habit_id: i64,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Result<Vec<HabitCheckin>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, habit_id, checked_at, note
+             FROM habit_checkins
+             WHERE habit_id = ?1 AND checked_at >= ?2 AND checked_at <= ?3
+             ORDER BY checked_at DESC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(
+                params![habit_id, from.unwrap_or(i64::MIN), to.unwrap_or(i64::MAX)],
+                habit_checkin_from_row,
+            )
+            .map_err(|e| format!("Failed to query check-ins: {}", e))?;
+
+        let mut result = Vec::new();
+        for checkin in rows {
+            result.push(checkin.map_err(|e| format!("Failed to parse check-in: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_habit_stats(&self, habit_id: i64, weeks: Option<i64>) -> Result<HabitStats, String> {
+        let weeks = weeks.unwrap_or(DEFAULT_HABIT_STATS_WEEKS);
+        if !(1..=MAX_HABIT_STATS_WEEKS).contains(&weeks) {
+            return Err(format!(
+                "Weeks must be between 1 and {}",
+                MAX_HABIT_STATS_WEEKS
+            ));
+        }
+
+        let conn = &self.conn;
+        let habit = get_habit(conn, habit_id)?;
+        let frequency = HabitFrequency::parse(&habit.frequency)?;
+        let dates = get_habit_checkin_dates(conn, habit_id)?;
+        let today = Local::now().date_naive();
+
+        let (current_streak, longest_streak) =
+            habits::compute_streaks(&dates, frequency, habit.target_count, today);
+
+        Ok(HabitStats {
+            habit_id,
+            current_streak,
+            longest_streak,
+            total_checkins: dates.len() as i64,
+            weekly_completion: habits::weekly_completion(
+                &dates,
+                frequency,
+                habit.target_count,
+                weeks,
+                today,
+            ),
+        })
+    }
+
+    pub fn get_habit_heatmap(
+        &self,
+        habit_id: i64,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<HeatmapDay>, String> {
+        let from_date = habits::local_date(from).ok_or("Invalid start date")?;
+        let to_date = habits::local_date(to).ok_or("Invalid end date")?;
+        if from_date > to_date {
+            return Err("Start date must be before end date".to_string());
+        }
+        if (to_date - from_date).num_days() > MAX_HABIT_HEATMAP_DAYS {
+            return Err(format!(
+                "Heatmap range cannot exceed {} days",
+                MAX_HABIT_HEATMAP_DAYS
+            ));
+        }
+
+        let conn = &self.conn;
+        let dates = get_habit_checkin_dates(conn, habit_id)?;
+
+        Ok(habits::heatmap(&dates, from_date, to_date))
+    }
+}
+
+fn validate_habit(
+    name: &str,
+    frequency: &str,
+    target_count: i64,
+) -> Result<(String, HabitFrequency), String> {
+    let clean_name = name.trim();
+    if clean_name.is_empty() {
+        return Err("Habit name cannot be empty".to_string());
+    }
+    if clean_name.chars().count() > MAX_HABIT_NAME_LENGTH {
+        return Err(format!(
+            "Habit name cannot exceed {} characters",
+            MAX_HABIT_NAME_LENGTH
+        ));
+    }
+    let frequency = HabitFrequency::parse(frequency)?;
+    if !(1..=MAX_HABIT_TARGET_COUNT).contains(&target_count) {
+        return Err(format!(
+            "Target count must be between 1 and {}",
+            MAX_HABIT_TARGET_COUNT
+        ));
+    }
+    Ok((clean_name.to_string(), frequency))
+}
+
+fn get_habit(conn: &Connection, id: i64) -> Result<Habit, String> {
+    conn.query_row(
+        "SELECT id, area_id, name, frequency, target_count, created_at, archived_at FROM habits WHERE id = ?",
+        params![id],
+        habit_from_row,
+    )
+    .map_err(|e| format!("Failed to get habit: {}", e))
+}
+
+fn get_habit_checkin_dates(conn: &Connection, habit_id: i64) -> Result<Vec<NaiveDate>, String> {
+    let mut stmt = conn
+        .prepare("SELECT checked_at FROM habit_checkins WHERE habit_id = ? ORDER BY checked_at")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![habit_id], |row| row.get::<_, i64>(0))
+        .map_err(|e| format!("Failed to query check-ins: {}", e))?;
+
+    let mut result = Vec::new();
+    for checked_at in rows {
+        let checked_at = checked_at.map_err(|e| format!("Failed to parse check-in: {}", e))?;
+        if let Some(date) = habits::local_date(checked_at) {
+            result.push(date);
+        }
+    }
+
+    Ok(result)
+}
+
+pub(super) fn habit_from_row(row: &Row) -> rusqlite::Result<Habit> {
+    Ok(Habit {
+        id: row.get(0)?,
+        area_id: row.get(1)?,
+        name: row.get(2)?,
+        frequency: row.get(3)?,
+        target_count: row.get(4)?,
+        created_at: row.get(5)?,
+        archived_at: row.get(6)?,
+    })
+}
+
+pub(super) fn habit_checkin_from_row(row: &Row) -> rusqlite::Result<HabitCheckin> {
+    Ok(HabitCheckin {
+        id: row.get(0)?,
+        habit_id: row.get(1)?,
+        checked_at: row.get(2)?,
+        note: row.get(3)?,
+    })
+}
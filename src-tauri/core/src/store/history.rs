@@ -0,0 +1,397 @@
+use super::Store;
+use crate::audit;
+use crate::models::{
+    ChangeLogEntry, RestoredChange, TrashBatch, TrashEntry, UndoResult, UndoStatus,
+};
+use crate::trash;
+use crate::undo::{self, Direction};
+use chrono::Utc;
+use rusqlite::params;
+
+const DEFAULT_CHANGE_LOG_LIMIT: i64 = 100;
+const MAX_CHANGE_LOG_LIMIT: i64 = 1000;
+
+impl Store {
+    pub fn get_change_log(
+        &self,
+        entity_type: Option<String>,
+        entity_id: Option<i64>,
+        from: Option<i64>,
+        to: Option<i64>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<Vec<ChangeLogEntry>, String> {
+        let limit = limit.unwrap_or(DEFAULT_CHANGE_LOG_LIMIT);
+        if !(1..=MAX_CHANGE_LOG_LIMIT).contains(&limit) {
+            return Err(format!(
+                "Limit must be between 1 and {}",
+                MAX_CHANGE_LOG_LIMIT
+            ));
+        }
+
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT l.id, l.operation_id, o.name, l.entity_type, l.entity_id, l.action,
+                        l.old_value, l.new_value, l.changed_at
+             FROM change_log l
+             INNER JOIN change_operations o ON o.id = l.operation_id
+             WHERE (?1 IS NULL OR l.entity_type = ?1)
+               AND (?2 IS NULL OR l.entity_id = ?2)
+               AND (?3 IS NULL OR l.changed_at >= ?3)
+               AND (?4 IS NULL OR l.changed_at <= ?4)
+             ORDER BY l.id DESC
+             LIMIT ?5 OFFSET ?6",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(
+                params![entity_type, entity_id, from, to, limit, offset.unwrap_or(0)],
+                |row| {
+                    Ok((
+                        ChangeLogEntry {
+                            id: row.get(0)?,
+                            operation_id: row.get(1)?,
+                            operation: row.get(2)?,
+                            entity_type: row.get(3)?,
+                            entity_id: row.get(4)?,
+                            action: row.get(5)?,
+                            old_value: None,
+                            new_value: None,
+                            changed_at: row.get(8)?,
+                        },
+                        row.get::<_, Option<String>>(6)?,
+                        row.get::<_, Option<String>>(7)?,
+                    ))
+                },
+            )
+            .map_err(|e| format!("Failed to query change log: {}", e))?;
+
+        let parse_value = |value: Option<String>| {
+            value
+                .map(|v| serde_json::from_str(&v))
+                .transpose()
+                .map_err(|e| format!("Failed to parse change log value: {}", e))
+        };
+
+        let mut result = Vec::new();
+        for row in rows {
+            let (mut entry, old_value, new_value) =
+                row.map_err(|e| format!("Failed to parse change log entry: {}", e))?;
+            entry.old_value = parse_value(old_value)?;
+            entry.new_value = parse_value(new_value)?;
+            result.push(entry);
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_trash(&self) -> Result<Vec<TrashBatch>, String> {
+        let conn = &self.conn;
+        trash::get_batches(conn)
+    }
+
+    pub fn get_trash_entries(
+        &self,
+        batch_id: i64,
+        entity_type: Option<String>,
+    ) -> Result<Vec<TrashEntry>, String> {
+        let conn = &self.conn;
+        trash::get_entries(conn, batch_id, entity_type.as_deref())
+    }
+
+    pub fn restore_trash(&self, batch_id: i64) -> Result<Vec<RestoredChange>, String> {
+        self.with_operation("restore_trash", |conn, op| {
+            trash::restore_batch(conn, op, batch_id)
+        })
+    }
+
+    /// Permanently deletes everything in the trash. Returns the number of rows removed.
+    pub fn empty_trash(&self) -> Result<usize, String> {
+        self.with_operation("empty_trash", |conn, op| trash::purge(conn, op, None))
+    }
+
+    pub fn get_trash_retention_days(&self) -> Result<Option<i64>, String> {
+        let conn = &self.conn;
+        trash::retention_days(conn)
+    }
+
+    /// Sets how long trashed rows are kept; `None` keeps them until the trash is emptied.
+    pub fn set_trash_retention_days(&self, days: Option<i64>) -> Result<Option<i64>, String> {
+        let value = trash::retention_setting_value(days)?;
+
+        self.with_operation("set_trash_retention_days", |conn, op| {
+            let before = audit::snapshot(
+                conn,
+                "settings",
+                "key = ?",
+                params![trash::RETENTION_SETTING],
+            )?;
+            conn.execute(
+                "INSERT INTO settings (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![trash::RETENTION_SETTING, value],
+            )
+            .map_err(|e| format!("Failed to save setting: {}", e))?;
+
+            if before.is_empty() {
+                op.created(
+                    conn,
+                    "settings",
+                    "key = ?",
+                    params![trash::RETENTION_SETTING],
+                )?;
+            } else {
+                op.updated(conn, "settings", before)?;
+            }
+
+            trash::retention_days(conn)
+        })
+    }
+
+    /// Removes trashed rows older than the retention period. Run whenever a
+    /// database is opened with [`Store::open`].
+    pub fn purge_expired_trash(&self) -> Result<usize, String> {
+        self.with_operation("purge_trash", |conn, op| {
+            let Some(days) = trash::retention_days(conn)? else {
+                return Ok(0);
+            };
+            trash::purge(conn, op, Some(Utc::now().timestamp() - days * 86_400))
+        })
+    }
+
+    pub fn get_undo_status(&self) -> Result<UndoStatus, String> {
+        let conn = &self.conn;
+
+        Ok(UndoStatus {
+            undo: undo::next_operation(conn, Direction::Undo)?,
+            redo: undo::next_operation(conn, Direction::Redo)?,
+        })
+    }
+
+    /// Reverts the most recent undoable operation. Returns `None` if there is
+    /// nothing to undo.
+    pub fn undo(&self) -> Result<Option<UndoResult>, String> {
+        self.step_undo_stack("undo", Direction::Undo)
+    }
+
+    /// Reapplies the most recently undone operation. Returns `None` if there is
+    /// nothing to redo.
+    pub fn redo(&self) -> Result<Option<UndoResult>, String> {
+        self.step_undo_stack("redo", Direction::Redo)
+    }
+
+    fn step_undo_stack(
+        &self,
+        name: &'static str,
+        direction: Direction,
+    ) -> Result<Option<UndoResult>, String> {
+        self.with_operation(name, |conn, op| {
+            let Some(mut operation) = undo::next_operation(conn, direction)? else {
+                return Ok(None);
+            };
+
+            let changes = undo::apply(conn, op, &mut operation, direction)?;
+
+            Ok(Some(UndoResult { operation, changes }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_with_item() -> (Store, i64, i64) {
+        let store = Store::open_in_memory().unwrap();
+        let area = store
+            .create_life_area("Health".to_string(), None, "#10b981".to_string(), 0)
+            .unwrap();
+        let item = store
+            .create_action_item(area.id, "Run a 10k".to_string(), None)
+            .unwrap();
+        (store, area.id, item.id)
+    }
+
+    fn open_item_ids(store: &Store) -> Vec<i64> {
+        store
+            .get_all_action_items(None, None)
+            .unwrap()
+            .iter()
+            .map(|item| item.id)
+            .collect()
+    }
+
+    #[test]
+    fn undo_and_redo_a_deletion() {
+        let (store, _, item_id) = store_with_item();
+        store
+            .add_action_item_link(item_id, "https://example.com".to_string(), None)
+            .unwrap();
+
+        store.delete_action_item(item_id).unwrap();
+        assert!(open_item_ids(&store).is_empty());
+
+        let undone = store.undo().unwrap().unwrap();
+        assert_eq!(undone.operation.name, "delete_action_item");
+        assert_eq!(open_item_ids(&store), [item_id]);
+        assert_eq!(store.get_action_item_links(item_id).unwrap().len(), 1);
+        assert!(store.get_trash().unwrap().is_empty());
+
+        let status = store.get_undo_status().unwrap();
+        assert!(status.undo.is_none());
+        assert_eq!(status.redo.unwrap().name, "delete_action_item");
+
+        store.redo().unwrap().unwrap();
+        assert!(open_item_ids(&store).is_empty());
+        assert!(store.redo().unwrap().is_none());
+    }
+
+    #[test]
+    fn a_new_operation_clears_the_redo_stack() {
+        let (store, area_id, first_id) = store_with_item();
+        let second = store
+            .create_action_item(area_id, "Stretch".to_string(), None)
+            .unwrap();
+
+        store.delete_action_item(first_id).unwrap();
+        store.undo().unwrap().unwrap();
+        store.delete_action_item(second.id).unwrap();
+
+        assert!(store.get_undo_status().unwrap().redo.is_none());
+        assert_eq!(open_item_ids(&store), [first_id]);
+    }
+
+    #[test]
+    fn undo_refuses_to_overwrite_later_changes() {
+        let (store, _, item_id) = store_with_item();
+        store.archive_action_item(item_id).unwrap();
+        store
+            .update_action_item(item_id, "Run a half marathon".to_string())
+            .unwrap();
+
+        let error = store.undo().unwrap_err();
+        assert!(error.contains("has changed since"), "{}", error);
+    }
+
+    #[test]
+    fn restoring_a_trash_batch_puts_rows_back_with_their_ids() {
+        let (store, _, item_id) = store_with_item();
+        store
+            .create_action_item_step(item_id, "Buy shoes".to_string(), None)
+            .unwrap();
+        store.delete_action_item(item_id).unwrap();
+
+        let batches = store.get_trash().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].operation, "delete_action_item");
+        assert_eq!(batches[0].counts.get("action_items"), Some(&1));
+        assert_eq!(batches[0].counts.get("action_item_steps"), Some(&1));
+
+        store.restore_trash(batches[0].id).unwrap();
+        assert_eq!(open_item_ids(&store), [item_id]);
+        assert_eq!(store.get_action_item_steps(item_id).unwrap().len(), 1);
+        assert!(store.get_trash().unwrap().is_empty());
+        // Undoing the deletion no longer applies once restored by hand
+        assert!(store.get_undo_status().unwrap().undo.is_none());
+    }
+
+    #[test]
+    fn emptying_the_trash_removes_its_rows() {
+        let (store, _, item_id) = store_with_item();
+        store.delete_action_item(item_id).unwrap();
+
+        assert_eq!(store.empty_trash().unwrap(), 1);
+        assert!(store.get_trash().unwrap().is_empty());
+    }
+
+    fn change_log_mentions(store: &Store, text: &str) -> bool {
+        store
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM change_log
+                 WHERE instr(COALESCE(old_value, '') || COALESCE(new_value, ''), ?1) > 0",
+                params![text],
+                |row| row.get::<_, i64>(0),
+            )
+            .unwrap()
+            > 0
+    }
+
+    #[test]
+    fn emptying_the_trash_keeps_history_and_records_the_purge() {
+        let (store, _, item_id) = store_with_item();
+        store
+            .update_action_item(item_id, "Run a secret marathon".to_string())
+            .unwrap();
+        store.delete_action_item(item_id).unwrap();
+        let entries_before: i64 = store
+            .conn
+            .query_row("SELECT COUNT(*) FROM change_log", [], |row| row.get(0))
+            .unwrap();
+
+        store.empty_trash().unwrap();
+        assert!(change_log_mentions(&store, "secret"));
+        assert!(store.get_undo_status().unwrap().undo.is_none());
+
+        let (entries_after, tombstone): (i64, String) = store
+            .conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM change_log), old_value FROM change_log
+                 WHERE entity_type = 'trash' AND action = 'delete'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(entries_after, entries_before + 1);
+        assert!(!tombstone.contains("secret"));
+    }
+
+    #[test]
+    fn expired_trash_is_purged_and_recent_trash_kept() {
+        let (store, area_id, old_item) = store_with_item();
+        let recent_item = store
+            .create_action_item(area_id, "Stretch".to_string(), None)
+            .unwrap()
+            .id;
+        store.delete_action_item(old_item).unwrap();
+        store.delete_action_item(recent_item).unwrap();
+        store
+            .conn
+            .execute(
+                "UPDATE trash SET deleted_at = deleted_at - 31 * 86400 WHERE entity_id = ?1",
+                params![old_item],
+            )
+            .unwrap();
+
+        assert_eq!(store.purge_expired_trash().unwrap(), 1);
+        let batches = store.get_trash().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(store.undo().unwrap().unwrap().operation.id, batches[0].id);
+    }
+
+    fn backdate_change_log(store: &Store, days: i64) {
+        store
+            .conn
+            .execute(
+                "UPDATE change_log SET changed_at = changed_at - ?1 * 86400",
+                params![days],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn old_history_is_kept_when_expired_trash_is_purged() {
+        let (store, _, item_id) = store_with_item();
+        let step = store
+            .create_action_item_step(item_id, "Buy shoes".to_string(), None)
+            .unwrap();
+        store.delete_action_item_step(step.id).unwrap();
+        backdate_change_log(&store, 400);
+
+        store.purge_expired_trash().unwrap();
+        assert!(change_log_mentions(&store, "Buy shoes"));
+        assert!(change_log_mentions(&store, "Run a 10k"));
+    }
+}
@@ -0,0 +1,81 @@
+use super::action_items::{action_item_from_row, archive_action_item, reopen_action_item};
+use super::Store;
+use crate::ical::{read_task_statuses, write_calendar, IcalComponent, IcalImportSummary};
+use crate::models::ActionItem;
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+
+impl Store {
+    /// Every action item as an iCalendar file, with its area as category.
+    /// `component` is `todo` or `event`.
+    pub fn export_ical(&self, component: String) -> Result<String, String> {
+        let component = IcalComponent::parse(&component)?;
+
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT ai.id, ai.area_id, ai.title, ai.created_at, ai.position, ai.archived_at,
+                        ai.description, ai.due_at, ai.recurrence, la.name
+                 FROM action_items ai
+                 INNER JOIN life_areas la ON la.id = ai.area_id
+                 ORDER BY la.\"order\", ai.position, ai.created_at",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| Ok((action_item_from_row(row)?, row.get(9)?)))
+            .map_err(|e| format!("Failed to query action items: {}", e))?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            items.push(row.map_err(|e| format!("Failed to parse action item: {}", e))?);
+        }
+
+        Ok(write_calendar(&items, component, Utc::now().timestamp()))
+    }
+
+    /// Applies completion changes made in a calendar to the action items an
+    /// earlier `export_ical` wrote: completed tasks are archived and tasks
+    /// marked as not done are reopened. Nothing else is changed.
+    pub fn import_ical(&self, contents: &str) -> Result<IcalImportSummary, String> {
+        let statuses = read_task_statuses(contents)?;
+
+        self.with_operation("import_ical", |conn, op| {
+            let now = Utc::now();
+            let mut summary = IcalImportSummary::default();
+
+            for status in statuses {
+                let item: Option<ActionItem> = match status.action_item_id {
+                    Some(id) => conn
+                        .query_row(
+                            "SELECT id, area_id, title, created_at, position, archived_at, description, due_at, recurrence
+                             FROM action_items WHERE id = ?",
+                            params![id],
+                            action_item_from_row,
+                        )
+                        .optional()
+                        .map_err(|e| format!("Failed to get action item: {}", e))?,
+                    None => None,
+                };
+                let Some(item) = item else {
+                    summary.skipped += 1;
+                    continue;
+                };
+
+                match (status.completed, item.archived_at) {
+                    (true, None) => {
+                        archive_action_item(conn, op, item.id, now)?;
+                        summary.completed += 1;
+                    }
+                    (false, Some(_)) => {
+                        reopen_action_item(conn, op, item.id)?;
+                        summary.reopened += 1;
+                    }
+                    _ => summary.unchanged += 1,
+                }
+            }
+
+            Ok(summary)
+        })
+    }
+}
@@ -0,0 +1,146 @@
+use super::Store;
+use crate::models::ActionItemLink;
+use chrono::Utc;
+use rusqlite::{params, Connection, Row};
+
+impl Store {
+    pub fn add_action_item_link(
+        &self,
+        action_item_id: i64,
+        url: String,
+        label: Option<String>,
+    ) -> Result<ActionItemLink, String> {
+        let clean_url = normalize_link_url(&url)?;
+        let clean_label = label
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty());
+
+        self.with_operation("add_action_item_link", |conn, op| {
+            let exists = conn
+                .prepare("SELECT id FROM action_items WHERE id = ?")
+                .and_then(|mut stmt| stmt.exists(params![action_item_id]))
+                .map_err(|e| format!("Failed to check action item: {}", e))?;
+            if !exists {
+                return Err("Action item not found".to_string());
+            }
+
+            let now = Utc::now().timestamp();
+            let next_position: i64 = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(position), -1) + 1 FROM action_item_links WHERE action_item_id = ?",
+                    params![action_item_id],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+
+            conn.execute(
+                "INSERT INTO action_item_links (action_item_id, url, label, position, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![action_item_id, clean_url, clean_label, next_position, now],
+            )
+            .map_err(|e| format!("Failed to insert link: {}", e))?;
+
+            let id = conn.last_insert_rowid();
+            op.created(conn, "action_item_links", "id = ?", params![id])?;
+
+            Ok(ActionItemLink {
+                id,
+                action_item_id,
+                url: clean_url,
+                label: clean_label,
+                position: next_position,
+                created_at: now,
+            })
+        })
+    }
+
+    pub fn get_action_item_links(
+        &self,
+        action_item_id: i64,
+    ) -> Result<Vec<ActionItemLink>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, action_item_id, url, label, position, created_at
+             FROM action_item_links
+             WHERE action_item_id = ?
+             ORDER BY position ASC, created_at ASC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![action_item_id], action_item_link_from_row)
+            .map_err(|e| format!("Failed to query links: {}", e))?;
+
+        let mut result = Vec::new();
+        for link in rows {
+            result.push(link.map_err(|e| format!("Failed to parse link: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn delete_action_item_link(&self, id: i64) -> Result<(), String> {
+        self.with_operation("delete_action_item_link", |conn, op| {
+            op.delete_where(conn, "action_item_links", "id = ?", params![id])?;
+            Ok(())
+        })
+    }
+
+    /// The URL of a link, validated so it is safe to hand to the system opener.
+    pub fn get_action_item_link_url(&self, id: i64) -> Result<String, String> {
+        let link = get_action_item_link(&self.conn, id)?;
+
+        // Re-validate in case the row predates the scheme check
+        normalize_link_url(&link.url)
+    }
+}
+
+fn normalize_link_url(url: &str) -> Result<String, String> {
+    let clean_url = url.trim();
+    if clean_url.is_empty() {
+        return Err("URL cannot be empty".to_string());
+    }
+    let lower = clean_url.to_lowercase();
+    if !(lower.starts_with("http://")
+        || lower.starts_with("https://")
+        || lower.starts_with("mailto:"))
+    {
+        return Err("URL must start with http://, https:// or mailto:".to_string());
+    }
+    Ok(clean_url.to_string())
+}
+
+fn get_action_item_link(conn: &Connection, id: i64) -> Result<ActionItemLink, String> {
+    conn.query_row(
+        "SELECT id, action_item_id, url, label, position, created_at FROM action_item_links WHERE id = ?",
+        params![id],
+        action_item_link_from_row,
+    )
+    .map_err(|e| format!("Failed to get link: {}", e))
+}
+
+pub(super) fn action_item_link_from_row(row: &Row) -> rusqlite::Result<ActionItemLink> {
+    Ok(ActionItemLink {
+        id: row.get(0)?,
+        action_item_id: row.get(1)?,
+        url: row.get(2)?,
+        label: row.get(3)?,
+        position: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adding_a_link_to_a_missing_item_fails() {
+        let store = Store::open_in_memory().unwrap();
+        let err = store
+            .add_action_item_link(1, "https://example.com".to_string(), None)
+            .unwrap_err();
+        assert_eq!(err, "Action item not found");
+    }
+}
@@ -0,0 +1,91 @@
+mod action_items;
+mod areas;
+mod csv_import;
+mod export;
+mod goals;
+mod habits;
+mod history;
+mod ical;
+mod links;
+mod printable;
+mod report;
+mod reset;
+mod scores;
+mod steps;
+mod tags;
+mod tasks;
+mod templates;
+mod vault;
+
+use crate::audit::Operation;
+#[cfg(test)]
+use crate::database::create_schema;
+use crate::database::{get_connection, open_database};
+use crate::undo;
+use rusqlite::Connection;
+use std::path::Path;
+
+pub use export::EXPORT_VERSION;
+
+/// Access to the life wheel data: every read and write goes through a
+/// `Store` wrapping one SQLite connection.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Opens the database at its default location.
+    pub fn open_default() -> Result<Self, String> {
+        Ok(Store {
+            conn: get_connection()?,
+        })
+    }
+
+    /// Opens the database at `path`, creating it and its schema if needed,
+    /// and purges trashed rows past their retention period.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let store = Store {
+            conn: open_database(path)?,
+        };
+        store.purge_expired_trash()?;
+        Ok(store)
+    }
+
+    /// A fresh database that lives only as long as the store.
+    #[cfg(test)]
+    pub(crate) fn open_in_memory() -> Result<Self, String> {
+        let conn =
+            Connection::open_in_memory().map_err(|e| format!("Failed to open database: {}", e))?;
+        create_schema(&conn)?;
+        Ok(Store { conn })
+    }
+
+    /// Runs `f` inside a transaction, recording its changes in the change log
+    /// under `name`. Nothing is committed if `f` fails. Operations listed in
+    /// `undo::UNDOABLE_OPERATIONS` are pushed onto the undo stack.
+    fn with_operation<T>(
+        &self,
+        name: &'static str,
+        f: impl FnOnce(&Connection, &Operation) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let op = if undo::is_undoable(name) {
+            Operation::undoable(name)
+        } else {
+            Operation::new(name)
+        };
+        let result = f(&tx, &op)?;
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        Ok(result)
+    }
+}
+
+fn sql_placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
@@ -0,0 +1,92 @@
+use super::Store;
+use crate::pdf::{PrintableArea, PrintableWheel};
+use chrono::Utc;
+
+const HISTORY_DAYS: i64 = 365;
+const MAX_NOTES: usize = 5;
+
+impl Store {
+    /// The current wheel with the last year of scores, the open action items
+    /// and the latest notes of every active area.
+    pub fn get_printable_wheel(&self) -> Result<PrintableWheel, String> {
+        let now = Utc::now().timestamp();
+        let history_start = now - HISTORY_DAYS * 86_400;
+
+        let mut areas = Vec::new();
+        for area in self.get_life_areas(false)? {
+            let mut history =
+                self.get_scores_by_area(area.id, Some(history_start), None, None, None)?;
+            let notes = history
+                .iter()
+                .filter(|score| {
+                    score
+                        .note
+                        .as_deref()
+                        .is_some_and(|note| !note.trim().is_empty())
+                })
+                .take(MAX_NOTES)
+                .cloned()
+                .collect();
+            history.reverse();
+
+            areas.push(PrintableArea {
+                score: self.get_latest_score(area.id)?,
+                action_items: self.get_action_items_by_area(area.id)?,
+                area,
+                history,
+                notes,
+            });
+        }
+
+        Ok(PrintableWheel {
+            generated_at: now,
+            history_start,
+            areas,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn printable_wheel_is_a_three_page_pdf() {
+        let store = Store::open_in_memory().unwrap();
+        let area = store
+            .create_life_area("Health".to_string(), None, "#10b981".to_string(), 0)
+            .unwrap();
+        let now = Utc::now().timestamp();
+        store
+            .create_score(
+                area.id,
+                5,
+                Some(now - 7 * 86_400),
+                Some("Sore knee".to_string()),
+            )
+            .unwrap();
+        store
+            .create_score(
+                area.id,
+                7,
+                Some(now - 86_400),
+                Some("Running again".to_string()),
+            )
+            .unwrap();
+        store
+            .create_action_item(area.id, "Run a 10k".to_string(), None)
+            .unwrap();
+
+        let wheel = store.get_printable_wheel().unwrap();
+        let printable = &wheel.areas[0];
+        let values: Vec<i64> = printable.history.iter().map(|s| s.value).collect();
+        assert_eq!(values, [5, 7]);
+        assert_eq!(printable.notes[0].note.as_deref(), Some("Running again"));
+
+        let pdf = wheel.to_pdf();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(text.trim_end().ends_with("%%EOF"));
+        assert!(text.contains("/Count 3"));
+    }
+}
@@ -0,0 +1,190 @@
+use super::action_items::action_item_from_row;
+use super::scores::{score_as_of, score_from_row};
+use super::Store;
+use crate::aggregation::{local_midnight, Granularity};
+use crate::habits::local_date;
+use crate::models::{ActionItem, Score};
+use crate::report::{review_title, AreaReview, ReviewReport};
+use chrono::{Duration, Utc};
+use rusqlite::params;
+
+impl Store {
+    /// The review of the `granularity` period (`week`, `month`, ...) containing
+    /// `at`, which defaults to now, compared with the period before it.
+    pub fn get_review_report(
+        &self,
+        granularity: String,
+        at: Option<i64>,
+    ) -> Result<ReviewReport, String> {
+        let granularity = Granularity::parse(&granularity)?;
+        let now = Utc::now().timestamp();
+        let date = local_date(at.unwrap_or(now)).ok_or("Invalid date")?;
+
+        let start_date = granularity.bucket_start(date);
+        let previous_date = granularity.bucket_start(start_date - Duration::days(1));
+        let (Some(start), Some(next_start), Some(previous_start)) = (
+            local_midnight(start_date),
+            local_midnight(granularity.next_start(start_date)),
+            local_midnight(previous_date),
+        ) else {
+            return Err("Invalid date".to_string());
+        };
+        if start > now {
+            return Err("The period has not started yet".to_string());
+        }
+        let end = (next_start - 1).min(now);
+
+        let notes = self.query_period_notes(start, end)?;
+        let action_items = self.query_period_action_items(start, end)?;
+
+        let mut areas = Vec::new();
+        for entry in self.get_wheel_as_of(end)? {
+            let area_id = entry.area.id;
+            let previous_score = score_as_of(&self.conn, area_id, start - 1)?;
+            let change = entry
+                .score
+                .as_ref()
+                .zip(previous_score.as_ref())
+                .map(|(score, previous)| score.value - previous.value);
+            let (completed, open) = action_items
+                .iter()
+                .filter(|item| item.area_id == area_id)
+                .cloned()
+                .partition(|item| item.archived_at.is_some_and(|at| at <= end));
+
+            areas.push(AreaReview {
+                notes: notes
+                    .iter()
+                    .filter(|score| score.area_id == area_id)
+                    .cloned()
+                    .collect(),
+                area: entry.area,
+                score: entry.score,
+                previous_score,
+                change,
+                completed,
+                open,
+            });
+        }
+
+        Ok(ReviewReport {
+            title: review_title(granularity, start_date),
+            period_start: start,
+            period_end: end,
+            previous_start,
+            generated_at: now,
+            areas,
+        })
+    }
+
+    fn query_period_notes(&self, start: i64, end: i64) -> Result<Vec<Score>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, area_id, value, recorded_at, note FROM scores
+                 WHERE recorded_at BETWEEN ?1 AND ?2 AND note IS NOT NULL AND note != ''
+                 ORDER BY recorded_at, id",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![start, end], score_from_row)
+            .map_err(|e| format!("Failed to query scores: {}", e))?;
+
+        let mut result = Vec::new();
+        for score in rows {
+            result.push(score.map_err(|e| format!("Failed to parse score: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    /// Action items that existed at the end of the period and were not
+    /// archived before it began.
+    fn query_period_action_items(&self, start: i64, end: i64) -> Result<Vec<ActionItem>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, area_id, title, created_at, position, archived_at, description, due_at, recurrence
+                 FROM action_items
+                 WHERE created_at <= ?2 AND (archived_at IS NULL OR archived_at >= ?1)
+                 ORDER BY position ASC, created_at ASC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![start, end], action_item_from_row)
+            .map_err(|e| format!("Failed to query action items: {}", e))?;
+
+        let mut result = Vec::new();
+        for item in rows {
+            result.push(item.map_err(|e| format!("Failed to parse action item: {}", e))?);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn midnight(year: i32, month: u32, day: u32) -> i64 {
+        local_midnight(NaiveDate::from_ymd_opt(year, month, day).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn monthly_review_covers_the_month_and_compares_with_the_one_before() {
+        let store = Store::open_in_memory().unwrap();
+        let area = store
+            .create_life_area("Health".to_string(), None, "#10b981".to_string(), 0)
+            .unwrap();
+        let noon = 12 * 3600;
+        store
+            .create_score(area.id, 5, Some(midnight(2025, 2, 20) + noon), None)
+            .unwrap();
+        store
+            .create_score(
+                area.id,
+                7,
+                Some(midnight(2025, 3, 31) + noon),
+                Some("Back to running".to_string()),
+            )
+            .unwrap();
+        store
+            .create_score(area.id, 9, Some(midnight(2025, 4, 1)), None)
+            .unwrap();
+
+        let at = midnight(2025, 3, 15) + noon;
+        let report = store
+            .get_review_report("month".to_string(), Some(at))
+            .unwrap();
+
+        assert_eq!(report.title, "Review of March 2025");
+        assert_eq!(report.period_start, midnight(2025, 3, 1));
+        assert_eq!(report.period_end, midnight(2025, 4, 1) - 1);
+        assert_eq!(report.previous_start, midnight(2025, 2, 1));
+
+        let review = &report.areas[0];
+        assert_eq!(review.score.as_ref().map(|s| s.value), Some(7));
+        assert_eq!(review.previous_score.as_ref().map(|s| s.value), Some(5));
+        assert_eq!(review.change, Some(2));
+        assert_eq!(review.notes.len(), 1);
+    }
+
+    #[test]
+    fn running_period_ends_now_and_future_periods_are_refused() {
+        let store = Store::open_in_memory().unwrap();
+        let now = Utc::now().timestamp();
+
+        let report = store.get_review_report("week".to_string(), None).unwrap();
+        assert!(report.period_start <= now && report.period_end >= now);
+        assert!(report.period_end <= Utc::now().timestamp());
+
+        let next_year = now + 400 * 86_400;
+        assert!(store
+            .get_review_report("year".to_string(), Some(next_year))
+            .is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use super::action_items::trash_action_item_dependents;
+use super::Store;
+use rusqlite::params;
+
+impl Store {
+    pub fn reset_area_data(&self, area_id: i64) -> Result<(), String> {
+        self.with_operation("reset_area_data", |conn, op| {
+            // Delete all scores for this area
+            op.trash_where(conn, "scores", "area_id = ?", params![area_id])?;
+
+            // Delete all action items for this area
+            trash_action_item_dependents(
+                conn,
+                op,
+                "SELECT id FROM action_items WHERE area_id = ?",
+                params![area_id],
+            )?;
+            op.trash_where(conn, "action_items", "area_id = ?", params![area_id])?;
+
+            // Delete all habits and their check-ins for this area
+            op.trash_where(
+                conn,
+                "habit_checkins",
+                "habit_id IN (SELECT id FROM habits WHERE area_id = ?)",
+                params![area_id],
+            )?;
+            op.trash_where(conn, "habits", "area_id = ?", params![area_id])?;
+
+            // Delete all goals for this area
+            op.trash_where(conn, "goals", "area_id = ?", params![area_id])?;
+
+            Ok(())
+        })
+    }
+
+    pub fn reset_area_scores(&self, area_id: i64) -> Result<(), String> {
+        self.with_operation("reset_area_scores", |conn, op| {
+            op.trash_where(conn, "scores", "area_id = ?", params![area_id])?;
+            Ok(())
+        })
+    }
+
+    pub fn reset_area_action_items(&self, area_id: i64) -> Result<(), String> {
+        self.with_operation("reset_area_action_items", |conn, op| {
+            trash_action_item_dependents(
+                conn,
+                op,
+                "SELECT id FROM action_items WHERE area_id = ?",
+                params![area_id],
+            )?;
+            op.trash_where(conn, "action_items", "area_id = ?", params![area_id])?;
+
+            Ok(())
+        })
+    }
+
+    pub fn reset_all_data(&self) -> Result<(), String> {
+        self.with_operation("reset_all_data", |conn, op| {
+            // Delete all scores
+            op.trash_where(conn, "scores", "1 = 1", params![])?;
+
+            // Delete everything attached to action items, then the tags themselves
+            trash_action_item_dependents(conn, op, "SELECT id FROM action_items", params![])?;
+            op.trash_where(conn, "tags", "1 = 1", params![])?;
+
+            // Delete all action items
+            op.trash_where(conn, "action_items", "1 = 1", params![])?;
+
+            // Delete all habits and check-ins
+            op.trash_where(conn, "habit_checkins", "1 = 1", params![])?;
+            op.trash_where(conn, "habits", "1 = 1", params![])?;
+
+            // Delete all goals
+            op.trash_where(conn, "goals", "1 = 1", params![])?;
+
+            // Delete all life areas
+            op.trash_where(conn, "life_areas", "1 = 1", params![])?;
+
+            Ok(())
+        })
+    }
+}
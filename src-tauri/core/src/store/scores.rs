@@ -0,0 +1,643 @@
+use super::{sql_placeholders, Store};
+use crate::aggregation::{self, AreaScoreBuckets, GapFill, Granularity};
+use crate::audit;
+use crate::habits;
+use crate::models::{LifeArea, Score, ScoreCursor, ScorePage, WheelEntry};
+use chrono::Utc;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+const MAX_SCORE_BUCKETS: usize = 5000;
+const DEFAULT_SCORE_PAGE_LIMIT: i64 = 500;
+const MAX_SCORE_PAGE_LIMIT: i64 = 5000;
+
+impl Store {
+    /// Records a score for an area. `recorded_at` defaults to now.
+    pub fn create_score(
+        &self,
+        area_id: i64,
+        value: i64,
+        recorded_at: Option<i64>,
+        note: Option<String>,
+    ) -> Result<Score, String> {
+        if !(0..=10).contains(&value) {
+            return Err("Score must be between 0 and 10".to_string());
+        }
+        let recorded_at = recorded_at.unwrap_or_else(|| Utc::now().timestamp());
+        let clean_note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+        self.with_operation("create_score", |conn, op| {
+            conn.execute(
+                "INSERT INTO scores (area_id, value, recorded_at, note) VALUES (?1, ?2, ?3, ?4)",
+                params![area_id, value, recorded_at, clean_note],
+            )
+            .map_err(|e| format!("Failed to insert score: {}", e))?;
+
+            let id = conn.last_insert_rowid();
+            op.created(conn, "scores", "id = ?", params![id])?;
+
+            Ok(Score {
+                id,
+                area_id,
+                value,
+                recorded_at,
+                note: clean_note,
+            })
+        })
+    }
+
+    /// Scores of an area, newest first. All filters are optional; without them
+    /// the whole history is returned.
+    pub fn get_scores_by_area(
+        &self,
+        area_id: i64,
+        from: Option<i64>,
+        to: Option<i64>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<Vec<Score>, String> {
+        if let Some(limit) = limit {
+            validate_score_page_limit(limit)?;
+        }
+
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, area_id, value, recorded_at, note FROM scores
+                 WHERE area_id = ?1
+                   AND (?2 IS NULL OR recorded_at >= ?2)
+                   AND (?3 IS NULL OR recorded_at <= ?3)
+                 ORDER BY recorded_at DESC, id DESC
+                 LIMIT ?4 OFFSET ?5",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let scores = stmt
+            .query_map(
+                params![area_id, from, to, limit.unwrap_or(-1), offset.unwrap_or(0)],
+                score_from_row,
+            )
+            .map_err(|e| format!("Failed to query scores: {}", e))?;
+
+        let mut result = Vec::new();
+        for score in scores {
+            result.push(score.map_err(|e| format!("Failed to parse score: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    /// One page of scores across several areas, paginated with a keyset cursor so
+    /// pages stay stable while new scores are recorded. Newest first unless
+    /// `ascending` is set.
+    pub fn get_scores_page(
+        &self,
+        area_ids: Vec<i64>,
+        from: Option<i64>,
+        to: Option<i64>,
+        limit: Option<i64>,
+        cursor: Option<ScoreCursor>,
+        ascending: Option<bool>,
+    ) -> Result<ScorePage, String> {
+        let limit = limit.unwrap_or(DEFAULT_SCORE_PAGE_LIMIT);
+        validate_score_page_limit(limit)?;
+        if area_ids.is_empty() {
+            return Ok(ScorePage {
+                scores: Vec::new(),
+                next_cursor: None,
+            });
+        }
+
+        let ascending = ascending.unwrap_or(false);
+        let (direction, comparison) = if ascending {
+            ("ASC", ">")
+        } else {
+            ("DESC", "<")
+        };
+
+        let mut query = format!(
+            "SELECT id, area_id, value, recorded_at, note FROM scores WHERE area_id IN ({})",
+            sql_placeholders(area_ids.len())
+        );
+        let mut values: Vec<i64> = area_ids;
+        if let Some(from) = from {
+            query.push_str(" AND recorded_at >= ?");
+            values.push(from);
+        }
+        if let Some(to) = to {
+            query.push_str(" AND recorded_at <= ?");
+            values.push(to);
+        }
+        if let Some(cursor) = cursor {
+            query.push_str(&format!(
+                " AND (recorded_at {0} ? OR (recorded_at = ? AND id {0} ?))",
+                comparison
+            ));
+            values.extend([cursor.recorded_at, cursor.recorded_at, cursor.id]);
+        }
+        query.push_str(&format!(
+            " ORDER BY recorded_at {0}, id {0} LIMIT ?",
+            direction
+        ));
+        values.push(limit + 1);
+
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params_from_iter(values), score_from_row)
+            .map_err(|e| format!("Failed to query scores: {}", e))?;
+
+        let mut scores = Vec::new();
+        for score in rows {
+            scores.push(score.map_err(|e| format!("Failed to parse score: {}", e))?);
+        }
+
+        let next_cursor = if scores.len() as i64 > limit {
+            scores.truncate(limit as usize);
+            scores.last().map(|score| ScoreCursor {
+                recorded_at: score.recorded_at,
+                id: score.id,
+            })
+        } else {
+            None
+        };
+
+        Ok(ScorePage {
+            scores,
+            next_cursor,
+        })
+    }
+
+    pub fn get_latest_score(&self, area_id: i64) -> Result<Option<Score>, String> {
+        let conn = &self.conn;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, area_id, value, recorded_at, note FROM scores WHERE area_id = ? ORDER BY recorded_at DESC LIMIT 1"
+        ).map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let mut scores = stmt
+            .query_map(params![area_id], score_from_row)
+            .map_err(|e| format!("Failed to query score: {}", e))?;
+
+        scores
+            .next()
+            .transpose()
+            .map_err(|e| format!("Failed to parse score: {}", e))
+    }
+
+    pub fn get_all_latest_scores(&self) -> Result<Vec<Score>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT s.id, s.area_id, s.value, s.recorded_at, s.note
+             FROM scores s
+             INNER JOIN (
+                 SELECT area_id, MAX(recorded_at) as max_recorded_at
+                 FROM scores
+                 GROUP BY area_id
+             ) latest ON s.area_id = latest.area_id AND s.recorded_at = latest.max_recorded_at
+             INNER JOIN life_areas la ON s.area_id = la.id
+             WHERE la.is_active = 1
+             ORDER BY la.\"order\"",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let scores = stmt
+            .query_map([], score_from_row)
+            .map_err(|e| format!("Failed to query scores: {}", e))?;
+
+        let mut result = Vec::new();
+        for score in scores {
+            result.push(score.map_err(|e| format!("Failed to parse score: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    /// Aggregates scores into local-time buckets for charts. Defaults to all
+    /// active areas and to the range covered by their scores; the range is
+    /// widened to whole buckets.
+    pub fn get_score_buckets(
+        &self,
+        granularity: String,
+        area_ids: Option<Vec<i64>>,
+        from: Option<i64>,
+        to: Option<i64>,
+        fill: Option<String>,
+    ) -> Result<Vec<AreaScoreBuckets>, String> {
+        let granularity = Granularity::parse(&granularity)?;
+        let fill = match fill {
+            Some(fill) => GapFill::parse(&fill)?,
+            None => GapFill::Null,
+        };
+
+        let conn = &self.conn;
+        let area_ids = match area_ids {
+            Some(area_ids) => area_ids,
+            None => {
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT id FROM life_areas WHERE is_active = 1 ORDER BY \"order\", name",
+                    )
+                    .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+                let ids = stmt
+                    .query_map([], |row| row.get::<_, i64>(0))
+                    .map_err(|e| format!("Failed to query life areas: {}", e))?;
+                ids.collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("Failed to parse area: {}", e))?
+            }
+        };
+        if area_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (first_recorded, last_recorded): (Option<i64>, Option<i64>) = conn
+            .query_row(
+                &format!(
+                    "SELECT MIN(recorded_at), MAX(recorded_at) FROM scores WHERE area_id IN ({})",
+                    sql_placeholders(area_ids.len())
+                ),
+                params_from_iter(&area_ids),
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| format!("Failed to query score range: {}", e))?;
+
+        let starts = match (from.or(first_recorded), to.or(last_recorded)) {
+            (Some(from), Some(to)) => {
+                if from > to {
+                    return Err("Start of range must not be after its end".to_string());
+                }
+                let from_date = habits::local_date(from).ok_or("Invalid start of range")?;
+                let to_date = habits::local_date(to).ok_or("Invalid end of range")?;
+                aggregation::bucket_starts(granularity, from_date, to_date, MAX_SCORE_BUCKETS)
+                    .ok_or(format!(
+                        "Range cannot span more than {} buckets",
+                        MAX_SCORE_BUCKETS
+                    ))?
+            }
+            _ => Vec::new(),
+        };
+
+        let range = match (starts.first(), starts.last()) {
+            (Some(first), Some(last)) => Some((
+                aggregation::local_midnight(*first).ok_or("Invalid start of range")?,
+                aggregation::local_midnight(granularity.next_start(*last))
+                    .ok_or("Invalid end of range")?,
+            )),
+            _ => None,
+        };
+
+        let mut result = Vec::new();
+        for area_id in area_ids {
+            let Some((range_start, range_end)) = range else {
+                result.push(AreaScoreBuckets {
+                    area_id,
+                    buckets: Vec::new(),
+                });
+                continue;
+            };
+
+            let carried: Option<i64> = conn
+                .query_row(
+                    "SELECT value FROM scores WHERE area_id = ?1 AND recorded_at < ?2
+                     ORDER BY recorded_at DESC, id DESC LIMIT 1",
+                    params![area_id, range_start],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to query score: {}", e))?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT recorded_at, value FROM scores
+                     WHERE area_id = ?1 AND recorded_at >= ?2 AND recorded_at < ?3
+                     ORDER BY recorded_at ASC, id ASC",
+                )
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+            let points = stmt
+                .query_map(params![area_id, range_start, range_end], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+                })
+                .map_err(|e| format!("Failed to query scores: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to parse score: {}", e))?;
+
+            result.push(AreaScoreBuckets {
+                area_id,
+                buckets: aggregation::bucket_scores(&points, granularity, &starts, fill, carried),
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// The wheel as it looked at `timestamp`: every area active then, with its
+    /// name and color at that time and the latest score recorded on or before it.
+    /// Areas created later are included when scores were backfilled for them,
+    /// and deleted areas are rebuilt from the change log.
+    pub fn get_wheel_as_of(&self, timestamp: i64) -> Result<Vec<WheelEntry>, String> {
+        let conn = &self.conn;
+
+        let mut area_ids: BTreeSet<i64> = BTreeSet::new();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id FROM life_areas
+                 UNION SELECT entity_id FROM change_log WHERE entity_type = 'life_areas'",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, i64>(0))
+            .map_err(|e| format!("Failed to query areas: {}", e))?;
+        for id in rows {
+            area_ids.insert(id.map_err(|e| format!("Failed to parse area: {}", e))?);
+        }
+
+        let mut entries = Vec::new();
+        for id in area_ids {
+            let current = audit::snapshot(conn, "life_areas", "rowid = ?", params![id])?
+                .pop()
+                .map(|row| row.values);
+            let Some(mut values) = area_as_of(conn, id, current, timestamp)? else {
+                continue;
+            };
+
+            let created_at = values.get("created_at").and_then(|v| v.as_i64());
+            let updated_at = values.get("updated_at").and_then(|v| v.as_i64());
+            let is_active = values.get("is_active").and_then(|v| v.as_i64()) == Some(1)
+                // Soft deletes made before the change log existed are only known
+                // by their `updated_at`.
+                || updated_at.is_some_and(|updated_at| updated_at > timestamp);
+            if !is_active {
+                continue;
+            }
+
+            let score = score_as_of(conn, id, timestamp)?;
+            if score.is_none() && created_at.is_none_or(|created_at| created_at > timestamp) {
+                continue;
+            }
+
+            values.insert("is_active".to_string(), serde_json::Value::Bool(true));
+            let area: LifeArea = serde_json::from_value(serde_json::Value::Object(values))
+                .map_err(|e| format!("Failed to parse area: {}", e))?;
+
+            entries.push(WheelEntry { area, score });
+        }
+
+        entries.sort_by(|a, b| {
+            a.area
+                .order
+                .cmp(&b.area.order)
+                .then_with(|| a.area.name.cmp(&b.area.name))
+        });
+
+        Ok(entries)
+    }
+}
+
+/// The area as it was at `timestamp` or, if it was created later, as it was
+/// created. `None` if it was deleted by then or is unknown.
+fn area_as_of(
+    conn: &Connection,
+    id: i64,
+    current: Option<Map<String, Value>>,
+    timestamp: i64,
+) -> Result<Option<Map<String, Value>>, String> {
+    if let Some(values) = audit::row_as_of(conn, "life_areas", id, current, timestamp)? {
+        return Ok(Some(values));
+    }
+
+    let created: Option<Option<String>> = conn
+        .query_row(
+            "SELECT new_value FROM change_log
+             WHERE entity_type = 'life_areas' AND entity_id = ?1 AND action = ?2
+               AND changed_at > ?3
+             ORDER BY id ASC LIMIT 1",
+            params![id, audit::ACTION_CREATE, timestamp],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to query change log: {}", e))?;
+
+    match created.flatten() {
+        Some(new_value) => serde_json::from_str(&new_value)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse change log value: {}", e)),
+        None => Ok(None),
+    }
+}
+
+/// The latest score of the area recorded on or before `timestamp`, including
+/// scores deleted after it that are still in the change log.
+pub(super) fn score_as_of(
+    conn: &Connection,
+    area_id: i64,
+    timestamp: i64,
+) -> Result<Option<Score>, String> {
+    let current = conn
+        .query_row(
+            "SELECT id, area_id, value, recorded_at, note FROM scores
+             WHERE area_id = ?1 AND recorded_at <= ?2
+             ORDER BY recorded_at DESC, id DESC LIMIT 1",
+            params![area_id, timestamp],
+            score_from_row,
+        )
+        .optional()
+        .map_err(|e| format!("Failed to query score: {}", e))?;
+
+    let deleted: Option<String> = conn
+        .query_row(
+            "SELECT c.old_value FROM change_log c
+             WHERE c.entity_type = 'scores' AND c.action = ?1 AND c.changed_at > ?2
+               AND json_extract(c.old_value, '$.area_id') = ?3
+               AND json_extract(c.old_value, '$.recorded_at') <= ?2
+               AND NOT EXISTS (SELECT 1 FROM scores s WHERE s.id = c.entity_id)
+             ORDER BY json_extract(c.old_value, '$.recorded_at') DESC, c.entity_id DESC
+             LIMIT 1",
+            params![audit::ACTION_DELETE, timestamp, area_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to query change log: {}", e))?;
+    let deleted: Option<Score> = deleted
+        .map(|old_value| serde_json::from_str(&old_value))
+        .transpose()
+        .map_err(|e| format!("Failed to parse change log value: {}", e))?;
+
+    Ok(match (current, deleted) {
+        (Some(current), Some(deleted)) => Some(
+            if (deleted.recorded_at, deleted.id) > (current.recorded_at, current.id) {
+                deleted
+            } else {
+                current
+            },
+        ),
+        (current, deleted) => current.or(deleted),
+    })
+}
+
+fn validate_score_page_limit(limit: i64) -> Result<(), String> {
+    if !(1..=MAX_SCORE_PAGE_LIMIT).contains(&limit) {
+        return Err(format!(
+            "Limit must be between 1 and {}",
+            MAX_SCORE_PAGE_LIMIT
+        ));
+    }
+    Ok(())
+}
+
+pub(super) fn score_from_row(row: &Row) -> rusqlite::Result<Score> {
+    Ok(Score {
+        id: row.get(0)?,
+        area_id: row.get(1)?,
+        value: row.get(2)?,
+        recorded_at: row.get(3)?,
+        note: row.get(4)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_with_area() -> (Store, i64) {
+        let store = Store::open_in_memory().unwrap();
+        let area = store
+            .create_life_area("Health".to_string(), None, "#10b981".to_string(), 0)
+            .unwrap();
+        (store, area.id)
+    }
+
+    fn all_pages(store: &Store, area_id: i64, ascending: bool) -> Vec<i64> {
+        let mut ids = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = store
+                .get_scores_page(vec![area_id], None, None, Some(2), cursor, Some(ascending))
+                .unwrap();
+            assert!(page.scores.len() <= 2);
+            ids.extend(page.scores.iter().map(|score| score.id));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return ids,
+            }
+        }
+    }
+
+    #[test]
+    fn pages_follow_time_then_id_across_equal_timestamps() {
+        let (store, area_id) = store_with_area();
+        let mut ids = Vec::new();
+        for (value, recorded_at) in [(5, 1_000), (6, 1_000), (7, 2_000), (8, 1_000), (9, 3_000)] {
+            let score = store
+                .create_score(area_id, value, Some(recorded_at), None)
+                .unwrap();
+            ids.push(score.id);
+        }
+
+        let newest_first = all_pages(&store, area_id, false);
+        assert_eq!(newest_first, [ids[4], ids[2], ids[3], ids[1], ids[0]]);
+
+        let oldest_first = all_pages(&store, area_id, true);
+        assert_eq!(oldest_first, [ids[0], ids[1], ids[3], ids[2], ids[4]]);
+    }
+
+    #[test]
+    fn pages_stay_stable_while_scores_are_added() {
+        let (store, area_id) = store_with_area();
+        for recorded_at in [1_000, 2_000, 3_000] {
+            store
+                .create_score(area_id, 5, Some(recorded_at), None)
+                .unwrap();
+        }
+
+        let first = store
+            .get_scores_page(vec![area_id], None, None, Some(2), None, None)
+            .unwrap();
+        store.create_score(area_id, 9, Some(4_000), None).unwrap();
+        let second = store
+            .get_scores_page(vec![area_id], None, None, Some(2), first.next_cursor, None)
+            .unwrap();
+
+        let times: Vec<i64> = first
+            .scores
+            .iter()
+            .chain(&second.scores)
+            .map(|score| score.recorded_at)
+            .collect();
+        assert_eq!(times, [3_000, 2_000, 1_000]);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn pages_apply_the_time_range_and_validate_the_limit() {
+        let (store, area_id) = store_with_area();
+        for recorded_at in [1_000, 2_000, 3_000] {
+            store
+                .create_score(area_id, 5, Some(recorded_at), None)
+                .unwrap();
+        }
+
+        let page = store
+            .get_scores_page(vec![area_id], Some(1_500), Some(2_500), None, None, None)
+            .unwrap();
+        assert_eq!(page.scores.len(), 1);
+        assert_eq!(page.scores[0].recorded_at, 2_000);
+
+        assert!(store
+            .get_scores_page(vec![area_id], None, None, Some(0), None, None)
+            .is_err());
+    }
+
+    #[test]
+    fn wheel_as_of_includes_backfilled_scores_for_newer_areas() {
+        let (store, area_id) = store_with_area();
+        let week_ago = Utc::now().timestamp() - 7 * 86_400;
+        store
+            .create_life_area("Finance".to_string(), None, "#f59e0b".to_string(), 1)
+            .unwrap();
+        let score = store
+            .create_score(area_id, 6, Some(week_ago), None)
+            .unwrap();
+
+        let wheel = store.get_wheel_as_of(week_ago + 60).unwrap();
+        assert_eq!(wheel.len(), 1);
+        assert_eq!(wheel[0].area.name, "Health");
+        assert_eq!(wheel[0].score.as_ref().map(|s| s.id), Some(score.id));
+
+        assert!(store.get_wheel_as_of(week_ago - 60).unwrap().is_empty());
+    }
+
+    #[test]
+    fn wheel_as_of_rebuilds_deleted_areas() {
+        let (store, area_id) = store_with_area();
+        let week_ago = Utc::now().timestamp() - 7 * 86_400;
+        store
+            .update_life_area(area_id, "Body".to_string(), None, "#10b981".to_string(), 0)
+            .unwrap();
+        store
+            .create_score(area_id, 4, Some(week_ago), None)
+            .unwrap();
+        let latest = store
+            .create_score(area_id, 8, Some(week_ago + 3600), None)
+            .unwrap();
+
+        store.reset_all_data().unwrap();
+        assert!(store.get_life_areas(true).unwrap().is_empty());
+
+        let wheel = store.get_wheel_as_of(week_ago + 7200).unwrap();
+        assert_eq!(wheel.len(), 1);
+        assert_eq!(wheel[0].area.id, area_id);
+        assert_eq!(wheel[0].area.name, "Health");
+        let score = wheel[0].score.as_ref().unwrap();
+        assert_eq!((score.id, score.value), (latest.id, 8));
+
+        assert!(store
+            .get_wheel_as_of(Utc::now().timestamp() + 60)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,364 @@
+use super::Store;
+use crate::audit;
+use crate::models::{ActionItemStep, ReorderUpdate};
+use chrono::Utc;
+use rusqlite::{params, Connection, Row};
+use std::collections::{HashMap, HashSet};
+
+const MAX_ACTION_ITEM_STEP_TITLE_LENGTH: usize = 120;
+
+impl Store {
+    /// Adds a step at the end of the item's checklist or, with `parent_id`,
+    /// nested under another step of the same item.
+    pub fn create_action_item_step(
+        &self,
+        action_item_id: i64,
+        title: String,
+        parent_id: Option<i64>,
+    ) -> Result<ActionItemStep, String> {
+        let clean_title = normalize_step_title(&title)?;
+
+        self.with_operation("create_action_item_step", |conn, op| {
+            let exists = conn
+                .prepare("SELECT id FROM action_items WHERE id = ?")
+                .and_then(|mut stmt| stmt.exists(params![action_item_id]))
+                .map_err(|e| format!("Failed to check action item: {}", e))?;
+            if !exists {
+                return Err("Action item not found".to_string());
+            }
+            if let Some(parent_id) = parent_id {
+                let parent = get_action_item_step(conn, parent_id)
+                    .map_err(|_| "Parent step not found".to_string())?;
+                if parent.action_item_id != action_item_id {
+                    return Err("Parent step belongs to another action item".to_string());
+                }
+            }
+
+            let now = Utc::now().timestamp();
+            let next_position: i64 = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(position), -1) + 1 FROM action_item_steps
+                     WHERE action_item_id = ?1 AND parent_id IS ?2",
+                    params![action_item_id, parent_id],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+
+            conn.execute(
+                "INSERT INTO action_item_steps (action_item_id, title, is_done, position, created_at, parent_id)
+                 VALUES (?1, ?2, 0, ?3, ?4, ?5)",
+                params![action_item_id, clean_title, next_position, now, parent_id],
+            )
+            .map_err(|e| format!("Failed to insert step: {}", e))?;
+
+            let id = conn.last_insert_rowid();
+            op.created(conn, "action_item_steps", "id = ?", params![id])?;
+
+            Ok(ActionItemStep {
+                id,
+                action_item_id,
+                title: clean_title,
+                is_done: false,
+                position: next_position,
+                created_at: now,
+                parent_id,
+            })
+        })
+    }
+
+    /// The item's steps with every step followed by the steps nested under
+    /// it, siblings in position order.
+    pub fn get_action_item_steps(
+        &self,
+        action_item_id: i64,
+    ) -> Result<Vec<ActionItemStep>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, action_item_id, title, is_done, position, created_at, parent_id
+             FROM action_item_steps
+             WHERE action_item_id = ?
+             ORDER BY position ASC, created_at ASC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![action_item_id], action_item_step_from_row)
+            .map_err(|e| format!("Failed to query steps: {}", e))?;
+
+        let mut result = Vec::new();
+        for step in rows {
+            result.push(step.map_err(|e| format!("Failed to parse step: {}", e))?);
+        }
+
+        Ok(tree_order(result))
+    }
+
+    pub fn update_action_item_step(
+        &self,
+        id: i64,
+        title: String,
+    ) -> Result<ActionItemStep, String> {
+        let clean_title = normalize_step_title(&title)?;
+
+        self.with_operation("update_action_item_step", |conn, op| {
+            let before = audit::snapshot(conn, "action_item_steps", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE action_item_steps SET title = ?1 WHERE id = ?2",
+                params![clean_title, id],
+            )
+            .map_err(|e| format!("Failed to update step: {}", e))?;
+            op.updated(conn, "action_item_steps", before)?;
+
+            get_action_item_step(conn, id)
+        })
+    }
+
+    pub fn set_action_item_step_done(
+        &self,
+        id: i64,
+        is_done: bool,
+    ) -> Result<ActionItemStep, String> {
+        self.with_operation("set_action_item_step_done", |conn, op| {
+            let before = audit::snapshot(conn, "action_item_steps", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE action_item_steps SET is_done = ?1 WHERE id = ?2",
+                params![is_done, id],
+            )
+            .map_err(|e| format!("Failed to update step: {}", e))?;
+            op.updated(conn, "action_item_steps", before)?;
+
+            get_action_item_step(conn, id)
+        })
+    }
+
+    /// Deletes the step together with the steps nested under it.
+    pub fn delete_action_item_step(&self, id: i64) -> Result<(), String> {
+        self.with_operation("delete_action_item_step", |conn, op| {
+            op.delete_where(
+                conn,
+                "action_item_steps",
+                "id IN (WITH RECURSIVE nested(id) AS (
+                     SELECT ?1
+                     UNION ALL
+                     SELECT s.id FROM action_item_steps s INNER JOIN nested n ON s.parent_id = n.id
+                 ) SELECT id FROM nested)",
+                params![id],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn reorder_action_item_steps(&self, updates: Vec<ReorderUpdate>) -> Result<(), String> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        self.with_operation("reorder_action_item_steps", |conn, op| {
+            for update in updates {
+                let before =
+                    audit::snapshot(conn, "action_item_steps", "id = ?", params![update.id])?;
+                conn.execute(
+                    "UPDATE action_item_steps SET position = ?1 WHERE id = ?2",
+                    params![update.position, update.id],
+                )
+                .map_err(|e| format!("Failed to update step position: {}", e))?;
+                op.updated(conn, "action_item_steps", before)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+fn normalize_step_title(title: &str) -> Result<String, String> {
+    let clean_title = title.trim();
+    if clean_title.is_empty() {
+        return Err("Step title cannot be empty".to_string());
+    }
+    if clean_title.chars().count() > MAX_ACTION_ITEM_STEP_TITLE_LENGTH {
+        return Err(format!(
+            "Step title cannot exceed {} characters",
+            MAX_ACTION_ITEM_STEP_TITLE_LENGTH
+        ));
+    }
+    Ok(clean_title.to_string())
+}
+
+/// Puts every step after its parent, keeping the order of siblings. Steps
+/// whose parent is missing are kept at the top level.
+fn tree_order(steps: Vec<ActionItemStep>) -> Vec<ActionItemStep> {
+    let ids: HashSet<i64> = steps.iter().map(|step| step.id).collect();
+    let mut children: HashMap<Option<i64>, Vec<ActionItemStep>> = HashMap::new();
+    for step in steps {
+        let parent_id = step.parent_id.filter(|parent_id| ids.contains(parent_id));
+        children.entry(parent_id).or_default().push(step);
+    }
+
+    let mut ordered = Vec::new();
+    let mut pending: Vec<ActionItemStep> = children.remove(&None).unwrap_or_default();
+    pending.reverse();
+    while let Some(step) = pending.pop() {
+        if let Some(nested) = children.remove(&Some(step.id)) {
+            pending.extend(nested.into_iter().rev());
+        }
+        ordered.push(step);
+    }
+    ordered
+}
+
+fn get_action_item_step(conn: &Connection, id: i64) -> Result<ActionItemStep, String> {
+    conn.query_row(
+        "SELECT id, action_item_id, title, is_done, position, created_at, parent_id FROM action_item_steps WHERE id = ?",
+        params![id],
+        action_item_step_from_row,
+    )
+    .map_err(|e| format!("Failed to get step: {}", e))
+}
+
+pub(super) fn action_item_step_from_row(row: &Row) -> rusqlite::Result<ActionItemStep> {
+    Ok(ActionItemStep {
+        id: row.get(0)?,
+        action_item_id: row.get(1)?,
+        title: row.get(2)?,
+        is_done: row.get(3)?,
+        position: row.get(4)?,
+        created_at: row.get(5)?,
+        parent_id: row.get(6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_with_item() -> (Store, i64) {
+        let store = Store::open_in_memory().unwrap();
+        let area = store
+            .create_life_area("Health".to_string(), None, "#10b981".to_string(), 0)
+            .unwrap();
+        let item = store
+            .create_action_item(area.id, "Run a 10k".to_string(), None)
+            .unwrap();
+        (store, item.id)
+    }
+
+    fn step_titles(store: &Store, item_id: i64) -> Vec<(String, Option<i64>)> {
+        store
+            .get_action_item_steps(item_id)
+            .unwrap()
+            .into_iter()
+            .map(|step| (step.title, step.parent_id))
+            .collect()
+    }
+
+    #[test]
+    fn creating_a_step_for_a_missing_item_fails() {
+        let (store, item_id) = store_with_item();
+        let err = store
+            .create_action_item_step(item_id + 1, "Buy shoes".to_string(), None)
+            .unwrap_err();
+        assert_eq!(err, "Action item not found");
+    }
+
+    #[test]
+    fn nested_steps_follow_their_parent() {
+        let (store, item_id) = store_with_item();
+        let gear = store
+            .create_action_item_step(item_id, "Gear".to_string(), None)
+            .unwrap();
+        let plan = store
+            .create_action_item_step(item_id, "Plan".to_string(), None)
+            .unwrap();
+        let shoes = store
+            .create_action_item_step(item_id, "Shoes".to_string(), Some(gear.id))
+            .unwrap();
+        store
+            .create_action_item_step(item_id, "Socks".to_string(), Some(gear.id))
+            .unwrap();
+        store
+            .create_action_item_step(item_id, "Insoles".to_string(), Some(shoes.id))
+            .unwrap();
+
+        assert_eq!(shoes.position, 0);
+        assert_eq!(
+            step_titles(&store, item_id),
+            [
+                ("Gear".to_string(), None),
+                ("Shoes".to_string(), Some(gear.id)),
+                ("Insoles".to_string(), Some(shoes.id)),
+                ("Socks".to_string(), Some(gear.id)),
+                ("Plan".to_string(), None),
+            ]
+        );
+        assert_eq!(plan.position, 1);
+    }
+
+    #[test]
+    fn parent_must_belong_to_the_same_item() {
+        let (store, item_id) = store_with_item();
+        let other = store
+            .create_action_item(
+                store.get_life_areas(false).unwrap()[0].id,
+                "Sleep more".to_string(),
+                None,
+            )
+            .unwrap();
+        let parent = store
+            .create_action_item_step(other.id, "Dark curtains".to_string(), None)
+            .unwrap();
+
+        let err = store
+            .create_action_item_step(item_id, "Shoes".to_string(), Some(parent.id))
+            .unwrap_err();
+        assert_eq!(err, "Parent step belongs to another action item");
+        let err = store
+            .create_action_item_step(item_id, "Shoes".to_string(), Some(parent.id + 1))
+            .unwrap_err();
+        assert_eq!(err, "Parent step not found");
+    }
+
+    #[test]
+    fn deleting_a_step_deletes_its_nested_steps() {
+        let (store, item_id) = store_with_item();
+        let gear = store
+            .create_action_item_step(item_id, "Gear".to_string(), None)
+            .unwrap();
+        let shoes = store
+            .create_action_item_step(item_id, "Shoes".to_string(), Some(gear.id))
+            .unwrap();
+        store
+            .create_action_item_step(item_id, "Insoles".to_string(), Some(shoes.id))
+            .unwrap();
+        store
+            .create_action_item_step(item_id, "Plan".to_string(), None)
+            .unwrap();
+
+        store.delete_action_item_step(gear.id).unwrap();
+        assert_eq!(step_titles(&store, item_id), [("Plan".to_string(), None)]);
+    }
+
+    #[test]
+    fn next_occurrence_keeps_nested_steps() {
+        let (store, item_id) = store_with_item();
+        let gear = store
+            .create_action_item_step(item_id, "Gear".to_string(), None)
+            .unwrap();
+        store
+            .create_action_item_step(item_id, "Shoes".to_string(), Some(gear.id))
+            .unwrap();
+        store
+            .set_action_item_due_date(item_id, Some(Utc::now().timestamp()))
+            .unwrap();
+        store
+            .set_action_item_recurrence(item_id, Some("FREQ=WEEKLY".to_string()))
+            .unwrap();
+
+        let next = store.archive_action_item(item_id).unwrap().unwrap();
+        let steps = store.get_action_item_steps(next.id).unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].title, "Gear");
+        assert_eq!(steps[1].title, "Shoes");
+        assert_eq!(steps[1].parent_id, Some(steps[0].id));
+    }
+}
@@ -0,0 +1,305 @@
+use super::Store;
+use crate::audit;
+use crate::models::Tag;
+use chrono::Utc;
+use rusqlite::{params, Connection, Row};
+
+const MAX_TAG_NAME_LENGTH: usize = 32;
+
+impl Store {
+    pub fn create_tag(&self, name: String) -> Result<Tag, String> {
+        let clean_name = normalize_tag_name(&name)?;
+
+        self.with_operation("create_tag", |conn, op| {
+            let now = Utc::now().timestamp();
+
+            let mut stmt = conn
+                .prepare("SELECT id FROM tags WHERE name = ?")
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            if stmt
+                .exists(params![clean_name])
+                .map_err(|e| format!("Failed to check tag name: {}", e))?
+            {
+                return Err("A tag with this name already exists".to_string());
+            }
+
+            conn.execute(
+                "INSERT INTO tags (name, created_at) VALUES (?1, ?2)",
+                params![clean_name, now],
+            )
+            .map_err(|e| format!("Failed to insert tag: {}", e))?;
+
+            let id = conn.last_insert_rowid();
+            op.created(conn, "tags", "id = ?", params![id])?;
+
+            Ok(Tag {
+                id,
+                name: clean_name,
+                created_at: now,
+            })
+        })
+    }
+
+    pub fn get_tags(&self) -> Result<Vec<Tag>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare("SELECT id, name, created_at FROM tags ORDER BY name")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let tags = stmt
+            .query_map([], tag_from_row)
+            .map_err(|e| format!("Failed to query tags: {}", e))?;
+
+        let mut result = Vec::new();
+        for tag in tags {
+            result.push(tag.map_err(|e| format!("Failed to parse tag: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn rename_tag(&self, id: i64, name: String) -> Result<Tag, String> {
+        let clean_name = normalize_tag_name(&name)?;
+
+        self.with_operation("rename_tag", |conn, op| {
+            let mut stmt = conn
+                .prepare("SELECT id FROM tags WHERE name = ? AND id != ?")
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            if stmt
+                .exists(params![clean_name, id])
+                .map_err(|e| format!("Failed to check tag name: {}", e))?
+            {
+                return Err("A tag with this name already exists".to_string());
+            }
+
+            let before = audit::snapshot(conn, "tags", "id = ?", params![id])?;
+            conn.execute(
+                "UPDATE tags SET name = ?1 WHERE id = ?2",
+                params![clean_name, id],
+            )
+            .map_err(|e| format!("Failed to rename tag: {}", e))?;
+            op.updated(conn, "tags", before)?;
+
+            get_tag(conn, id)
+        })
+    }
+
+    pub fn delete_tag(&self, id: i64) -> Result<(), String> {
+        self.with_operation("delete_tag", |conn, op| {
+            op.delete_where(conn, "action_item_tags", "tag_id = ?", params![id])?;
+            op.delete_where(conn, "tags", "id = ?", params![id])?;
+
+            Ok(())
+        })
+    }
+
+    pub fn get_action_item_tags(&self, action_item_id: i64) -> Result<Vec<Tag>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT t.id, t.name, t.created_at
+             FROM tags t
+             INNER JOIN action_item_tags ait ON ait.tag_id = t.id
+             WHERE ait.action_item_id = ?
+             ORDER BY t.name",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let tags = stmt
+            .query_map(params![action_item_id], tag_from_row)
+            .map_err(|e| format!("Failed to query tags: {}", e))?;
+
+        let mut result = Vec::new();
+        for tag in tags {
+            result.push(tag.map_err(|e| format!("Failed to parse tag: {}", e))?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn assign_tags(&self, action_item_ids: Vec<i64>, tag_ids: Vec<i64>) -> Result<(), String> {
+        if action_item_ids.is_empty() || tag_ids.is_empty() {
+            return Ok(());
+        }
+
+        self.with_operation("assign_tags", |conn, op| {
+            for tag_id in &tag_ids {
+                let exists = conn
+                    .prepare("SELECT id FROM tags WHERE id = ?")
+                    .and_then(|mut stmt| stmt.exists(params![tag_id]))
+                    .map_err(|e| format!("Failed to check tag: {}", e))?;
+                if !exists {
+                    return Err("Tag not found".to_string());
+                }
+            }
+
+            for action_item_id in &action_item_ids {
+                let exists = conn
+                    .prepare("SELECT id FROM action_items WHERE id = ?")
+                    .and_then(|mut stmt| stmt.exists(params![action_item_id]))
+                    .map_err(|e| format!("Failed to check action item: {}", e))?;
+                if !exists {
+                    return Err("Action item not found".to_string());
+                }
+
+                for tag_id in &tag_ids {
+                    let inserted = conn
+                        .execute(
+                            "INSERT OR IGNORE INTO action_item_tags (action_item_id, tag_id) VALUES (?1, ?2)",
+                            params![action_item_id, tag_id],
+                        )
+                        .map_err(|e| format!("Failed to assign tag: {}", e))?;
+                    if inserted > 0 {
+                        op.created(
+                            conn,
+                            "action_item_tags",
+                            "rowid = ?",
+                            params![conn.last_insert_rowid()],
+                        )?;
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    pub fn unassign_tags(
+        &self,
+        action_item_ids: Vec<i64>,
+        tag_ids: Vec<i64>,
+    ) -> Result<(), String> {
+        if action_item_ids.is_empty() || tag_ids.is_empty() {
+            return Ok(());
+        }
+
+        self.with_operation("unassign_tags", |conn, op| {
+            for action_item_id in &action_item_ids {
+                for tag_id in &tag_ids {
+                    op.delete_where(
+                        conn,
+                        "action_item_tags",
+                        "action_item_id = ?1 AND tag_id = ?2",
+                        params![action_item_id, tag_id],
+                    )?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+pub(super) fn normalize_tag_name(name: &str) -> Result<String, String> {
+    let clean_name = name.trim().trim_start_matches('#').to_lowercase();
+    if clean_name.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    if clean_name.chars().any(char::is_whitespace) {
+        return Err("Tag name cannot contain spaces".to_string());
+    }
+    if clean_name.chars().count() > MAX_TAG_NAME_LENGTH {
+        return Err(format!(
+            "Tag name cannot exceed {} characters",
+            MAX_TAG_NAME_LENGTH
+        ));
+    }
+    Ok(clean_name)
+}
+
+fn get_tag(conn: &Connection, id: i64) -> Result<Tag, String> {
+    conn.query_row(
+        "SELECT id, name, created_at FROM tags WHERE id = ?",
+        params![id],
+        tag_from_row,
+    )
+    .map_err(|e| format!("Failed to get tag: {}", e))
+}
+
+pub(super) fn tag_from_row(row: &Row) -> rusqlite::Result<Tag> {
+    Ok(Tag {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_at: row.get(2)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TagFilter;
+
+    fn item_titles(store: &Store, filter: TagFilter) -> Vec<String> {
+        store
+            .get_all_action_items(None, Some(filter))
+            .unwrap()
+            .into_iter()
+            .map(|item| item.title)
+            .collect()
+    }
+
+    #[test]
+    fn tag_names_are_normalized_and_unique() {
+        let store = Store::open_in_memory().unwrap();
+        let tag = store.create_tag(" #Quick-Win ".to_string()).unwrap();
+
+        assert_eq!(tag.name, "quick-win");
+        assert!(store.create_tag("quick-win".to_string()).is_err());
+        assert!(store.create_tag("two words".to_string()).is_err());
+        assert!(store.create_tag("#".to_string()).is_err());
+    }
+
+    #[test]
+    fn items_are_filtered_by_their_tags() {
+        let store = Store::open_in_memory().unwrap();
+        let area = store
+            .create_life_area("Health".to_string(), None, "#10b981".to_string(), 0)
+            .unwrap();
+        let run = store
+            .create_action_item(area.id, "Run a 10k".to_string(), None)
+            .unwrap();
+        let stretch = store
+            .create_action_item(area.id, "Stretch".to_string(), None)
+            .unwrap();
+        store
+            .create_action_item(area.id, "Sleep early".to_string(), None)
+            .unwrap();
+        let quick = store.create_tag("quick".to_string()).unwrap();
+        let outdoor = store.create_tag("outdoor".to_string()).unwrap();
+
+        store
+            .assign_tags(vec![run.id, stretch.id], vec![quick.id])
+            .unwrap();
+        store.assign_tags(vec![run.id], vec![outdoor.id]).unwrap();
+
+        let all_of = TagFilter {
+            all_of: vec![quick.id, outdoor.id],
+            ..TagFilter::default()
+        };
+        assert_eq!(item_titles(&store, all_of), ["Run a 10k"]);
+        let any_of = TagFilter {
+            any_of: vec![quick.id, outdoor.id],
+            ..TagFilter::default()
+        };
+        assert_eq!(item_titles(&store, any_of), ["Run a 10k", "Stretch"]);
+        let none_of = TagFilter {
+            none_of: vec![outdoor.id],
+            ..TagFilter::default()
+        };
+        assert_eq!(item_titles(&store, none_of), ["Stretch", "Sleep early"]);
+
+        store.unassign_tags(vec![run.id], vec![quick.id]).unwrap();
+        let names: Vec<String> = store
+            .get_action_item_tags(run.id)
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        assert_eq!(names, ["outdoor"]);
+
+        store.delete_tag(outdoor.id).unwrap();
+        assert!(store.get_action_item_tags(run.id).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,409 @@
+use super::action_items::{
+    action_item_from_row, archive_action_item, normalize_action_item_title, reopen_action_item,
+};
+use super::areas::query_active_life_areas;
+use super::tags::normalize_tag_name;
+use super::Store;
+use crate::aggregation::local_midnight;
+use crate::audit::{self, Operation};
+use crate::habits::local_date;
+use crate::models::{ActionItem, LifeArea};
+use crate::tasks::{
+    project_name, read_task_list, write_task_list, PlainTask, SkippedTask, TaskImportSummary,
+    TaskListFormat,
+};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+
+/// A task of an imported list matched to its area and, if it was exported
+/// before, to its action item.
+struct PlannedTask {
+    task: PlainTask,
+    area_id: i64,
+    title: String,
+    tags: Vec<String>,
+    existing: Option<ActionItem>,
+}
+
+impl Store {
+    /// The action items of active areas as a todo.txt or Markdown task list,
+    /// archived ones marked done. `format` is `todotxt` or `markdown`.
+    pub fn export_task_list(&self, format: String) -> Result<String, String> {
+        let format = TaskListFormat::parse(&format)?;
+
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT ai.id, ai.area_id, ai.title, ai.created_at, ai.position, ai.archived_at,
+                        ai.description, ai.due_at, ai.recurrence, la.name
+                 FROM action_items ai
+                 INNER JOIN life_areas la ON la.id = ai.area_id
+                 WHERE la.is_active = 1
+                 ORDER BY la.\"order\", ai.position, ai.created_at",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((action_item_from_row(row)?, row.get::<_, String>(9)?))
+            })
+            .map_err(|e| format!("Failed to query action items: {}", e))?;
+
+        let mut tasks = Vec::new();
+        for row in rows {
+            let (item, area) = row.map_err(|e| format!("Failed to parse action item: {}", e))?;
+            tasks.push(PlainTask {
+                tags: query_tag_names(conn, item.id)?,
+                title: item.title,
+                area: Some(area),
+                done: item.archived_at.is_some(),
+                created: local_date(item.created_at),
+                completed: item.archived_at.and_then(local_date),
+                due: item.due_at.and_then(local_date),
+            });
+        }
+
+        Ok(write_task_list(&tasks, format))
+    }
+
+    /// Applies a task list to the action items of active areas, in one
+    /// operation. Tasks are matched to existing items by title, preferring the
+    /// same area; their area, done state, tags, due date and order are taken
+    /// from the list. Other tasks become new action items. Items missing from
+    /// the list are left alone.
+    pub fn import_task_list(
+        &self,
+        contents: &str,
+        format: String,
+    ) -> Result<TaskImportSummary, String> {
+        let format = TaskListFormat::parse(&format)?;
+        let tasks = read_task_list(contents, format);
+
+        self.with_operation("import_task_list", |conn, op| {
+            let areas = query_active_life_areas(conn)?;
+            let mut candidates = query_area_action_items(conn)?;
+            let mut summary = TaskImportSummary::default();
+
+            let mut planned = Vec::new();
+            for (line, task) in tasks {
+                match plan_task(task, &areas, &mut candidates) {
+                    Ok(task) => planned.push(task),
+                    Err(reason) => summary.skipped.push(SkippedTask { line, reason }),
+                }
+            }
+
+            // The listed items take over the positions the matched ones held,
+            // plus new ones after every existing item, in list order.
+            let next_position: i64 = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(position), -1) + 1 FROM action_items",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+            let mut positions: Vec<i64> = planned
+                .iter()
+                .filter_map(|task| task.existing.as_ref().map(|item| item.position))
+                .collect();
+            positions.sort_unstable();
+            let new_count = planned.len() - positions.len();
+            positions.extend((next_position..).take(new_count));
+
+            let now = Utc::now();
+            for (task, position) in planned.into_iter().zip(positions) {
+                match &task.existing {
+                    Some(item) => {
+                        if apply_task(conn, op, item, &task, position, now)? {
+                            summary.updated += 1;
+                        } else {
+                            summary.unchanged += 1;
+                        }
+                    }
+                    None => {
+                        insert_task(conn, op, &task, position, now)?;
+                        summary.created += 1;
+                    }
+                }
+            }
+
+            Ok(summary)
+        })
+    }
+}
+
+fn plan_task(
+    task: PlainTask,
+    areas: &[LifeArea],
+    candidates: &mut Vec<ActionItem>,
+) -> Result<PlannedTask, String> {
+    let area_name = task.area.as_deref().ok_or("No area given")?;
+    let wanted = project_name(area_name).to_lowercase();
+    let area_id = areas
+        .iter()
+        .find(|area| project_name(&area.name).to_lowercase() == wanted)
+        .map(|area| area.id)
+        .ok_or_else(|| format!("No active area named \"{}\"", area_name))?;
+
+    let title = normalize_action_item_title(&task.title)?;
+    let mut tags = Vec::new();
+    for tag in &task.tags {
+        let tag = normalize_tag_name(tag)?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    // Prefers an item in the same area and with the same done state
+    let index = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.title == title)
+        .min_by_key(|(_, item)| {
+            (
+                item.area_id != area_id,
+                item.archived_at.is_some() != task.done,
+            )
+        })
+        .map(|(index, _)| index);
+    let existing = index.map(|index| candidates.remove(index));
+
+    Ok(PlannedTask {
+        task,
+        area_id,
+        title,
+        tags,
+        existing,
+    })
+}
+
+/// Brings `item` in line with `task`, returning whether anything changed.
+fn apply_task(
+    conn: &Connection,
+    op: &Operation,
+    item: &ActionItem,
+    task: &PlannedTask,
+    position: i64,
+    now: DateTime<Utc>,
+) -> Result<bool, String> {
+    let mut changed = false;
+
+    match (task.task.done, item.archived_at) {
+        (true, None) => {
+            archive_action_item(conn, op, item.id, completion_time(&task.task, now))?;
+            changed = true;
+        }
+        (false, Some(_)) => {
+            reopen_action_item(conn, op, item.id)?;
+            changed = true;
+        }
+        _ => {}
+    }
+
+    // Keeps the time of day of an unchanged due date
+    let due_at = match (task.task.due, item.due_at) {
+        (Some(due), Some(due_at)) if local_date(due_at) == Some(due) => Some(due_at),
+        (due, _) => due.and_then(local_midnight),
+    };
+    if item.area_id != task.area_id || item.due_at != due_at || item.position != position {
+        let before = audit::snapshot(conn, "action_items", "id = ?", params![item.id])?;
+        conn.execute(
+            "UPDATE action_items SET area_id = ?1, due_at = ?2, position = ?3 WHERE id = ?4",
+            params![task.area_id, due_at, position, item.id],
+        )
+        .map_err(|e| format!("Failed to update action item: {}", e))?;
+        op.updated(conn, "action_items", before)?;
+        changed = true;
+    }
+
+    let current = query_tag_names(conn, item.id)?;
+    for tag in task.tags.iter().filter(|tag| !current.contains(tag)) {
+        add_tag(conn, op, item.id, tag)?;
+        changed = true;
+    }
+    for tag in current.iter().filter(|tag| !task.tags.contains(tag)) {
+        op.delete_where(
+            conn,
+            "action_item_tags",
+            "action_item_id = ?1 AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+            params![item.id, tag],
+        )?;
+        changed = true;
+    }
+
+    Ok(changed)
+}
+
+fn insert_task(
+    conn: &Connection,
+    op: &Operation,
+    task: &PlannedTask,
+    position: i64,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let created_at = task
+        .task
+        .created
+        .and_then(local_midnight)
+        .unwrap_or(now.timestamp());
+    let archived_at = task
+        .task
+        .done
+        .then(|| completion_time(&task.task, now).timestamp());
+
+    conn.execute(
+        "INSERT INTO action_items (area_id, title, created_at, position, archived_at, due_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            task.area_id,
+            task.title,
+            created_at,
+            position,
+            archived_at,
+            task.task.due.and_then(local_midnight)
+        ],
+    )
+    .map_err(|e| format!("Failed to insert action item: {}", e))?;
+
+    let id = conn.last_insert_rowid();
+    op.created(conn, "action_items", "id = ?", params![id])?;
+
+    for tag in &task.tags {
+        add_tag(conn, op, id, tag)?;
+    }
+
+    Ok(())
+}
+
+/// The completion date of a done task, or `now` without one.
+fn completion_time(task: &PlainTask, now: DateTime<Utc>) -> DateTime<Utc> {
+    task.completed
+        .and_then(local_midnight)
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .unwrap_or(now)
+}
+
+/// Tags the item with `name`, creating the tag if needed.
+fn add_tag(
+    conn: &Connection,
+    op: &Operation,
+    action_item_id: i64,
+    name: &str,
+) -> Result<(), String> {
+    let existing = conn
+        .query_row("SELECT id FROM tags WHERE name = ?", params![name], |row| {
+            row.get::<_, i64>(0)
+        })
+        .ok();
+    let tag_id = match existing {
+        Some(id) => id,
+        None => {
+            conn.execute(
+                "INSERT INTO tags (name, created_at) VALUES (?1, ?2)",
+                params![name, Utc::now().timestamp()],
+            )
+            .map_err(|e| format!("Failed to insert tag: {}", e))?;
+            let id = conn.last_insert_rowid();
+            op.created(conn, "tags", "id = ?", params![id])?;
+            id
+        }
+    };
+
+    conn.execute(
+        "INSERT INTO action_item_tags (action_item_id, tag_id) VALUES (?1, ?2)",
+        params![action_item_id, tag_id],
+    )
+    .map_err(|e| format!("Failed to assign tag: {}", e))?;
+    op.created(
+        conn,
+        "action_item_tags",
+        "rowid = ?",
+        params![conn.last_insert_rowid()],
+    )
+}
+
+fn query_tag_names(conn: &Connection, action_item_id: i64) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.name FROM tags t
+             INNER JOIN action_item_tags ait ON ait.tag_id = t.id
+             WHERE ait.action_item_id = ?
+             ORDER BY t.name",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![action_item_id], |row| row.get(0))
+        .map_err(|e| format!("Failed to query tags: {}", e))?;
+
+    let mut result = Vec::new();
+    for name in rows {
+        result.push(name.map_err(|e| format!("Failed to parse tag: {}", e))?);
+    }
+
+    Ok(result)
+}
+
+/// Every action item of an active area.
+fn query_area_action_items(conn: &Connection) -> Result<Vec<ActionItem>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT ai.id, ai.area_id, ai.title, ai.created_at, ai.position, ai.archived_at,
+                    ai.description, ai.due_at, ai.recurrence
+             FROM action_items ai
+             INNER JOIN life_areas la ON la.id = ai.area_id
+             WHERE la.is_active = 1
+             ORDER BY ai.position, ai.created_at",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let rows = stmt
+        .query_map([], action_item_from_row)
+        .map_err(|e| format!("Failed to query action items: {}", e))?;
+
+    let mut result = Vec::new();
+    for item in rows {
+        result.push(item.map_err(|e| format!("Failed to parse action item: {}", e))?);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reimporting_an_export_changes_nothing() {
+        let store = Store::open_in_memory().unwrap();
+        let area = store
+            .create_life_area("Career".to_string(), None, "#3b82f6".to_string(), 0)
+            .unwrap();
+        for title in ["Fix bug #123", "Ask @lead about +1", "x-ray the codebase"] {
+            store
+                .create_action_item(area.id, title.to_string(), None)
+                .unwrap();
+        }
+
+        for format in ["todotxt", "markdown"] {
+            let list = store.export_task_list(format.to_string()).unwrap();
+            let summary = store.import_task_list(&list, format.to_string()).unwrap();
+            assert_eq!(
+                (summary.created, summary.updated, summary.unchanged),
+                (0, 0, 3),
+                "{}",
+                list
+            );
+        }
+
+        let titles: Vec<String> = store
+            .get_action_items_by_area(area.id)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.title)
+            .collect();
+        assert_eq!(
+            titles,
+            ["Fix bug #123", "Ask @lead about +1", "x-ray the codebase"]
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use super::areas::insert_life_area;
+use super::Store;
+use crate::models::LifeArea;
+use crate::templates::WheelTemplate;
+use rusqlite::params;
+
+impl Store {
+    /// Adds the areas of `template` after the existing ones, in the template's
+    /// order, skipping names already used by an active area. Returns the
+    /// areas created.
+    pub fn apply_template(&self, template: &WheelTemplate) -> Result<Vec<LifeArea>, String> {
+        self.with_operation("apply_template", |conn, op| {
+            let mut next_order: i64 = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(\"order\"), -1) + 1 FROM life_areas WHERE is_active = 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("Failed to query area order: {}", e))?;
+
+            let mut areas: Vec<_> = template.areas.iter().collect();
+            areas.sort_by_key(|area| area.order);
+
+            let mut created = Vec::new();
+            for area in areas {
+                let exists = conn
+                    .prepare("SELECT id FROM life_areas WHERE name = ? AND is_active = 1")
+                    .and_then(|mut stmt| stmt.exists(params![area.name]))
+                    .map_err(|e| format!("Failed to check name: {}", e))?;
+                if exists {
+                    continue;
+                }
+
+                created.push(insert_life_area(
+                    conn,
+                    op,
+                    &area.name,
+                    area.description.as_deref(),
+                    &area.color,
+                    next_order,
+                )?);
+                next_order += 1;
+            }
+
+            Ok(created)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::TemplateArea;
+
+    #[test]
+    fn applying_a_template_skips_existing_areas_and_follows_its_order() {
+        let store = Store::open_in_memory().unwrap();
+        store
+            .create_life_area("Health".to_string(), None, "#10b981".to_string(), 0)
+            .unwrap();
+        let area = |name: &str, order: i64| TemplateArea {
+            name: name.to_string(),
+            description: None,
+            color: "#3b82f6".to_string(),
+            order,
+        };
+        let template = WheelTemplate {
+            id: "mine".to_string(),
+            name: "Mine".to_string(),
+            description: None,
+            areas: vec![area("Fun", 2), area("Health", 0), area("Career", 1)],
+            built_in: false,
+        };
+
+        let created = store.apply_template(&template).unwrap();
+        let names: Vec<(&str, i64)> = created
+            .iter()
+            .map(|area| (area.name.as_str(), area.order))
+            .collect();
+        assert_eq!(names, [("Career", 1), ("Fun", 2)]);
+        assert_eq!(store.get_life_areas(false).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,116 @@
+use super::action_items::action_item_from_row;
+use super::Store;
+use crate::models::ActionItem;
+use crate::vault::{MarkdownVault, VaultActionItem, VaultArea};
+use chrono::Utc;
+use rusqlite::params;
+
+impl Store {
+    /// Every active area with its full score history and all its action
+    /// items, open and archived, with their tags, steps and links.
+    pub fn get_markdown_vault(&self) -> Result<MarkdownVault, String> {
+        let mut areas = Vec::new();
+        for area in self.get_life_areas(false)? {
+            let open = self.get_action_items_by_area(area.id)?;
+            let archived = self.query_archived_action_items(area.id)?;
+
+            areas.push(VaultArea {
+                score: self.get_latest_score(area.id)?,
+                scores: self.get_scores_by_area(area.id, None, None, None, None)?,
+                open: self.vault_action_items(open)?,
+                archived: self.vault_action_items(archived)?,
+                area,
+            });
+        }
+
+        Ok(MarkdownVault {
+            generated_at: Utc::now().timestamp(),
+            areas,
+        })
+    }
+
+    fn vault_action_items(&self, items: Vec<ActionItem>) -> Result<Vec<VaultActionItem>, String> {
+        let mut result = Vec::new();
+        for item in items {
+            result.push(VaultActionItem {
+                tags: self.get_action_item_tags(item.id)?,
+                steps: self.get_action_item_steps(item.id)?,
+                links: self.get_action_item_links(item.id)?,
+                item,
+            });
+        }
+        Ok(result)
+    }
+
+    fn query_archived_action_items(&self, area_id: i64) -> Result<Vec<ActionItem>, String> {
+        let conn = &self.conn;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, area_id, title, created_at, position, archived_at, description, due_at, recurrence
+                 FROM action_items
+                 WHERE area_id = ? AND archived_at IS NOT NULL
+                 ORDER BY archived_at DESC, id DESC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![area_id], action_item_from_row)
+            .map_err(|e| format!("Failed to query action items: {}", e))?;
+
+        let mut result = Vec::new();
+        for item in rows {
+            result.push(item.map_err(|e| format!("Failed to parse action item: {}", e))?);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vault_has_an_index_and_one_linked_note_per_area() {
+        let store = Store::open_in_memory().unwrap();
+        for (order, name) in ["Health", "Work/Life", "Health?", "Life Wheel"]
+            .iter()
+            .enumerate()
+        {
+            store
+                .create_life_area(name.to_string(), None, "#10b981".to_string(), order as i64)
+                .unwrap();
+        }
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Journal.md"), "Mine").unwrap();
+
+        let paths = store
+            .get_markdown_vault()
+            .unwrap()
+            .save(dir.path())
+            .unwrap();
+
+        let names: Vec<String> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Life Wheel.md",
+                "Health.md",
+                "Work Life.md",
+                "Health 2.md",
+                "Life Wheel 2.md"
+            ]
+        );
+        let index = std::fs::read_to_string(dir.path().join("Life Wheel.md")).unwrap();
+        assert!(index.contains("| [[Work Life\\|Work/Life]] | - | - | 0 |"));
+        let health = std::fs::read_to_string(dir.path().join("Health.md")).unwrap();
+        assert!(health.contains("Previous: [[Life Wheel 2]]. Next: [[Work Life]]."));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("Journal.md")).unwrap(),
+            "Mine"
+        );
+    }
+}
@@ -0,0 +1,414 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::path::Path;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DUE_KEY: &str = "due:";
+
+/// Plain-text formats action items can be exchanged in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskListFormat {
+    /// One task per line as described at todo.txt: the area is the `+project`,
+    /// tags are `@contexts` and archived items are marked `x` with their
+    /// completion date.
+    TodoTxt,
+    /// Checkbox lists under a heading per area, with tags written as `#tag`.
+    Markdown,
+}
+
+impl TaskListFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.trim().to_lowercase().as_str() {
+            "todotxt" | "todo.txt" | "todo" => Ok(TaskListFormat::TodoTxt),
+            "markdown" | "md" => Ok(TaskListFormat::Markdown),
+            _ => Err("Format must be \"todotxt\" or \"markdown\"".to_string()),
+        }
+    }
+
+    /// `.md` and `.markdown` files are Markdown, anything else todo.txt.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .as_deref()
+        {
+            Some("md") | Some("markdown") => TaskListFormat::Markdown,
+            _ => TaskListFormat::TodoTxt,
+        }
+    }
+}
+
+/// What importing a task list changed.
+#[derive(Debug, Default, Serialize, Clone)]
+pub struct TaskImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub skipped: Vec<SkippedTask>,
+}
+
+/// A task that could not be imported.
+#[derive(Debug, Serialize, Clone)]
+pub struct SkippedTask {
+    /// 1-based line number in the imported file.
+    pub line: usize,
+    pub reason: String,
+}
+
+/// An action item as written to or read from a task list.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PlainTask {
+    pub title: String,
+    /// Area name when writing; when reading, the project or heading as written.
+    pub area: Option<String>,
+    pub tags: Vec<String>,
+    pub done: bool,
+    pub created: Option<NaiveDate>,
+    pub completed: Option<NaiveDate>,
+    pub due: Option<NaiveDate>,
+}
+
+/// `tasks` are expected grouped by area, in wheel and position order. Title
+/// words that look like metadata are escaped with a backslash; see
+/// `escape_title`.
+pub(crate) fn write_task_list(tasks: &[PlainTask], format: TaskListFormat) -> String {
+    match format {
+        TaskListFormat::TodoTxt => tasks
+            .iter()
+            .map(|task| todo_txt_line(task) + "\n")
+            .collect(),
+        TaskListFormat::Markdown => {
+            let mut out = String::new();
+            let mut current_area = None;
+            for task in tasks {
+                if current_area != Some(&task.area) {
+                    if current_area.is_some() {
+                        out.push('\n');
+                    }
+                    out.push_str(&format!("## {}\n\n", task.area.as_deref().unwrap_or("")));
+                    current_area = Some(&task.area);
+                }
+                out.push_str(&markdown_line(task));
+                out.push('\n');
+            }
+            out
+        }
+    }
+}
+
+fn todo_txt_line(task: &PlainTask) -> String {
+    let mut words = Vec::new();
+    if task.done {
+        words.push("x".to_string());
+        // todo.txt only allows a creation date after a completion date
+        if let Some(completed) = task.completed {
+            words.push(completed.format(DATE_FORMAT).to_string());
+        }
+    }
+    if let Some(created) = task
+        .created
+        .filter(|_| !task.done || task.completed.is_some())
+    {
+        words.push(created.format(DATE_FORMAT).to_string());
+    }
+    words.push(escape_title(&task.title, TaskListFormat::TodoTxt));
+    if let Some(area) = &task.area {
+        words.push(format!("+{}", project_name(area)));
+    }
+    for tag in &task.tags {
+        words.push(format!("@{}", tag));
+    }
+    if let Some(due) = task.due {
+        words.push(format!("{}{}", DUE_KEY, due.format(DATE_FORMAT)));
+    }
+    words.join(" ")
+}
+
+fn markdown_line(task: &PlainTask) -> String {
+    let mut line = format!(
+        "- [{}] {}",
+        if task.done { "x" } else { " " },
+        escape_title(&task.title, TaskListFormat::Markdown)
+    );
+    for tag in &task.tags {
+        line.push_str(&format!(" #{}", tag));
+    }
+    if let Some(due) = task.due {
+        line.push_str(&format!(" {}{}", DUE_KEY, due.format(DATE_FORMAT)));
+    }
+    line
+}
+
+/// The title with a backslash before every word that would otherwise be read
+/// back as something else: a tag, project, due date or, at the start of a
+/// todo.txt line, a completion mark, priority or date. Words that start with a
+/// backslash are escaped too, so reading strips exactly one.
+///
+/// The escape is not part of todo.txt or Markdown task lists; it only makes
+/// titles survive a round trip through this module. Other tools show the
+/// backslash as part of the title.
+fn escape_title(title: &str, format: TaskListFormat) -> String {
+    title
+        .split_whitespace()
+        .enumerate()
+        .map(|(index, word)| {
+            let escape = word.starts_with('\\')
+                || parse_due(word).is_some()
+                || match format {
+                    TaskListFormat::TodoTxt => {
+                        has_prefix_marker(word, &['+', '@'])
+                            || (index == 0
+                                && (word == "x"
+                                    || leading_priority(&format!("{} ", word)).is_some()
+                                    || leading_date(word).is_some()))
+                    }
+                    TaskListFormat::Markdown => has_prefix_marker(word, &['#']),
+                };
+            if escape {
+                format!("\\{}", word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `word` is one of `markers` followed by some text, like `+Health`.
+fn has_prefix_marker(word: &str, markers: &[char]) -> bool {
+    word.strip_prefix(markers)
+        .is_some_and(|rest| !rest.is_empty())
+}
+
+/// The `+project` for an area: todo.txt projects cannot contain spaces.
+pub(crate) fn project_name(area: &str) -> String {
+    area.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+/// Every task in `contents` with its 1-based line number. Lines that are not
+/// tasks, such as blank lines or Markdown prose, are ignored.
+pub(crate) fn read_task_list(contents: &str, format: TaskListFormat) -> Vec<(usize, PlainTask)> {
+    let mut tasks = Vec::new();
+    let mut heading: Option<String> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        let task = match format {
+            TaskListFormat::TodoTxt => read_todo_txt_line(line),
+            TaskListFormat::Markdown => {
+                if let Some(title) = markdown_heading(line) {
+                    heading = Some(title.to_string());
+                    continue;
+                }
+                read_markdown_line(line, heading.as_deref())
+            }
+        };
+        if let Some(task) = task {
+            tasks.push((index + 1, task));
+        }
+    }
+
+    tasks
+}
+
+fn read_todo_txt_line(line: &str) -> Option<PlainTask> {
+    if line.is_empty() {
+        return None;
+    }
+
+    let mut task = PlainTask::default();
+    let mut rest = line;
+    if let Some(after) = rest.strip_prefix("x ") {
+        task.done = true;
+        rest = after;
+        if let Some((date, after)) = leading_date(rest) {
+            task.completed = Some(date);
+            rest = after;
+        }
+    } else if let Some(after) = leading_priority(rest) {
+        rest = after;
+    }
+    if let Some((date, after)) = leading_date(rest) {
+        task.created = Some(date);
+        rest = after;
+    }
+
+    let mut title = Vec::new();
+    for word in rest.split_whitespace() {
+        if let Some(escaped) = word.strip_prefix('\\') {
+            title.push(escaped);
+            continue;
+        }
+        if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+            // The first project is the area; any others stay in the title
+            if task.area.is_none() {
+                task.area = Some(project.to_string());
+                continue;
+            }
+        } else if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+            task.tags.push(context.to_string());
+            continue;
+        } else if let Some(due) = parse_due(word) {
+            task.due = Some(due);
+            continue;
+        }
+        title.push(word);
+    }
+    task.title = title.join(" ");
+
+    Some(task)
+}
+
+fn read_markdown_line(line: &str, heading: Option<&str>) -> Option<PlainTask> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))?;
+    let (done, rest) = if let Some(rest) = rest.strip_prefix("[ ]") {
+        (false, rest)
+    } else if let Some(rest) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, rest)
+    } else {
+        return None;
+    };
+
+    let mut task = PlainTask {
+        area: heading.map(str::to_string),
+        done,
+        ..PlainTask::default()
+    };
+    let mut title = Vec::new();
+    for word in rest.split_whitespace() {
+        if let Some(escaped) = word.strip_prefix('\\') {
+            title.push(escaped);
+        } else if let Some(tag) = word.strip_prefix('#').filter(|t| !t.is_empty()) {
+            task.tags.push(tag.to_string());
+        } else if let Some(due) = parse_due(word) {
+            task.due = Some(due);
+        } else {
+            title.push(word);
+        }
+    }
+    task.title = title.join(" ");
+
+    Some(task)
+}
+
+/// The text of a `#`-style Markdown heading.
+fn markdown_heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    if text.len() == line.len() || !text.starts_with(' ') {
+        return None;
+    }
+    Some(text.trim().trim_end_matches('#').trim())
+}
+
+fn leading_date(text: &str) -> Option<(NaiveDate, &str)> {
+    let (word, rest) = text.split_once(' ').unwrap_or((text, ""));
+    let date = NaiveDate::parse_from_str(word, DATE_FORMAT).ok()?;
+    Some((date, rest.trim_start()))
+}
+
+/// Skips a todo.txt priority such as `(A) `.
+fn leading_priority(text: &str) -> Option<&str> {
+    let bytes = text.as_bytes();
+    if bytes.len() >= 4
+        && bytes[0] == b'('
+        && bytes[1].is_ascii_uppercase()
+        && bytes[2] == b')'
+        && bytes[3] == b' '
+    {
+        Some(text[4..].trim_start())
+    } else {
+        None
+    }
+}
+
+fn parse_due(word: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(word.strip_prefix(DUE_KEY)?, DATE_FORMAT).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str) -> PlainTask {
+        PlainTask {
+            title: title.to_string(),
+            area: Some("Health".to_string()),
+            tags: vec!["gym".to_string()],
+            due: NaiveDate::from_ymd_opt(2025, 3, 1),
+            ..PlainTask::default()
+        }
+    }
+
+    fn round_trip(task: &PlainTask, format: TaskListFormat) -> PlainTask {
+        let contents = write_task_list(std::slice::from_ref(task), format);
+        let mut tasks = read_task_list(&contents, format);
+        assert_eq!(tasks.len(), 1, "{}", contents);
+        tasks.remove(0).1
+    }
+
+    #[test]
+    fn titles_that_look_like_metadata_survive_a_round_trip() {
+        let titles = [
+            "Fix bug #123",
+            "Email @alice about +project",
+            "Move due:2025-01-01 to next week",
+            "x marks the spot",
+            "(A) grade paper",
+            "2025-01-01 retrospective",
+            r"Escape \n in C:\temp",
+            "Plain title",
+        ];
+
+        for format in [TaskListFormat::TodoTxt, TaskListFormat::Markdown] {
+            for title in titles {
+                let original = task(title);
+                let read = round_trip(&original, format);
+                assert_eq!(read.title, title, "{:?}", format);
+                assert!(!read.done, "{:?} {}", format, title);
+                assert_eq!(read.tags, original.tags, "{:?} {}", format, title);
+                assert_eq!(read.due, original.due, "{:?} {}", format, title);
+                assert_eq!(
+                    read.area.as_deref(),
+                    Some("Health"),
+                    "{:?} {}",
+                    format,
+                    title
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn done_tasks_with_dates_survive_a_round_trip() {
+        let original = PlainTask {
+            done: true,
+            created: NaiveDate::from_ymd_opt(2025, 1, 2),
+            completed: NaiveDate::from_ymd_opt(2025, 1, 5),
+            ..task("2025-01-03 follow-up")
+        };
+
+        let read = round_trip(&original, TaskListFormat::TodoTxt);
+        assert!(read.done);
+        assert_eq!(read.created, original.created);
+        assert_eq!(read.completed, original.completed);
+        assert_eq!(read.title, "2025-01-03 follow-up");
+    }
+
+    #[test]
+    fn only_ambiguous_words_are_escaped() {
+        let line = write_task_list(&[task("Call +mom at 5")], TaskListFormat::Markdown);
+        assert_eq!(
+            line,
+            "## Health\n\n- [ ] Call +mom at 5 #gym due:2025-03-01\n"
+        );
+
+        let line = write_task_list(&[task("Call +mom at 5")], TaskListFormat::TodoTxt);
+        assert_eq!(line, "Call \\+mom at 5 +Health @gym due:2025-03-01\n");
+    }
+}
@@ -0,0 +1,253 @@
+use crate::models::{Score, WheelExport};
+use crate::store::EXPORT_VERSION;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::path::{Path, PathBuf};
+
+/// Aggregated wheel of several people, built from their exports.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TeamWheel {
+    pub contributors: Vec<TeamContributor>,
+    pub areas: Vec<TeamAreaStats>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TeamContributor {
+    /// The export's label, or `Member N` when anonymized.
+    pub label: String,
+    /// When the export was made; left out when anonymized, since it could
+    /// tell members apart.
+    pub exported_at: Option<i64>,
+}
+
+/// Statistics over the latest score each contributor gave an area. Areas are
+/// matched by name, ignoring case and surrounding whitespace.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TeamAreaStats {
+    pub name: String,
+    pub color: String,
+    /// Contributors who have a score for the area. The statistics below are
+    /// `None` when there are none.
+    pub responses: usize,
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    /// Population standard deviation.
+    pub std_dev: Option<f64>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    /// Number of contributors per score, indexed 0 to 10.
+    pub histogram: Vec<usize>,
+    /// Individual scores; empty when anonymized.
+    pub scores: Vec<ContributorScore>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContributorScore {
+    pub contributor: String,
+    pub value: i64,
+}
+
+/// Reads an export written by `Store::export`.
+pub fn load_export(path: &Path) -> Result<WheelExport, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let export: WheelExport = serde_json::from_str(&contents)
+        .map_err(|e| format!("{} is not a wheel export: {}", path.display(), e))?;
+
+    if export.version > EXPORT_VERSION {
+        return Err(format!(
+            "{} was written by a newer version (export version {})",
+            path.display(),
+            export.version
+        ));
+    }
+
+    Ok(export)
+}
+
+/// Loads each export and builds the team wheel, labelling contributors by
+/// file name.
+pub fn load_team_wheel(paths: &[PathBuf], anonymize: bool) -> Result<TeamWheel, String> {
+    if paths.is_empty() {
+        return Err("At least one export is needed".to_string());
+    }
+
+    let mut exports = Vec::new();
+    for path in paths {
+        let label = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        exports.push((label, load_export(path)?));
+    }
+
+    Ok(build_team_wheel(&exports, anonymize))
+}
+
+/// Aligns the active areas of each `(label, export)` by name and aggregates
+/// their latest scores. With `anonymize`, labels are replaced and individual
+/// scores left out.
+pub fn build_team_wheel(exports: &[(String, WheelExport)], anonymize: bool) -> TeamWheel {
+    let mut contributors = Vec::new();
+    // In order of first appearance
+    let mut areas: Vec<AlignedArea> = Vec::new();
+    let mut index_by_key: HashMap<String, usize> = HashMap::new();
+
+    // Anonymized members are numbered in a random order, so `Member 1` does
+    // not give away whose export came first.
+    let mut numbers: Vec<usize> = (1..=exports.len()).collect();
+    if anonymize {
+        let state = RandomState::new();
+        numbers.sort_by_cached_key(|number| state.hash_one(number));
+    }
+
+    for ((label, export), number) in exports.iter().zip(&numbers) {
+        let label = if anonymize {
+            format!("Member {}", number)
+        } else {
+            label.clone()
+        };
+
+        let mut latest: HashMap<i64, &Score> = HashMap::new();
+        for score in &export.scores {
+            let entry = latest.entry(score.area_id).or_insert(score);
+            if (score.recorded_at, score.id) > (entry.recorded_at, entry.id) {
+                *entry = score;
+            }
+        }
+
+        let mut life_areas: Vec<_> = export.life_areas.iter().filter(|a| a.is_active).collect();
+        life_areas.sort_by_key(|area| area.order);
+        for area in life_areas {
+            let key = area.name.trim().to_lowercase();
+            let index = *index_by_key.entry(key).or_insert_with(|| {
+                areas.push(AlignedArea {
+                    name: area.name.trim().to_string(),
+                    color: area.color.clone(),
+                    scores: Vec::new(),
+                });
+                areas.len() - 1
+            });
+
+            if let Some(score) = latest.get(&area.id) {
+                areas[index].scores.push(ContributorScore {
+                    contributor: label.clone(),
+                    value: score.value,
+                });
+            }
+        }
+
+        contributors.push(TeamContributor {
+            label,
+            exported_at: (!anonymize).then_some(export.exported_at),
+        });
+    }
+
+    if anonymize {
+        let mut numbered: Vec<_> = numbers.into_iter().zip(contributors).collect();
+        numbered.sort_by_key(|(number, _)| *number);
+        contributors = numbered
+            .into_iter()
+            .map(|(_, contributor)| contributor)
+            .collect();
+    }
+
+    TeamWheel {
+        contributors,
+        areas: areas
+            .into_iter()
+            .map(|area| area_stats(area, anonymize))
+            .collect(),
+    }
+}
+
+/// One area across all exports, before aggregation.
+struct AlignedArea {
+    name: String,
+    color: String,
+    scores: Vec<ContributorScore>,
+}
+
+fn area_stats(area: AlignedArea, anonymize: bool) -> TeamAreaStats {
+    let mut values: Vec<i64> = area.scores.iter().map(|s| s.value).collect();
+    values.sort_unstable();
+
+    let mut histogram = vec![0; 11];
+    for value in &values {
+        if let Some(count) = usize::try_from(*value)
+            .ok()
+            .and_then(|v| histogram.get_mut(v))
+        {
+            *count += 1;
+        }
+    }
+
+    let n = values.len();
+    let (mean, median, std_dev) = if n == 0 {
+        (None, None, None)
+    } else {
+        let mean = values.iter().sum::<i64>() as f64 / n as f64;
+        let median = if n.is_multiple_of(2) {
+            (values[n / 2 - 1] + values[n / 2]) as f64 / 2.0
+        } else {
+            values[n / 2] as f64
+        };
+        let variance = values
+            .iter()
+            .map(|v| (*v as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        (Some(mean), Some(median), Some(variance.sqrt()))
+    };
+
+    TeamAreaStats {
+        name: area.name,
+        color: area.color,
+        responses: n,
+        mean,
+        median,
+        std_dev,
+        min: values.first().copied(),
+        max: values.last().copied(),
+        histogram,
+        scores: if anonymize { Vec::new() } else { area.scores },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+
+    fn export_with_score(value: i64) -> WheelExport {
+        let store = Store::open_in_memory().unwrap();
+        let area = store
+            .create_life_area("Health".to_string(), None, "#10b981".to_string(), 0)
+            .unwrap();
+        store.create_score(area.id, value, None, None).unwrap();
+        store.export().unwrap()
+    }
+
+    #[test]
+    fn anonymized_members_are_numbered_without_scores() {
+        let exports: Vec<(String, WheelExport)> = (0..6)
+            .map(|i| (format!("person{}", i), export_with_score(i)))
+            .collect();
+
+        let team = build_team_wheel(&exports, true);
+        let labels: Vec<&str> = team.contributors.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            ["Member 1", "Member 2", "Member 3", "Member 4", "Member 5", "Member 6"]
+        );
+        assert_eq!(team.areas[0].responses, 6);
+        assert!(team.areas[0].scores.is_empty());
+        assert!(team.contributors.iter().all(|c| c.exported_at.is_none()));
+
+        let team = build_team_wheel(&exports, false);
+        assert_eq!(team.contributors[0].label, "person0");
+        assert!(team.contributors[0].exported_at.is_some());
+        assert_eq!(team.areas[0].scores[5].contributor, "person5");
+    }
+}
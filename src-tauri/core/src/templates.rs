@@ -0,0 +1,332 @@
+use crate::location::data_dir;
+use crate::models::LifeArea;
+use crate::profiles::slugify;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const TEMPLATES_DIR: &str = "templates";
+const MAX_TEMPLATE_NAME_LENGTH: usize = 60;
+
+/// A set of life areas that can be added to a wheel in one go.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WheelTemplate {
+    /// `classic`, `career`, ... for presets; the file name for user templates.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub areas: Vec<TemplateArea>,
+    #[serde(default)]
+    pub built_in: bool,
+}
+
+/// Every template that could be read, and why the others could not.
+#[derive(Debug, Serialize, Clone)]
+pub struct TemplateList {
+    pub templates: Vec<WheelTemplate>,
+    /// One message per user template file that was skipped.
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateArea {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub color: String,
+    /// Position on the wheel; areas with the same order keep their order in
+    /// the file.
+    #[serde(default)]
+    pub order: i64,
+}
+
+/// `(name, description, color)` of each preset area, in wheel order.
+type PresetAreas = &'static [(&'static str, &'static str, &'static str)];
+
+const PRESETS: &[(&str, &str, &str, PresetAreas)] = &[
+    (
+        "classic",
+        "Classic wheel",
+        "The eight areas of the traditional wheel of life.",
+        &[
+            ("Health", "Physical and mental wellbeing", "#10b981"),
+            ("Career", "Work, purpose and professional growth", "#3b82f6"),
+            (
+                "Finances",
+                "Income, savings and financial security",
+                "#f59e0b",
+            ),
+            (
+                "Relationships",
+                "Partner, family and close friends",
+                "#ec4899",
+            ),
+            (
+                "Personal Growth",
+                "Learning and self-development",
+                "#8b5cf6",
+            ),
+            ("Fun & Recreation", "Hobbies, play and rest", "#f97316"),
+            ("Physical Environment", "Home and surroundings", "#06b6d4"),
+            ("Contribution", "Community and giving back", "#84cc16"),
+        ],
+    ),
+    (
+        "career",
+        "Career wheel",
+        "A closer look at professional life.",
+        &[
+            (
+                "Role Satisfaction",
+                "Enjoyment of day-to-day work",
+                "#3b82f6",
+            ),
+            ("Skills", "Mastery of current and future skills", "#8b5cf6"),
+            ("Compensation", "Pay and benefits", "#f59e0b"),
+            ("Growth", "Progression and new opportunities", "#10b981"),
+            ("Network", "Mentors, peers and sponsors", "#ec4899"),
+            (
+                "Work-Life Balance",
+                "Time and energy outside work",
+                "#06b6d4",
+            ),
+            ("Recognition", "Feedback and visibility", "#f97316"),
+            ("Purpose", "Meaning and alignment with values", "#6366f1"),
+        ],
+    ),
+    (
+        "relationship",
+        "Relationship wheel",
+        "The parts of a healthy partnership.",
+        &[
+            ("Communication", "Talking and listening openly", "#3b82f6"),
+            ("Trust", "Reliability and honesty", "#10b981"),
+            ("Intimacy", "Physical and emotional closeness", "#ec4899"),
+            ("Quality Time", "Time spent together", "#f97316"),
+            ("Shared Goals", "Plans and direction in common", "#8b5cf6"),
+            (
+                "Conflict Resolution",
+                "Working through disagreements",
+                "#ef4444",
+            ),
+            ("Fun", "Play and laughter together", "#f59e0b"),
+            ("Independence", "Room for each person's own life", "#06b6d4"),
+        ],
+    ),
+    (
+        "team-health",
+        "Team health wheel",
+        "For team retrospectives.",
+        &[
+            ("Mission", "Clarity of purpose and goals", "#6366f1"),
+            (
+                "Psychological Safety",
+                "Freedom to speak up and take risks",
+                "#10b981",
+            ),
+            ("Collaboration", "Working well together", "#3b82f6"),
+            ("Delivery", "Shipping at a sustainable pace", "#f59e0b"),
+            ("Quality", "Pride in the work produced", "#8b5cf6"),
+            ("Learning", "Room to grow and experiment", "#84cc16"),
+            ("Fun", "Enjoying the work and each other", "#f97316"),
+            ("Support", "Help from the organisation", "#06b6d4"),
+        ],
+    ),
+];
+
+fn presets() -> Vec<WheelTemplate> {
+    PRESETS
+        .iter()
+        .map(|(id, name, description, areas)| WheelTemplate {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            areas: areas
+                .iter()
+                .zip(0..)
+                .map(|((name, description, color), order)| TemplateArea {
+                    name: name.to_string(),
+                    description: Some(description.to_string()),
+                    color: color.to_string(),
+                    order,
+                })
+                .collect(),
+            built_in: true,
+        })
+        .collect()
+}
+
+/// A color for the `index`th area added without one, cycling through the
+/// colors of the classic wheel.
+pub(crate) fn area_color(index: usize) -> &'static str {
+    let (_, _, _, areas) = PRESETS[0];
+    areas[index % areas.len()].2
+}
+
+fn templates_dir() -> Result<PathBuf, String> {
+    Ok(data_dir()?.0.join(TEMPLATES_DIR))
+}
+
+/// The built-in presets followed by the user templates, sorted by name.
+/// User templates that cannot be read are left out and listed in `skipped`.
+pub fn list_templates() -> Result<TemplateList, String> {
+    let mut list = user_templates(&templates_dir()?)?;
+    list.templates.splice(0..0, presets());
+    Ok(list)
+}
+
+fn user_templates(dir: &Path) -> Result<TemplateList, String> {
+    let mut user_templates = Vec::new();
+    let mut skipped = Vec::new();
+
+    if dir.is_dir() {
+        let entries =
+            std::fs::read_dir(dir).map_err(|e| format!("Failed to read templates: {}", e))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|e| format!("Failed to read templates: {}", e))?
+                .path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            // One broken file should not hide the other templates, nor the
+            // built-in ones.
+            let template = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+                .and_then(|contents| {
+                    serde_json::from_str::<WheelTemplate>(&contents)
+                        .map_err(|e| format!("Invalid template {}: {}", path.display(), e))
+                });
+            let mut template = match template {
+                Ok(template) => template,
+                Err(e) => {
+                    skipped.push(e);
+                    continue;
+                }
+            };
+            template.id = id.to_string();
+            template.built_in = false;
+            user_templates.push(template);
+        }
+    }
+    user_templates.sort_by(|a, b| a.name.cmp(&b.name));
+    skipped.sort();
+
+    Ok(TemplateList {
+        templates: user_templates,
+        skipped,
+    })
+}
+
+/// Looks up a template by id or, ignoring case, by name.
+pub fn get_template(key: &str) -> Result<WheelTemplate, String> {
+    let key = key.trim();
+    let lower = key.to_lowercase();
+
+    list_templates()?
+        .templates
+        .into_iter()
+        .find(|t| t.id == key || t.name.to_lowercase() == lower)
+        .ok_or_else(|| format!("No template named \"{}\"", key))
+}
+
+/// Saves `areas` as a user template, replacing any user template with the
+/// same name.
+pub fn save_template(
+    name: &str,
+    description: Option<String>,
+    areas: &[LifeArea],
+) -> Result<WheelTemplate, String> {
+    let clean_name = name.trim();
+    if clean_name.is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    if clean_name.chars().count() > MAX_TEMPLATE_NAME_LENGTH {
+        return Err(format!(
+            "Template name cannot exceed {} characters",
+            MAX_TEMPLATE_NAME_LENGTH
+        ));
+    }
+    if areas.is_empty() {
+        return Err("A template needs at least one area".to_string());
+    }
+
+    let id = slugify(clean_name);
+    if PRESETS.iter().any(|(preset_id, ..)| *preset_id == id) {
+        return Err("A built-in template with this name already exists".to_string());
+    }
+
+    let mut areas = areas.to_vec();
+    areas.sort_by_key(|area| area.order);
+    let template = WheelTemplate {
+        id: id.clone(),
+        name: clean_name.to_string(),
+        description: description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty()),
+        areas: areas
+            .into_iter()
+            .zip(0..)
+            .map(|(area, order)| TemplateArea {
+                name: area.name,
+                description: area.description,
+                color: area.color,
+                order,
+            })
+            .collect(),
+        built_in: false,
+    };
+
+    let dir = templates_dir()?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let path = dir.join(format!("{}.json", id));
+    let contents = serde_json::to_string_pretty(&template)
+        .map_err(|e| format!("Failed to serialize template: {}", e))?;
+    std::fs::write(&path, contents + "\n")
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(template)
+}
+
+pub fn delete_template(id: &str) -> Result<(), String> {
+    let template = get_template(id)?;
+    if template.built_in {
+        return Err("Built-in templates cannot be deleted".to_string());
+    }
+
+    let path = templates_dir()?.join(format!("{}.json", template.id));
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn broken_user_templates_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("weekend.json"),
+            r##"{"name": "Weekend", "areas": [{"name": "Rest", "color": "#10b981"}]}"##,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("broken.json"), "{\"name\": ").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a template").unwrap();
+
+        let list = user_templates(dir.path()).unwrap();
+        assert_eq!(list.templates.len(), 1);
+        assert_eq!(list.templates[0].id, "weekend");
+        assert!(!list.templates[0].built_in);
+        assert_eq!(list.skipped.len(), 1);
+        assert!(
+            list.skipped[0].contains("broken.json"),
+            "{}",
+            list.skipped[0]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::audit::{self, Operation, ACTION_CREATE};
+use crate::database::get_setting;
+use crate::models::{RestoredChange, TrashBatch, TrashEntry};
+use crate::undo::{self, RESTORABLE_TABLES};
+use rusqlite::{params, Connection};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub const RETENTION_SETTING: &str = "trash_retention_days";
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+pub const MAX_RETENTION_DAYS: i64 = 3650;
+const RETENTION_NEVER: &str = "never";
+
+/// Days trashed rows are kept before being purged, or `None` to keep them
+/// until the trash is emptied.
+pub fn retention_days(conn: &Connection) -> Result<Option<i64>, String> {
+    match get_setting(conn, RETENTION_SETTING)? {
+        None => Ok(Some(DEFAULT_RETENTION_DAYS)),
+        Some(value) if value == RETENTION_NEVER => Ok(None),
+        Some(value) => value
+            .parse::<i64>()
+            .map(Some)
+            .map_err(|_| format!("Invalid trash retention setting: {}", value)),
+    }
+}
+
+pub fn retention_setting_value(days: Option<i64>) -> Result<String, String> {
+    match days {
+        None => Ok(RETENTION_NEVER.to_string()),
+        Some(days) if (1..=MAX_RETENTION_DAYS).contains(&days) => Ok(days.to_string()),
+        Some(_) => Err(format!(
+            "Retention must be between 1 and {} days",
+            MAX_RETENTION_DAYS
+        )),
+    }
+}
+
+pub fn get_batches(conn: &Connection) -> Result<Vec<TrashBatch>, String> {
+    let retention = retention_days(conn)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.batch_id, o.name, MIN(t.deleted_at), t.entity_type, COUNT(*)
+             FROM trash t
+             INNER JOIN change_operations o ON o.id = t.batch_id
+             GROUP BY t.batch_id, t.entity_type
+             ORDER BY t.batch_id DESC, t.entity_type ASC",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to query trash: {}", e))?;
+
+    let mut batches: Vec<TrashBatch> = Vec::new();
+    for row in rows {
+        let (batch_id, operation, deleted_at, entity_type, count) =
+            row.map_err(|e| format!("Failed to parse trash batch: {}", e))?;
+
+        match batches.last_mut() {
+            Some(batch) if batch.id == batch_id => {
+                batch.deleted_at = batch.deleted_at.min(deleted_at);
+                batch.counts.insert(entity_type, count);
+            }
+            _ => batches.push(TrashBatch {
+                id: batch_id,
+                operation,
+                deleted_at,
+                purge_at: retention.map(|days| deleted_at + days * 86_400),
+                counts: BTreeMap::from([(entity_type, count)]),
+            }),
+        }
+    }
+
+    Ok(batches)
+}
+
+pub fn get_entries(
+    conn: &Connection,
+    batch_id: i64,
+    entity_type: Option<&str>,
+) -> Result<Vec<TrashEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, batch_id, entity_type, entity_id, data, deleted_at FROM trash
+             WHERE batch_id = ?1 AND (?2 IS NULL OR entity_type = ?2)
+             ORDER BY id ASC",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![batch_id, entity_type], |row| {
+            Ok((
+                TrashEntry {
+                    id: row.get(0)?,
+                    batch_id: row.get(1)?,
+                    entity_type: row.get(2)?,
+                    entity_id: row.get(3)?,
+                    data: Value::Null,
+                    deleted_at: row.get(5)?,
+                },
+                row.get::<_, String>(4)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to query trash: {}", e))?;
+
+    let mut result = Vec::new();
+    for row in rows {
+        let (mut entry, data) = row.map_err(|e| format!("Failed to parse trash entry: {}", e))?;
+        entry.data = serde_json::from_str(&data)
+            .map_err(|e| format!("Failed to parse trash entry: {}", e))?;
+        result.push(entry);
+    }
+
+    Ok(result)
+}
+
+/// Puts every row of the batch back where it was, in reverse order of deletion
+/// so parents exist before their dependents. Rows keep their ids, except for
+/// tables without an `id` column, whose rowid may have been reused.
+pub fn restore_batch(
+    conn: &Connection,
+    op: &Operation,
+    batch_id: i64,
+) -> Result<Vec<RestoredChange>, String> {
+    let entries = get_entries(conn, batch_id, None)?;
+    if entries.is_empty() {
+        return Err("Trash batch not found".to_string());
+    }
+
+    let mut restored = Vec::new();
+    for entry in entries.into_iter().rev() {
+        let table = RESTORABLE_TABLES
+            .iter()
+            .find(|t| **t == entry.entity_type)
+            .ok_or_else(|| format!("Cannot restore {} from the trash", entry.entity_type))?;
+        let values: Map<String, Value> = match entry.data {
+            Value::Object(values) => values,
+            _ => return Err("Invalid trash entry".to_string()),
+        };
+
+        let rowid = values.contains_key("id").then_some(entry.entity_id);
+        let rowid = undo::insert_row(conn, table, rowid, &values)?;
+        op.created(conn, table, "rowid = ?", params![rowid])?;
+        op.delete_where(conn, "trash", "id = ?", params![entry.id])?;
+
+        restored.push(RestoredChange {
+            entity_type: entry.entity_type,
+            entity_id: rowid,
+            action: ACTION_CREATE.to_string(),
+            value: Some(Value::Object(values)),
+        });
+    }
+
+    // The batch has been put back by hand, so undoing its operation no longer applies.
+    conn.execute(
+        "UPDATE change_operations SET undo_state = NULL WHERE id = ?1",
+        params![batch_id],
+    )
+    .map_err(|e| format!("Failed to update undo stack: {}", e))?;
+
+    Ok(restored)
+}
+
+/// Permanently removes trashed rows deleted before `before` (all of them if
+/// `None`). Returns the number of rows removed.
+///
+/// The change log is append-only: the history of the purged rows stays, and
+/// each purge is recorded as the deletion of its trash entry, without the
+/// row data the entry held. The operations that trashed the rows can no
+/// longer be undone.
+pub fn purge(conn: &Connection, op: &Operation, before: Option<i64>) -> Result<usize, String> {
+    let filter = "(?1 IS NULL OR deleted_at < ?1)";
+    let mut rows = audit::snapshot(conn, "trash", filter, params![before])?;
+
+    conn.execute(
+        &format!("DELETE FROM trash WHERE {}", filter),
+        params![before],
+    )
+    .map_err(|e| format!("Failed to purge trash: {}", e))?;
+
+    let mut batch_ids = BTreeSet::new();
+    for row in &mut rows {
+        row.values.remove("data");
+        if let Some(batch_id) = row.values.get("batch_id").and_then(Value::as_i64) {
+            batch_ids.insert(batch_id);
+        }
+    }
+    op.deleted(conn, "trash", &rows)?;
+
+    for batch_id in batch_ids {
+        conn.execute(
+            "UPDATE change_operations SET undo_state = NULL WHERE id = ?1",
+            params![batch_id],
+        )
+        .map_err(|e| format!("Failed to update undo stack: {}", e))?;
+    }
+
+    Ok(rows.len())
+}
@@ -0,0 +1,336 @@
+use crate::audit::{
+    self, Operation, ACTION_CREATE, ACTION_DELETE, ACTION_UPDATE, UNDO_STATE_DONE,
+    UNDO_STATE_UNDONE,
+};
+use crate::models::{RestoredChange, UndoableOperation};
+use chrono::Utc;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde_json::{Map, Value};
+
+/// Commands whose operations can be undone and redone.
+pub const UNDOABLE_OPERATIONS: &[&str] = &[
+    "delete_action_item",
+    "archive_action_item",
+    "soft_delete_life_area",
+    "reset_area_scores",
+    "reset_area_action_items",
+    "reset_area_data",
+    "reset_all_data",
+    "apply_template",
+    "import_ical",
+    "import_task_list",
+    "import_csv",
+];
+
+/// Tables that undo and redo may write to. Table names read back from the
+/// change log are checked against this list before being used in SQL.
+pub const RESTORABLE_TABLES: &[&str] = &[
+    "life_areas",
+    "scores",
+    "action_items",
+    "action_item_tags",
+    "action_item_links",
+    "action_item_steps",
+    "tags",
+    "habits",
+    "habit_checkins",
+    "goals",
+    "trash",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Undo,
+    Redo,
+}
+
+impl Direction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Direction::Undo => "undo",
+            Direction::Redo => "redo",
+        }
+    }
+}
+
+pub fn is_undoable(name: &str) -> bool {
+    UNDOABLE_OPERATIONS.contains(&name)
+}
+
+/// The operation the next undo (most recent one still done) or redo (most
+/// recently undone one) applies to.
+pub fn next_operation(
+    conn: &Connection,
+    direction: Direction,
+) -> Result<Option<UndoableOperation>, String> {
+    let query = match direction {
+        Direction::Undo => {
+            "SELECT id, name, created_at, undone_at FROM change_operations
+             WHERE undo_state = ?1 ORDER BY id DESC LIMIT 1"
+        }
+        Direction::Redo => {
+            "SELECT id, name, created_at, undone_at FROM change_operations
+             WHERE undo_state = ?1 ORDER BY id ASC LIMIT 1"
+        }
+    };
+    let state = match direction {
+        Direction::Undo => UNDO_STATE_DONE,
+        Direction::Redo => UNDO_STATE_UNDONE,
+    };
+
+    conn.query_row(query, params![state], |row| {
+        Ok(UndoableOperation {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            created_at: row.get(2)?,
+            undone_at: row.get(3)?,
+        })
+    })
+    .optional()
+    .map_err(|e| format!("Failed to query undo stack: {}", e))
+}
+
+struct LoggedChange {
+    entity_type: String,
+    entity_id: i64,
+    old_value: Option<Map<String, Value>>,
+    new_value: Option<Map<String, Value>>,
+}
+
+fn logged_changes(conn: &Connection, operation_id: i64) -> Result<Vec<LoggedChange>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT entity_type, entity_id, old_value, new_value FROM change_log
+             WHERE operation_id = ?1 ORDER BY id ASC",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![operation_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to query change log: {}", e))?;
+
+    let parse_value = |value: Option<String>| {
+        value
+            .map(|v| serde_json::from_str::<Map<String, Value>>(&v))
+            .transpose()
+            .map_err(|e| format!("Failed to parse change log value: {}", e))
+    };
+
+    let mut result = Vec::new();
+    for row in rows {
+        let (entity_type, entity_id, old_value, new_value) =
+            row.map_err(|e| format!("Failed to parse change log entry: {}", e))?;
+        result.push(LoggedChange {
+            entity_type,
+            entity_id,
+            old_value: parse_value(old_value)?,
+            new_value: parse_value(new_value)?,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Reverts (undo) or reapplies (redo) every change of `operation`, recording
+/// the result under `op`. Fails without touching anything if a row was
+/// modified after the operation, so later edits are never overwritten.
+pub fn apply(
+    conn: &Connection,
+    op: &Operation,
+    operation: &mut UndoableOperation,
+    direction: Direction,
+) -> Result<Vec<RestoredChange>, String> {
+    let mut changes = logged_changes(conn, operation.id)?;
+    if direction == Direction::Undo {
+        changes.reverse();
+    }
+
+    let mut restored = Vec::new();
+    for change in changes {
+        let table = RESTORABLE_TABLES
+            .iter()
+            .find(|t| **t == change.entity_type)
+            .ok_or_else(|| {
+                format!(
+                    "Cannot {} changes to {}",
+                    direction.verb(),
+                    change.entity_type
+                )
+            })?;
+
+        let (expected, target) = match direction {
+            Direction::Undo => (change.new_value, change.old_value),
+            Direction::Redo => (change.old_value, change.new_value),
+        };
+
+        let current = audit::snapshot(conn, table, "rowid = ?", params![change.entity_id])?
+            .into_iter()
+            .next()
+            .map(|row| row.values);
+        if current != expected {
+            return Err(format!(
+                "Cannot {} \"{}\": it touched data that has changed since",
+                direction.verb(),
+                operation.name
+            ));
+        }
+
+        let action = match (&expected, &target) {
+            (Some(_), None) => {
+                op.delete_where(conn, table, "rowid = ?", params![change.entity_id])?;
+                ACTION_DELETE
+            }
+            (None, Some(values)) => {
+                insert_row(conn, table, Some(change.entity_id), values)?;
+                op.created(conn, table, "rowid = ?", params![change.entity_id])?;
+                ACTION_CREATE
+            }
+            (Some(_), Some(values)) => {
+                let before = audit::snapshot(conn, table, "rowid = ?", params![change.entity_id])?;
+                update_row(conn, table, change.entity_id, values)?;
+                op.updated(conn, table, before)?;
+                ACTION_UPDATE
+            }
+            (None, None) => continue,
+        };
+
+        restored.push(RestoredChange {
+            entity_type: change.entity_type,
+            entity_id: change.entity_id,
+            action: action.to_string(),
+            value: target.map(Value::Object),
+        });
+    }
+
+    let (state, undone_at) = match direction {
+        Direction::Undo => (UNDO_STATE_UNDONE, Some(Utc::now().timestamp())),
+        Direction::Redo => (UNDO_STATE_DONE, None),
+    };
+    conn.execute(
+        "UPDATE change_operations SET undo_state = ?1, undone_at = ?2 WHERE id = ?3",
+        params![state, undone_at, operation.id],
+    )
+    .map_err(|e| format!("Failed to update undo stack: {}", e))?;
+    operation.undone_at = undone_at;
+
+    Ok(restored)
+}
+
+fn checked_columns<'a>(
+    conn: &Connection,
+    table: &str,
+    values: &'a Map<String, Value>,
+) -> Result<Vec<(&'a String, SqlValue)>, String> {
+    let stmt = conn
+        .prepare(&format!("SELECT * FROM {} LIMIT 0", table))
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let columns = stmt.column_names();
+
+    values
+        .iter()
+        .map(|(column, value)| {
+            if !columns.contains(&column.as_str()) {
+                return Err(format!("Unknown column {} in {}", column, table));
+            }
+            Ok((column, json_to_sql(value)))
+        })
+        .collect()
+}
+
+fn json_to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        Value::Array(items) => SqlValue::Blob(
+            items
+                .iter()
+                .filter_map(|item| item.as_u64().map(|b| b as u8))
+                .collect(),
+        ),
+        Value::Object(_) => SqlValue::Text(value.to_string()),
+    }
+}
+
+/// Inserts `values` into `table`, keeping `rowid` if given. Returns the rowid
+/// of the new row.
+pub fn insert_row(
+    conn: &Connection,
+    table: &str,
+    rowid: Option<i64>,
+    values: &Map<String, Value>,
+) -> Result<i64, String> {
+    let columns = checked_columns(conn, table, values)?;
+    let mut names: Vec<String> = columns
+        .iter()
+        .map(|(name, _)| format!("\"{}\"", name))
+        .collect();
+    let mut row_values: Vec<SqlValue> = columns.into_iter().map(|(_, value)| value).collect();
+    if let Some(rowid) = rowid {
+        names.push("rowid".to_string());
+        row_values.push(SqlValue::Integer(rowid));
+    }
+    let placeholders: Vec<String> = (1..=names.len()).map(|i| format!("?{}", i)).collect();
+
+    let inserted = conn
+        .execute(
+            &format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                table,
+                names.join(", "),
+                placeholders.join(", ")
+            ),
+            params_from_iter(row_values),
+        )
+        .map_err(|e| format!("Failed to restore {} row: {}", table, e))?;
+
+    if inserted == 0 {
+        return Err(format!("Failed to restore {} row", table));
+    }
+    Ok(conn.last_insert_rowid())
+}
+
+fn update_row(
+    conn: &Connection,
+    table: &str,
+    rowid: i64,
+    values: &Map<String, Value>,
+) -> Result<(), String> {
+    let columns = checked_columns(conn, table, values)?;
+    let assignments: Vec<String> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, (name, _))| format!("\"{}\" = ?{}", name, index + 2))
+        .collect();
+
+    let updated = conn
+        .execute(
+            &format!(
+                "UPDATE {} SET {} WHERE rowid = ?1",
+                table,
+                assignments.join(", ")
+            ),
+            params_from_iter(
+                std::iter::once(SqlValue::Integer(rowid))
+                    .chain(columns.into_iter().map(|(_, value)| value)),
+            ),
+        )
+        .map_err(|e| format!("Failed to restore {} row: {}", table, e))?;
+
+    if updated == 0 {
+        return Err(format!("Failed to restore {} row {}", table, rowid));
+    }
+    Ok(())
+}
@@ -0,0 +1,296 @@
+use crate::aggregation::format_local_date;
+use crate::models::{ActionItem, ActionItemLink, ActionItemStep, LifeArea, Score, Tag};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const INDEX_NOTE: &str = "Life Wheel";
+/// Characters Obsidian does not allow in note names, or that break links.
+const FORBIDDEN_CHARACTERS: &[char] = &[
+    '/', '\\', ':', '*', '?', '"', '<', '>', '|', '#', '^', '[', ']',
+];
+
+/// The wheel as a folder of Markdown notes, gathered by
+/// `Store::get_markdown_vault`.
+#[derive(Debug, Serialize, Clone)]
+pub struct MarkdownVault {
+    pub generated_at: i64,
+    /// Active areas in wheel order.
+    pub areas: Vec<VaultArea>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct VaultArea {
+    pub area: LifeArea,
+    pub score: Option<Score>,
+    /// Every score of the area, newest first.
+    pub scores: Vec<Score>,
+    /// Open action items in list order.
+    pub open: Vec<VaultActionItem>,
+    /// Archived action items, most recently archived first.
+    pub archived: Vec<VaultActionItem>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct VaultActionItem {
+    pub item: ActionItem,
+    pub tags: Vec<Tag>,
+    pub steps: Vec<ActionItemStep>,
+    pub links: Vec<ActionItemLink>,
+}
+
+impl MarkdownVault {
+    /// `(file name, contents)` of every note: an index note linking to one
+    /// note per area.
+    pub fn notes(&self) -> Vec<(String, String)> {
+        let names = self.note_names();
+        let mut notes = vec![(format!("{}.md", INDEX_NOTE), self.index_note(&names))];
+        for (i, area) in self.areas.iter().enumerate() {
+            let previous = names[(i + names.len() - 1) % names.len()].as_str();
+            let next = names[(i + 1) % names.len()].as_str();
+            notes.push((format!("{}.md", names[i]), area.note(previous, next)));
+        }
+        notes
+    }
+
+    /// Writes the notes into `dir`, creating it if needed. Notes left from an
+    /// earlier export are overwritten; other files are kept.
+    pub fn save(&self, dir: &Path) -> Result<Vec<PathBuf>, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+        let mut paths = Vec::new();
+        for (name, contents) in self.notes() {
+            let path = dir.join(name);
+            std::fs::write(&path, contents)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// A unique note name for each area, in the order of `areas`.
+    fn note_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for area in &self.areas {
+            let base = note_name(&area.area.name);
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while name.eq_ignore_ascii_case(INDEX_NOTE)
+                || names.iter().any(|other| other.eq_ignore_ascii_case(&name))
+            {
+                name = format!("{} {}", base, suffix);
+                suffix += 1;
+            }
+            names.push(name);
+        }
+        names
+    }
+
+    fn index_note(&self, names: &[String]) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "---");
+        let _ = writeln!(out, "generated: {}", format_local_date(self.generated_at));
+        let _ = writeln!(out, "tags: [life-wheel]");
+        let _ = writeln!(out, "---\n");
+        let _ = writeln!(out, "# {}\n", INDEX_NOTE);
+
+        if self.areas.is_empty() {
+            let _ = writeln!(out, "No life areas yet.");
+            return out;
+        }
+
+        let _ = writeln!(out, "| Area | Score | Scored | Open actions |");
+        let _ = writeln!(out, "| --- | --- | --- | --- |");
+        for (area, name) in self.areas.iter().zip(names) {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                link(name, &area.area.name, true),
+                area.score
+                    .as_ref()
+                    .map_or("-".to_string(), |score| score.value.to_string()),
+                area.score
+                    .as_ref()
+                    .map_or("-".to_string(), |score| format_local_date(
+                        score.recorded_at
+                    )),
+                area.open.len()
+            );
+        }
+        out
+    }
+}
+
+impl VaultArea {
+    fn note(&self, previous: &str, next: &str) -> String {
+        let area = &self.area;
+        let mut out = String::new();
+
+        let _ = writeln!(out, "---");
+        let _ = writeln!(out, "color: \"{}\"", area.color.replace('"', ""));
+        let _ = writeln!(out, "order: {}", area.order);
+        match &self.score {
+            Some(score) => {
+                let _ = writeln!(out, "score: {}", score.value);
+                let _ = writeln!(out, "scored: {}", format_local_date(score.recorded_at));
+            }
+            None => {
+                let _ = writeln!(out, "score: null");
+            }
+        }
+        let _ = writeln!(out, "open_actions: {}", self.open.len());
+        let _ = writeln!(out, "tags: [life-area]");
+        let _ = writeln!(out, "---\n");
+
+        let _ = writeln!(out, "# {}\n", area.name);
+        let _ = writeln!(
+            out,
+            "Part of {}. Previous: {}. Next: {}.\n",
+            link(INDEX_NOTE, INDEX_NOTE, false),
+            link(previous, previous, false),
+            link(next, next, false)
+        );
+        if let Some(description) = area.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            let _ = writeln!(out, "{}\n", description.trim());
+        }
+
+        let _ = writeln!(out, "## Scores\n");
+        if self.scores.is_empty() {
+            let _ = writeln!(out, "No scores yet.\n");
+        } else {
+            let _ = writeln!(out, "| Date | Score | Note |");
+            let _ = writeln!(out, "| --- | --- | --- |");
+            for score in &self.scores {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} |",
+                    format_local_date(score.recorded_at),
+                    score.value,
+                    table_cell(score.note.as_deref().unwrap_or(""))
+                );
+            }
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "## Open actions\n");
+        if self.open.is_empty() {
+            let _ = writeln!(out, "Nothing to do.\n");
+        } else {
+            for item in &self.open {
+                item.write(&mut out);
+            }
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "## Archived actions\n");
+        if self.archived.is_empty() {
+            let _ = writeln!(out, "Nothing archived yet.");
+        } else {
+            for item in &self.archived {
+                item.write(&mut out);
+            }
+        }
+
+        out
+    }
+}
+
+impl VaultActionItem {
+    /// A task line with its details indented below it.
+    fn write(&self, out: &mut String) {
+        let item = &self.item;
+        let mut line = format!(
+            "- [{}] {}",
+            if item.archived_at.is_some() { "x" } else { " " },
+            single_line(&item.title)
+        );
+        for tag in &self.tags {
+            let _ = write!(line, " #{}", tag.name);
+        }
+        if let Some(due_at) = item.due_at {
+            let _ = write!(line, " (due {})", format_local_date(due_at));
+        }
+        if let Some(archived_at) = item.archived_at {
+            let _ = write!(line, " (archived {})", format_local_date(archived_at));
+        }
+        let _ = writeln!(out, "{}", line);
+
+        if let Some(description) = &item.description {
+            for description_line in description.lines().filter(|l| !l.trim().is_empty()) {
+                let _ = writeln!(out, "    {}", description_line.trim());
+            }
+        }
+        // Steps come parents first, so a step's depth is known by the time
+        // it is written.
+        let mut depths: HashMap<i64, usize> = HashMap::new();
+        for step in &self.steps {
+            let depth = step
+                .parent_id
+                .and_then(|parent_id| depths.get(&parent_id))
+                .map_or(0, |depth| depth + 1);
+            depths.insert(step.id, depth);
+            let _ = writeln!(
+                out,
+                "    {}- [{}] {}",
+                "    ".repeat(depth),
+                if step.is_done { "x" } else { " " },
+                single_line(&step.title)
+            );
+        }
+        for item_link in &self.links {
+            let label = item_link
+                .label
+                .as_deref()
+                .filter(|label| !label.trim().is_empty())
+                .unwrap_or(&item_link.url);
+            let _ = writeln!(
+                out,
+                "    - [{}](<{}>)",
+                single_line(label).replace(['[', ']'], ""),
+                item_link.url
+            );
+        }
+    }
+}
+
+/// A note name for an area, without the characters notes cannot have.
+fn note_name(area: &str) -> String {
+    let name: String = area
+        .chars()
+        .map(|c| {
+            if FORBIDDEN_CHARACTERS.contains(&c) {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        "Area".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// A wiki link to the note `name`, showing `text` when it differs. Pipes are
+/// escaped inside tables.
+fn link(name: &str, text: &str, in_table: bool) -> String {
+    let separator = if in_table { "\\|" } else { "|" };
+    if name == text {
+        format!("[[{}]]", name)
+    } else {
+        format!("[[{}{}{}]]", name, separator, single_line(text))
+    }
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn table_cell(text: &str) -> String {
+    single_line(text).replace('|', "\\|")
+}
@@ -1,13 +1,15 @@
-mod database;
-mod models;
-
-use chrono::Utc;
-use database::{get_connection, init_database};
-use models::{ActionItem, LifeArea, Score};
-use rusqlite::params;
-use serde::Deserialize;
-
-const MAX_ACTION_ITEM_TITLE_LENGTH: usize = 80;
+use roda_core::models::{
+    ActionItem, ActionItemLink, ActionItemStep, ChangeLogEntry, Goal, Habit, HabitCheckin,
+    LifeArea, ReorderUpdate, RestoredChange, Score, ScoreCursor, ScorePage, Tag, TagFilter,
+    TrashBatch, TrashEntry, UndoResult, UndoStatus, WheelEntry,
+};
+use roda_core::{
+    get_db_path, set_db_passphrase, set_db_path_override, AreaScoreBuckets, CsvColumnMapping,
+    CsvImportPreview, DatabaseEncryption, DatabaseLocation, GoalProgress, HabitStats, HeatmapDay,
+    IcalImportSummary, Profile, ReviewReport, Store, TaskImportSummary, TeamWheel, TemplateList,
+    WheelImageOptions, WheelTemplate,
+};
+use std::path::PathBuf;
 
 #[tauri::command]
 fn create_life_area(
@@ -16,100 +18,17 @@ fn create_life_area(
     color: String,
     order: i64,
 ) -> Result<LifeArea, String> {
-    let conn = get_connection()?;
-    let now = Utc::now().timestamp();
-
-    // Check if name already exists for active areas
-    let mut stmt = conn
-        .prepare("SELECT id FROM life_areas WHERE name = ? AND is_active = 1")
-        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    if stmt
-        .exists(params![name])
-        .map_err(|e| format!("Failed to check name: {}", e))?
-    {
-        return Err("An active area with this name already exists".to_string());
-    }
-
-    conn.execute(
-        "INSERT INTO life_areas (name, description, color, \"order\", is_active, created_at, updated_at)
-         VALUES (?1, ?2, ?3, ?4, 1, ?5, ?5)",
-        params![name, description, color, order, now],
-    ).map_err(|e| format!("Failed to insert life area: {}", e))?;
-
-    let id = conn.last_insert_rowid();
-
-    // Get the created area
-    conn.query_row(
-        "SELECT id, name, description, color, \"order\", is_active, created_at, updated_at FROM life_areas WHERE id = ?",
-        params![id],
-        |row| {
-            Ok(LifeArea {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                color: row.get(3)?,
-                order: row.get(4)?,
-                is_active: row.get(5)?,
-                created_at: row.get(6)?,
-                updated_at: row.get(7)?,
-            })
-        },
-    ).map_err(|e| format!("Failed to get created life area: {}", e))
+    Store::open_default()?.create_life_area(name, description, color, order)
 }
 
 #[tauri::command]
 fn get_life_areas(include_archived: bool) -> Result<Vec<LifeArea>, String> {
-    let conn = get_connection()?;
-    let mut stmt = if include_archived {
-        conn.prepare("SELECT id, name, description, color, \"order\", is_active, created_at, updated_at FROM life_areas ORDER BY \"order\", name")
-    } else {
-        conn.prepare("SELECT id, name, description, color, \"order\", is_active, created_at, updated_at FROM life_areas WHERE is_active = 1 ORDER BY \"order\", name")
-    }.map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    let areas = stmt
-        .query_map([], |row| {
-            Ok(LifeArea {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                color: row.get(3)?,
-                order: row.get(4)?,
-                is_active: row.get(5)?,
-                created_at: row.get(6)?,
-                updated_at: row.get(7)?,
-            })
-        })
-        .map_err(|e| format!("Failed to query life areas: {}", e))?;
-
-    let mut result = Vec::new();
-    for area in areas {
-        result.push(area.map_err(|e| format!("Failed to parse area: {}", e))?);
-    }
-
-    Ok(result)
+    Store::open_default()?.get_life_areas(include_archived)
 }
 
 #[tauri::command]
 fn get_life_area(id: i64) -> Result<LifeArea, String> {
-    let conn = get_connection()?;
-
-    conn.query_row(
-        "SELECT id, name, description, color, \"order\", is_active, created_at, updated_at FROM life_areas WHERE id = ?",
-        params![id],
-        |row| {
-            Ok(LifeArea {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                color: row.get(3)?,
-                order: row.get(4)?,
-                is_active: row.get(5)?,
-                created_at: row.get(6)?,
-                updated_at: row.get(7)?,
-            })
-        },
-    ).map_err(|e| format!("Failed to get life area: {}", e))
+    Store::open_default()?.get_life_area(id)
 }
 
 #[tauri::command]
@@ -120,509 +39,672 @@ fn update_life_area(
     color: String,
     order: i64,
 ) -> Result<LifeArea, String> {
-    let conn = get_connection()?;
-    let now = Utc::now().timestamp();
-
-    // Check if name already exists for active areas (excluding current)
-    let mut stmt = conn
-        .prepare("SELECT id FROM life_areas WHERE name = ? AND is_active = 1 AND id != ?")
-        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    if stmt
-        .exists(params![name, id])
-        .map_err(|e| format!("Failed to check name: {}", e))?
-    {
-        return Err("An active area with this name already exists".to_string());
-    }
+    Store::open_default()?.update_life_area(id, name, description, color, order)
+}
 
-    conn.execute(
-        "UPDATE life_areas SET name = ?1, description = ?2, color = ?3, \"order\" = ?4, updated_at = ?5 WHERE id = ?6",
-        params![name, description, color, order, now, id],
-    ).map_err(|e| format!("Failed to update life area: {}", e))?;
+#[tauri::command]
+fn soft_delete_life_area(id: i64) -> Result<(), String> {
+    Store::open_default()?.soft_delete_life_area(id)
+}
 
-    get_life_area(id)
+#[tauri::command]
+fn restore_life_area(id: i64) -> Result<LifeArea, String> {
+    Store::open_default()?.restore_life_area(id)
 }
 
 #[tauri::command]
-fn soft_delete_life_area(id: i64) -> Result<(), String> {
-    let conn = get_connection()?;
-    let now = Utc::now().timestamp();
+fn create_score(
+    area_id: i64,
+    value: i64,
+    recorded_at: Option<i64>,
+    note: Option<String>,
+) -> Result<Score, String> {
+    Store::open_default()?.create_score(area_id, value, recorded_at, note)
+}
 
-    conn.execute(
-        "UPDATE life_areas SET is_active = 0, updated_at = ? WHERE id = ?",
-        params![now, id],
-    )
-    .map_err(|e| format!("Failed to delete life area: {}", e))?;
+#[tauri::command]
+fn get_scores_by_area(
+    area_id: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<Score>, String> {
+    Store::open_default()?.get_scores_by_area(area_id, from, to, limit, offset)
+}
 
-    Ok(())
+#[tauri::command]
+fn get_scores_page(
+    area_ids: Vec<i64>,
+    from: Option<i64>,
+    to: Option<i64>,
+    limit: Option<i64>,
+    cursor: Option<ScoreCursor>,
+    ascending: Option<bool>,
+) -> Result<ScorePage, String> {
+    Store::open_default()?.get_scores_page(area_ids, from, to, limit, cursor, ascending)
 }
 
 #[tauri::command]
-fn restore_life_area(id: i64) -> Result<LifeArea, String> {
-    let conn = get_connection()?;
-    let now = Utc::now().timestamp();
-
-    // Check if name conflicts with active area
-    let area = get_life_area(id)?;
-    let mut stmt = conn
-        .prepare("SELECT id FROM life_areas WHERE name = ? AND is_active = 1 AND id != ?")
-        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    if stmt
-        .exists(params![area.name, id])
-        .map_err(|e| format!("Failed to check name: {}", e))?
-    {
-        return Err("An active area with this name already exists".to_string());
-    }
+fn get_latest_score(area_id: i64) -> Result<Option<Score>, String> {
+    Store::open_default()?.get_latest_score(area_id)
+}
 
-    conn.execute(
-        "UPDATE life_areas SET is_active = 1, updated_at = ? WHERE id = ?",
-        params![now, id],
-    )
-    .map_err(|e| format!("Failed to restore life area: {}", e))?;
+#[tauri::command]
+fn get_all_latest_scores() -> Result<Vec<Score>, String> {
+    Store::open_default()?.get_all_latest_scores()
+}
 
-    get_life_area(id)
+#[tauri::command]
+fn get_score_buckets(
+    granularity: String,
+    area_ids: Option<Vec<i64>>,
+    from: Option<i64>,
+    to: Option<i64>,
+    fill: Option<String>,
+) -> Result<Vec<AreaScoreBuckets>, String> {
+    Store::open_default()?.get_score_buckets(granularity, area_ids, from, to, fill)
 }
 
 #[tauri::command]
-fn create_score(area_id: i64, value: i64) -> Result<Score, String> {
-    if value < 0 || value > 10 {
-        return Err("Score must be between 0 and 10".to_string());
-    }
+fn get_wheel_as_of(timestamp: i64) -> Result<Vec<WheelEntry>, String> {
+    Store::open_default()?.get_wheel_as_of(timestamp)
+}
 
-    let conn = get_connection()?;
-    let now = Utc::now().timestamp();
-
-    conn.execute(
-        "INSERT INTO scores (area_id, value, recorded_at) VALUES (?1, ?2, ?3)",
-        params![area_id, value, now],
-    )
-    .map_err(|e| format!("Failed to insert score: {}", e))?;
-
-    let id = conn.last_insert_rowid();
-
-    Ok(Score {
-        id,
-        area_id,
-        value,
-        recorded_at: now,
-    })
-}
-
-#[tauri::command]
-fn get_scores_by_area(area_id: i64) -> Result<Vec<Score>, String> {
-    let conn = get_connection()?;
-    let mut stmt = conn.prepare(
-        "SELECT id, area_id, value, recorded_at FROM scores WHERE area_id = ? ORDER BY recorded_at DESC"
-    ).map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    let scores = stmt
-        .query_map(params![area_id], |row| {
-            Ok(Score {
-                id: row.get(0)?,
-                area_id: row.get(1)?,
-                value: row.get(2)?,
-                recorded_at: row.get(3)?,
-            })
-        })
-        .map_err(|e| format!("Failed to query scores: {}", e))?;
-
-    let mut result = Vec::new();
-    for score in scores {
-        result.push(score.map_err(|e| format!("Failed to parse score: {}", e))?);
+/// The wheel at `at`, or the current one.
+fn wheel_entries(store: &Store, at: Option<i64>) -> Result<Vec<WheelEntry>, String> {
+    match at {
+        Some(at) => store.get_wheel_as_of(at),
+        None => {
+            let mut entries = Vec::new();
+            for area in store.get_life_areas(false)? {
+                let score = store.get_latest_score(area.id)?;
+                entries.push(WheelEntry { area, score });
+            }
+            Ok(entries)
+        }
     }
+}
 
-    Ok(result)
+#[tauri::command]
+fn get_wheel_svg(at: Option<i64>, options: Option<WheelImageOptions>) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let entries = wheel_entries(&Store::open_default()?, at)?;
+    Ok(roda_core::wheel_svg(&entries, &options))
 }
 
+/// Writes the wheel to `path`, as SVG or PNG depending on its extension.
 #[tauri::command]
-fn get_latest_score(area_id: i64) -> Result<Option<Score>, String> {
-    let conn = get_connection()?;
+fn save_wheel_image(
+    path: String,
+    at: Option<i64>,
+    options: Option<WheelImageOptions>,
+) -> Result<(), String> {
+    let entries = wheel_entries(&Store::open_default()?, at)?;
+    roda_core::save_wheel_image(&entries, &PathBuf::from(path), &options.unwrap_or_default())
+}
 
-    let mut stmt = conn.prepare(
-        "SELECT id, area_id, value, recorded_at FROM scores WHERE area_id = ? ORDER BY recorded_at DESC LIMIT 1"
-    ).map_err(|e| format!("Failed to prepare statement: {}", e))?;
+/// Writes every action item to `path` as an iCalendar file of `todo` or
+/// `event` entries.
+#[tauri::command]
+fn export_ical(component: String, path: String) -> Result<(), String> {
+    let calendar = Store::open_default()?.export_ical(component)?;
+    std::fs::write(&path, calendar).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
 
-    let mut scores = stmt
-        .query_map(params![area_id], |row| {
-            Ok(Score {
-                id: row.get(0)?,
-                area_id: row.get(1)?,
-                value: row.get(2)?,
-                recorded_at: row.get(3)?,
-            })
-        })
-        .map_err(|e| format!("Failed to query score: {}", e))?;
+#[tauri::command]
+fn import_ical(path: String) -> Result<IcalImportSummary, String> {
+    let contents =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Store::open_default()?.import_ical(&contents)
+}
 
-    Ok(scores
-        .next()
-        .transpose()
-        .map_err(|e| format!("Failed to parse score: {}", e))?)
+/// Writes the action items to `path` as a `todotxt` or `markdown` task list.
+#[tauri::command]
+fn export_task_list(format: String, path: String) -> Result<(), String> {
+    let list = Store::open_default()?.export_task_list(format)?;
+    std::fs::write(&path, list).map_err(|e| format!("Failed to write {}: {}", path, e))
 }
 
 #[tauri::command]
-fn get_all_latest_scores() -> Result<Vec<Score>, String> {
-    let conn = get_connection()?;
-    let mut stmt = conn
-        .prepare(
-            "SELECT s.id, s.area_id, s.value, s.recorded_at
-         FROM scores s
-         INNER JOIN (
-             SELECT area_id, MAX(recorded_at) as max_recorded_at
-             FROM scores
-             GROUP BY area_id
-         ) latest ON s.area_id = latest.area_id AND s.recorded_at = latest.max_recorded_at
-         INNER JOIN life_areas la ON s.area_id = la.id
-         WHERE la.is_active = 1
-         ORDER BY la.\"order\"",
-        )
-        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    let scores = stmt
-        .query_map([], |row| {
-            Ok(Score {
-                id: row.get(0)?,
-                area_id: row.get(1)?,
-                value: row.get(2)?,
-                recorded_at: row.get(3)?,
-            })
-        })
-        .map_err(|e| format!("Failed to query scores: {}", e))?;
-
-    let mut result = Vec::new();
-    for score in scores {
-        result.push(score.map_err(|e| format!("Failed to parse score: {}", e))?);
-    }
+fn import_task_list(path: String, format: String) -> Result<TaskImportSummary, String> {
+    let contents =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Store::open_default()?.import_task_list(&contents, format)
+}
 
-    Ok(result)
+/// Imports the CSV file at `path` with `mapping`. With `dry_run` nothing is
+/// written and only the preview is returned.
+#[tauri::command]
+fn import_csv(
+    path: String,
+    mapping: CsvColumnMapping,
+    dry_run: bool,
+) -> Result<CsvImportPreview, String> {
+    let contents =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Store::open_default()?.import_csv(&contents, &mapping, dry_run)
 }
 
 #[tauri::command]
-fn create_action_item(area_id: i64, title: String) -> Result<ActionItem, String> {
-    let clean_title = title.trim();
-    if clean_title.is_empty() {
-        return Err("Title cannot be empty".to_string());
-    }
-    if clean_title.chars().count() > MAX_ACTION_ITEM_TITLE_LENGTH {
-        return Err(format!(
-            "Title cannot exceed {} characters",
-            MAX_ACTION_ITEM_TITLE_LENGTH
-        ));
-    }
+fn save_wheel_pdf(path: String) -> Result<(), String> {
+    Store::open_default()?
+        .get_printable_wheel()?
+        .save(&PathBuf::from(path))
+}
 
-    let conn = get_connection()?;
-    let now = Utc::now().timestamp();
-    let next_position: i64 = conn
-        .query_row(
-            "SELECT COALESCE(MAX(position), -1) + 1 FROM action_items",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(0);
+/// Writes a Markdown note per area into the folder `dir`, returning the paths
+/// of the notes written.
+#[tauri::command]
+fn save_markdown_vault(dir: String) -> Result<Vec<PathBuf>, String> {
+    Store::open_default()?
+        .get_markdown_vault()?
+        .save(&PathBuf::from(dir))
+}
 
-    conn.execute(
-        "INSERT INTO action_items (area_id, title, created_at, position)
-         VALUES (?1, ?2, ?3, ?4)",
-        params![area_id, clean_title, now, next_position],
-    )
-    .map_err(|e| format!("Failed to insert action item: {}", e))?;
+#[tauri::command]
+fn get_review_report(granularity: String, at: Option<i64>) -> Result<ReviewReport, String> {
+    Store::open_default()?.get_review_report(granularity, at)
+}
 
-    let id = conn.last_insert_rowid();
+/// Writes the review to `path`, as Markdown or HTML depending on its extension.
+#[tauri::command]
+fn save_review_report(granularity: String, at: Option<i64>, path: String) -> Result<(), String> {
+    Store::open_default()?
+        .get_review_report(granularity, at)?
+        .save(&PathBuf::from(path))
+}
 
-    Ok(ActionItem {
-        id,
-        area_id,
-        title: clean_title.to_string(),
-        created_at: now,
-        position: next_position,
-        archived_at: None,
-    })
+#[tauri::command]
+fn create_action_item(
+    area_id: i64,
+    title: String,
+    description: Option<String>,
+) -> Result<ActionItem, String> {
+    Store::open_default()?.create_action_item(area_id, title, description)
 }
 
 #[tauri::command]
 fn get_action_items_by_area(area_id: i64) -> Result<Vec<ActionItem>, String> {
-    let conn = get_connection()?;
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, area_id, title, created_at, position, archived_at
-         FROM action_items
-         WHERE area_id = ? AND archived_at IS NULL
-         ORDER BY position ASC, created_at ASC",
-        )
-        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    let rows = stmt
-        .query_map(params![area_id], |row| {
-            Ok(ActionItem {
-                id: row.get(0)?,
-                area_id: row.get(1)?,
-                title: row.get(2)?,
-                created_at: row.get(3)?,
-                position: row.get(4)?,
-                archived_at: row.get(5)?,
-            })
-        })
-        .map_err(|e| format!("Failed to query action items: {}", e))?;
-
-    let mut result = Vec::new();
-    for item in rows {
-        result.push(item.map_err(|e| format!("Failed to parse action item: {}", e))?);
-    }
+    Store::open_default()?.get_action_items_by_area(area_id)
+}
+
+#[tauri::command]
+fn get_all_action_items(
+    area_filter: Option<i64>,
+    tag_filter: Option<TagFilter>,
+) -> Result<Vec<ActionItem>, String> {
+    Store::open_default()?.get_all_action_items(area_filter, tag_filter)
+}
+
+#[tauri::command]
+fn update_action_item(id: i64, title: String) -> Result<ActionItem, String> {
+    Store::open_default()?.update_action_item(id, title)
+}
+
+#[tauri::command]
+fn update_action_item_area(id: i64, area_id: i64) -> Result<ActionItem, String> {
+    Store::open_default()?.update_action_item_area(id, area_id)
+}
 
-    Ok(result)
+#[tauri::command]
+fn update_action_item_description(
+    id: i64,
+    description: Option<String>,
+) -> Result<ActionItem, String> {
+    Store::open_default()?.update_action_item_description(id, description)
 }
 
 #[tauri::command]
-fn get_all_action_items(area_filter: Option<i64>) -> Result<Vec<ActionItem>, String> {
-    let conn = get_connection()?;
+fn set_action_item_due_date(id: i64, due_at: Option<i64>) -> Result<ActionItem, String> {
+    Store::open_default()?.set_action_item_due_date(id, due_at)
+}
 
-    let base_query = "SELECT id, area_id, title, created_at, position, archived_at
-                      FROM action_items
-                      WHERE archived_at IS NULL";
+#[tauri::command]
+fn set_action_item_recurrence(id: i64, recurrence: Option<String>) -> Result<ActionItem, String> {
+    Store::open_default()?.set_action_item_recurrence(id, recurrence)
+}
 
-    let mut result = Vec::new();
+#[tauri::command]
+fn archive_action_item(id: i64) -> Result<Option<ActionItem>, String> {
+    Store::open_default()?.archive_action_item(id)
+}
 
-    if let Some(area_id) = area_filter {
-        let mut stmt = conn
-            .prepare(&format!(
-                "{base_query} AND area_id = ? ORDER BY position ASC, created_at ASC"
-            ))
-            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+#[tauri::command]
+fn delete_action_item(id: i64) -> Result<(), String> {
+    Store::open_default()?.delete_action_item(id)
+}
 
-        let rows = stmt
-            .query_map(params![area_id], |row| {
-                Ok(ActionItem {
-                    id: row.get(0)?,
-                    area_id: row.get(1)?,
-                    title: row.get(2)?,
-                    created_at: row.get(3)?,
-                    position: row.get(4)?,
-                    archived_at: row.get(5)?,
-                })
-            })
-            .map_err(|e| format!("Failed to query action items: {}", e))?;
+#[tauri::command]
+fn reorder_action_items(updates: Vec<ReorderUpdate>) -> Result<(), String> {
+    Store::open_default()?.reorder_action_items(updates)
+}
 
-        for item in rows {
-            result.push(item.map_err(|e| format!("Failed to parse action item: {}", e))?);
-        }
-    } else {
-        let mut stmt = conn
-            .prepare(&format!(
-                "{base_query} ORDER BY position ASC, created_at ASC"
-            ))
-            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-        let rows = stmt
-            .query_map([], |row| {
-                Ok(ActionItem {
-                    id: row.get(0)?,
-                    area_id: row.get(1)?,
-                    title: row.get(2)?,
-                    created_at: row.get(3)?,
-                    position: row.get(4)?,
-                    archived_at: row.get(5)?,
-                })
-            })
-            .map_err(|e| format!("Failed to query action items: {}", e))?;
-
-        for item in rows {
-            result.push(item.map_err(|e| format!("Failed to parse action item: {}", e))?);
-        }
-    }
+#[tauri::command]
+fn add_action_item_link(
+    action_item_id: i64,
+    url: String,
+    label: Option<String>,
+) -> Result<ActionItemLink, String> {
+    Store::open_default()?.add_action_item_link(action_item_id, url, label)
+}
 
-    Ok(result)
+#[tauri::command]
+fn get_action_item_links(action_item_id: i64) -> Result<Vec<ActionItemLink>, String> {
+    Store::open_default()?.get_action_item_links(action_item_id)
 }
 
 #[tauri::command]
-fn update_action_item(id: i64, title: String) -> Result<ActionItem, String> {
-    let clean_title = title.trim();
-    if clean_title.is_empty() {
-        return Err("Title cannot be empty".to_string());
-    }
-    if clean_title.chars().count() > MAX_ACTION_ITEM_TITLE_LENGTH {
-        return Err(format!(
-            "Title cannot exceed {} characters",
-            MAX_ACTION_ITEM_TITLE_LENGTH
-        ));
-    }
+fn delete_action_item_link(id: i64) -> Result<(), String> {
+    Store::open_default()?.delete_action_item_link(id)
+}
 
-    let conn = get_connection()?;
+#[tauri::command]
+fn open_action_item_link(id: i64) -> Result<(), String> {
+    let url = Store::open_default()?.get_action_item_link_url(id)?;
+    tauri_plugin_opener::open_url(url, None::<&str>)
+        .map_err(|e| format!("Failed to open link: {}", e))
+}
 
-    conn.execute(
-        "UPDATE action_items SET title = ?1 WHERE id = ?2",
-        params![clean_title, id],
-    )
-    .map_err(|e| format!("Failed to update action item: {}", e))?;
+#[tauri::command]
+fn create_action_item_step(
+    action_item_id: i64,
+    title: String,
+    parent_id: Option<i64>,
+) -> Result<ActionItemStep, String> {
+    Store::open_default()?.create_action_item_step(action_item_id, title, parent_id)
+}
 
-    conn.query_row(
-        "SELECT id, area_id, title, created_at, position, archived_at FROM action_items WHERE id = ?",
-        params![id],
-        |row| {
-            Ok(ActionItem {
-                id: row.get(0)?,
-                area_id: row.get(1)?,
-                title: row.get(2)?,
-                created_at: row.get(3)?,
-                position: row.get(4)?,
-                archived_at: row.get(5)?,
-            })
-        },
-    ).map_err(|e| format!("Failed to get action item: {}", e))
+#[tauri::command]
+fn get_action_item_steps(action_item_id: i64) -> Result<Vec<ActionItemStep>, String> {
+    Store::open_default()?.get_action_item_steps(action_item_id)
 }
 
 #[tauri::command]
-fn update_action_item_area(id: i64, area_id: i64) -> Result<ActionItem, String> {
-    let conn = get_connection()?;
+fn update_action_item_step(id: i64, title: String) -> Result<ActionItemStep, String> {
+    Store::open_default()?.update_action_item_step(id, title)
+}
 
-    // Verify area exists
-    conn.query_row(
-        "SELECT id FROM life_areas WHERE id = ? AND is_active = 1",
-        params![area_id],
-        |row| Ok(row.get::<_, i64>(0)?),
-    )
-    .map_err(|_| "Area not found or inactive".to_string())?;
+#[tauri::command]
+fn set_action_item_step_done(id: i64, is_done: bool) -> Result<ActionItemStep, String> {
+    Store::open_default()?.set_action_item_step_done(id, is_done)
+}
 
-    conn.execute(
-        "UPDATE action_items SET area_id = ?1 WHERE id = ?2",
-        params![area_id, id],
-    )
-    .map_err(|e| format!("Failed to update action item area: {}", e))?;
+#[tauri::command]
+fn delete_action_item_step(id: i64) -> Result<(), String> {
+    Store::open_default()?.delete_action_item_step(id)
+}
 
-    conn.query_row(
-        "SELECT id, area_id, title, created_at, position, archived_at FROM action_items WHERE id = ?",
-        params![id],
-        |row| {
-            Ok(ActionItem {
-                id: row.get(0)?,
-                area_id: row.get(1)?,
-                title: row.get(2)?,
-                created_at: row.get(3)?,
-                position: row.get(4)?,
-                archived_at: row.get(5)?,
-            })
-        },
-    ).map_err(|e| format!("Failed to get action item: {}", e))
+#[tauri::command]
+fn reorder_action_item_steps(updates: Vec<ReorderUpdate>) -> Result<(), String> {
+    Store::open_default()?.reorder_action_item_steps(updates)
 }
 
 #[tauri::command]
-fn archive_action_item(id: i64) -> Result<(), String> {
-    let conn = get_connection()?;
-    let now = Utc::now().timestamp();
+fn create_tag(name: String) -> Result<Tag, String> {
+    Store::open_default()?.create_tag(name)
+}
 
-    conn.execute(
-        "UPDATE action_items SET archived_at = ?1 WHERE id = ?2",
-        params![now, id],
-    )
-    .map_err(|e| format!("Failed to archive action item: {}", e))?;
+#[tauri::command]
+fn get_tags() -> Result<Vec<Tag>, String> {
+    Store::open_default()?.get_tags()
+}
 
-    Ok(())
+#[tauri::command]
+fn rename_tag(id: i64, name: String) -> Result<Tag, String> {
+    Store::open_default()?.rename_tag(id, name)
 }
 
 #[tauri::command]
-fn delete_action_item(id: i64) -> Result<(), String> {
-    let conn = get_connection()?;
+fn delete_tag(id: i64) -> Result<(), String> {
+    Store::open_default()?.delete_tag(id)
+}
 
-    conn.execute("DELETE FROM action_items WHERE id = ?", params![id])
-        .map_err(|e| format!("Failed to delete action item: {}", e))?;
+#[tauri::command]
+fn get_action_item_tags(action_item_id: i64) -> Result<Vec<Tag>, String> {
+    Store::open_default()?.get_action_item_tags(action_item_id)
+}
 
-    Ok(())
+#[tauri::command]
+fn assign_tags(action_item_ids: Vec<i64>, tag_ids: Vec<i64>) -> Result<(), String> {
+    Store::open_default()?.assign_tags(action_item_ids, tag_ids)
 }
 
-#[derive(Debug, Deserialize)]
-struct ReorderUpdate {
+#[tauri::command]
+fn unassign_tags(action_item_ids: Vec<i64>, tag_ids: Vec<i64>) -> Result<(), String> {
+    Store::open_default()?.unassign_tags(action_item_ids, tag_ids)
+}
+
+#[tauri::command]
+fn create_habit(
+    area_id: i64,
+    name: String,
+    frequency: String,
+    target_count: i64,
+) -> Result<Habit, String> {
+    Store::open_default()?.create_habit(area_id, name, frequency, target_count)
+}
+
+#[tauri::command]
+fn get_habits(area_id: Option<i64>, include_archived: bool) -> Result<Vec<Habit>, String> {
+    Store::open_default()?.get_habits(area_id, include_archived)
+}
+
+#[tauri::command]
+fn update_habit(
     id: i64,
-    position: i64,
+    name: String,
+    frequency: String,
+    target_count: i64,
+) -> Result<Habit, String> {
+    Store::open_default()?.update_habit(id, name, frequency, target_count)
 }
 
 #[tauri::command]
-fn reorder_action_items(updates: Vec<ReorderUpdate>) -> Result<(), String> {
-    if updates.is_empty() {
-        return Ok(());
-    }
+fn archive_habit(id: i64) -> Result<(), String> {
+    Store::open_default()?.archive_habit(id)
+}
 
-    let mut conn = get_connection()?;
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("Failed to start transaction: {}", e))?;
-
-    for update in updates {
-        tx.execute(
-            "UPDATE action_items SET position = ?1 WHERE id = ?2",
-            params![update.position, update.id],
-        )
-        .map_err(|e| format!("Failed to update action item position: {}", e))?;
-    }
+#[tauri::command]
+fn delete_habit(id: i64) -> Result<(), String> {
+    Store::open_default()?.delete_habit(id)
+}
+
+#[tauri::command]
+fn check_in_habit(
+    habit_id: i64,
+    checked_at: Option<i64>,
+    note: Option<String>,
+) -> Result<HabitCheckin, String> {
+    Store::open_default()?.check_in_habit(habit_id, checked_at, note)
+}
 
-    tx.commit()
-        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
-    Ok(())
+#[tauri::command]
+fn delete_habit_checkin(id: i64) -> Result<(), String> {
+    Store::open_default()?.delete_habit_checkin(id)
 }
 
 #[tauri::command]
-fn reset_area_data(area_id: i64) -> Result<(), String> {
-    let conn = get_connection()?;
+fn get_habit_checkins(
+    habit_id: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Vec<HabitCheckin>, String> {
+    Store::open_default()?.get_habit_checkins(habit_id, from, to)
+}
 
-    // Delete all scores for this area
-    conn.execute("DELETE FROM scores WHERE area_id = ?", params![area_id])
-        .map_err(|e| format!("Failed to delete scores: {}", e))?;
+#[tauri::command]
+fn get_habit_stats(habit_id: i64, weeks: Option<i64>) -> Result<HabitStats, String> {
+    Store::open_default()?.get_habit_stats(habit_id, weeks)
+}
 
-    // Delete all action items for this area
-    conn.execute(
-        "DELETE FROM action_items WHERE area_id = ?",
-        params![area_id],
-    )
-    .map_err(|e| format!("Failed to delete action items: {}", e))?;
+#[tauri::command]
+fn get_habit_heatmap(habit_id: i64, from: i64, to: i64) -> Result<Vec<HeatmapDay>, String> {
+    Store::open_default()?.get_habit_heatmap(habit_id, from, to)
+}
 
-    Ok(())
+#[tauri::command]
+fn get_goal(id: i64) -> Result<Goal, String> {
+    Store::open_default()?.get_goal(id)
 }
 
 #[tauri::command]
-fn reset_area_scores(area_id: i64) -> Result<(), String> {
-    let conn = get_connection()?;
+fn create_goal(
+    area_id: i64,
+    target_score: i64,
+    target_date: i64,
+    rationale: Option<String>,
+) -> Result<Goal, String> {
+    Store::open_default()?.create_goal(area_id, target_score, target_date, rationale)
+}
 
-    conn.execute("DELETE FROM scores WHERE area_id = ?", params![area_id])
-        .map_err(|e| format!("Failed to delete scores: {}", e))?;
+#[tauri::command]
+fn get_goals(area_id: Option<i64>, include_closed: bool) -> Result<Vec<Goal>, String> {
+    Store::open_default()?.get_goals(area_id, include_closed)
+}
+
+#[tauri::command]
+fn update_goal(
+    id: i64,
+    target_score: i64,
+    target_date: i64,
+    rationale: Option<String>,
+) -> Result<Goal, String> {
+    Store::open_default()?.update_goal(id, target_score, target_date, rationale)
+}
 
-    Ok(())
+#[tauri::command]
+fn close_goal(id: i64, status: String) -> Result<Goal, String> {
+    Store::open_default()?.close_goal(id, status)
 }
 
 #[tauri::command]
-fn reset_area_action_items(area_id: i64) -> Result<(), String> {
-    let conn = get_connection()?;
+fn reopen_goal(id: i64) -> Result<Goal, String> {
+    Store::open_default()?.reopen_goal(id)
+}
+
+#[tauri::command]
+fn delete_goal(id: i64) -> Result<(), String> {
+    Store::open_default()?.delete_goal(id)
+}
+
+#[tauri::command]
+fn get_goal_progress(id: i64) -> Result<GoalProgress, String> {
+    Store::open_default()?.get_goal_progress(id)
+}
+
+#[tauri::command]
+fn get_change_log(
+    entity_type: Option<String>,
+    entity_id: Option<i64>,
+    from: Option<i64>,
+    to: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<ChangeLogEntry>, String> {
+    Store::open_default()?.get_change_log(entity_type, entity_id, from, to, limit, offset)
+}
+
+#[tauri::command]
+fn get_trash() -> Result<Vec<TrashBatch>, String> {
+    Store::open_default()?.get_trash()
+}
+
+#[tauri::command]
+fn get_trash_entries(
+    batch_id: i64,
+    entity_type: Option<String>,
+) -> Result<Vec<TrashEntry>, String> {
+    Store::open_default()?.get_trash_entries(batch_id, entity_type)
+}
+
+#[tauri::command]
+fn restore_trash(batch_id: i64) -> Result<Vec<RestoredChange>, String> {
+    Store::open_default()?.restore_trash(batch_id)
+}
+
+#[tauri::command]
+fn empty_trash() -> Result<usize, String> {
+    Store::open_default()?.empty_trash()
+}
+
+#[tauri::command]
+fn get_trash_retention_days() -> Result<Option<i64>, String> {
+    Store::open_default()?.get_trash_retention_days()
+}
+
+#[tauri::command]
+fn set_trash_retention_days(days: Option<i64>) -> Result<Option<i64>, String> {
+    Store::open_default()?.set_trash_retention_days(days)
+}
+
+#[tauri::command]
+fn get_undo_status() -> Result<UndoStatus, String> {
+    Store::open_default()?.get_undo_status()
+}
+
+#[tauri::command]
+fn undo() -> Result<Option<UndoResult>, String> {
+    Store::open_default()?.undo()
+}
+
+#[tauri::command]
+fn redo() -> Result<Option<UndoResult>, String> {
+    Store::open_default()?.redo()
+}
+
+#[tauri::command]
+fn reset_area_data(area_id: i64) -> Result<(), String> {
+    Store::open_default()?.reset_area_data(area_id)
+}
 
-    conn.execute(
-        "DELETE FROM action_items WHERE area_id = ?",
-        params![area_id],
-    )
-    .map_err(|e| format!("Failed to delete action items: {}", e))?;
+#[tauri::command]
+fn reset_area_scores(area_id: i64) -> Result<(), String> {
+    Store::open_default()?.reset_area_scores(area_id)
+}
 
-    Ok(())
+#[tauri::command]
+fn reset_area_action_items(area_id: i64) -> Result<(), String> {
+    Store::open_default()?.reset_area_action_items(area_id)
 }
 
 #[tauri::command]
 fn reset_all_data() -> Result<(), String> {
-    let conn = get_connection()?;
+    Store::open_default()?.reset_all_data()
+}
+
+#[tauri::command]
+fn get_templates() -> Result<TemplateList, String> {
+    roda_core::list_templates()
+}
+
+#[tauri::command]
+fn apply_template(id: String) -> Result<Vec<LifeArea>, String> {
+    let template = roda_core::get_template(&id)?;
+    Store::open_default()?.apply_template(&template)
+}
+
+/// Saves the active life areas as a user template.
+#[tauri::command]
+fn save_template(name: String, description: Option<String>) -> Result<WheelTemplate, String> {
+    let areas = Store::open_default()?.get_life_areas(false)?;
+    roda_core::save_template(&name, description, &areas)
+}
+
+#[tauri::command]
+fn delete_template(id: String) -> Result<(), String> {
+    roda_core::delete_template(&id)
+}
 
-    // Delete all scores
-    conn.execute("DELETE FROM scores", [])
-        .map_err(|e| format!("Failed to delete scores: {}", e))?;
+/// Aggregates wheel exports of several people. Nothing is written to the database.
+#[tauri::command]
+fn get_team_wheel(paths: Vec<String>, anonymize: bool) -> Result<TeamWheel, String> {
+    let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+    roda_core::load_team_wheel(&paths, anonymize)
+}
+
+#[tauri::command]
+fn get_database_location() -> Result<DatabaseLocation, String> {
+    roda_core::resolve_db_location()
+}
 
-    // Delete all action items
-    conn.execute("DELETE FROM action_items", [])
-        .map_err(|e| format!("Failed to delete action items: {}", e))?;
+#[tauri::command]
+fn move_database(target: String) -> Result<DatabaseLocation, String> {
+    roda_core::move_database(&PathBuf::from(target))
+}
 
-    // Delete all life areas
-    conn.execute("DELETE FROM life_areas", [])
-        .map_err(|e| format!("Failed to delete life areas: {}", e))?;
+#[tauri::command]
+fn get_database_encryption() -> Result<DatabaseEncryption, String> {
+    roda_core::get_database_encryption()
+}
 
-    Ok(())
+/// Remembers the passphrase of the encrypted database for the rest of the
+/// session and finishes the startup the locked database prevented. A wrong
+/// passphrase is forgotten again.
+#[tauri::command]
+fn unlock_database(passphrase: String) -> Result<(), String> {
+    set_db_passphrase(Some(passphrase));
+    get_db_path()
+        .and_then(|path| Store::open(&path))
+        .map(|_| ())
+        .inspect_err(|_| set_db_passphrase(None))
+}
+
+#[tauri::command]
+fn encrypt_database(passphrase: String) -> Result<(), String> {
+    roda_core::encrypt_database(&passphrase)
+}
+
+#[tauri::command]
+fn decrypt_database() -> Result<(), String> {
+    roda_core::decrypt_database()
+}
+
+#[tauri::command]
+fn change_database_passphrase(passphrase: String) -> Result<(), String> {
+    roda_core::change_db_passphrase(&passphrase)
+}
+
+#[tauri::command]
+fn get_profiles() -> Result<Vec<Profile>, String> {
+    roda_core::list_profiles()
+}
+
+#[tauri::command]
+fn create_profile(name: String) -> Result<Profile, String> {
+    roda_core::create_profile(&name)
+}
+
+#[tauri::command]
+fn rename_profile(name: String, new_name: String) -> Result<Profile, String> {
+    roda_core::rename_profile(&name, &new_name)
+}
+
+/// Switches to the profile and brings its database up to the current schema,
+/// unless it is encrypted and waits for `unlock_database`.
+#[tauri::command]
+fn switch_profile(name: String) -> Result<Profile, String> {
+    let profile = roda_core::switch_profile(&name)?;
+    if roda_core::get_database_encryption()?.unlocked {
+        Store::open(&get_db_path()?)?;
+    }
+    Ok(profile)
+}
+
+#[tauri::command]
+fn delete_profile(name: String) -> Result<(), String> {
+    roda_core::delete_profile(&name)
+}
+
+/// The value of `--db <path>` or `--db=<path>` on the command line.
+fn db_path_flag() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--db" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--db=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize database on startup
-    if let Err(e) = init_database() {
-        eprintln!("Failed to initialize database: {}", e);
+    if let Some(path) = db_path_flag() {
+        set_db_path_override(path);
+    }
+
+    // An encrypted database waits for `unlock_database` with the passphrase
+    // entered in the app, unless RODA_DB_PASSPHRASE already gives it
+    let locked = roda_core::get_database_encryption().is_ok_and(|status| !status.unlocked);
+    if !locked {
+        // Initialize the database and purge expired data on startup
+        if let Err(e) = get_db_path().and_then(|path| Store::open(&path)) {
+            eprintln!("Failed to initialize database: {}", e);
+        }
     }
 
     tauri::Builder::default()
@@ -636,20 +718,99 @@ pub fn run() {
             restore_life_area,
             create_score,
             get_scores_by_area,
+            get_scores_page,
             get_latest_score,
             get_all_latest_scores,
+            get_wheel_as_of,
+            get_wheel_svg,
+            save_wheel_image,
+            save_wheel_pdf,
+            save_markdown_vault,
+            export_ical,
+            import_ical,
+            export_task_list,
+            import_task_list,
+            import_csv,
+            get_review_report,
+            save_review_report,
+            get_score_buckets,
             create_action_item,
             get_action_items_by_area,
             get_all_action_items,
             update_action_item,
             update_action_item_area,
+            update_action_item_description,
+            set_action_item_due_date,
+            set_action_item_recurrence,
             reorder_action_items,
             archive_action_item,
             delete_action_item,
+            add_action_item_link,
+            get_action_item_links,
+            delete_action_item_link,
+            open_action_item_link,
+            create_action_item_step,
+            get_action_item_steps,
+            update_action_item_step,
+            set_action_item_step_done,
+            delete_action_item_step,
+            reorder_action_item_steps,
+            create_tag,
+            get_tags,
+            rename_tag,
+            delete_tag,
+            get_action_item_tags,
+            assign_tags,
+            unassign_tags,
+            create_habit,
+            get_habits,
+            update_habit,
+            archive_habit,
+            delete_habit,
+            check_in_habit,
+            delete_habit_checkin,
+            get_habit_checkins,
+            get_habit_stats,
+            get_habit_heatmap,
+            create_goal,
+            get_goals,
+            get_goal,
+            update_goal,
+            close_goal,
+            reopen_goal,
+            delete_goal,
+            get_goal_progress,
+            get_change_log,
+            get_trash,
+            get_trash_entries,
+            restore_trash,
+            empty_trash,
+            get_trash_retention_days,
+            set_trash_retention_days,
+            get_undo_status,
+            undo,
+            redo,
             reset_area_data,
             reset_area_scores,
             reset_area_action_items,
             reset_all_data,
+            get_templates,
+            apply_template,
+            save_template,
+            delete_template,
+            get_team_wheel,
+            get_database_location,
+            move_database,
+            get_database_encryption,
+            unlock_database,
+            encrypt_database,
+            decrypt_database,
+            change_database_passphrase,
+            get_profiles,
+            create_profile,
+            rename_profile,
+            switch_profile,
+            delete_profile,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,10 +1,12 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { Toaster } from "sonner";
 import HomePage from "./pages/HomePage";
 import LifeAreaDetailPage from "./pages/LifeAreaDetailPage";
 import LifeAreasManagementPage from "./pages/LifeAreasManagementPage";
 import HistoryPage from "./pages/HistoryPage";
-import type { Page } from "./types";
+import UnlockDatabaseDialog from "./components/common/UnlockDatabaseDialog";
+import type { DatabaseEncryption, Page } from "./types";
 
 interface NavigationState {
   page: Page;
@@ -13,6 +15,14 @@ interface NavigationState {
 
 function App() {
   const [navigation, setNavigation] = useState<NavigationState>({ page: "home" });
+  const [encryption, setEncryption] = useState<DatabaseEncryption | null>(null);
+
+  // An encrypted database stays locked until its passphrase is entered
+  useEffect(() => {
+    invoke<DatabaseEncryption>("get_database_encryption")
+      .then(setEncryption)
+      .catch(() => setEncryption({ path: "", encrypted: false, unlocked: true }));
+  }, []);
 
   const handleNavigate = (page: Page, data?: any) => {
     if (page === "detail" && data) {
@@ -34,22 +44,31 @@ function App() {
         gap={0}
       />
 
-      <div className="container mx-auto px-4 sm:px-6 py-4 sm:py-6 max-w-7xl h-screen flex flex-col">
-        {navigation.page === "home" && <HomePage onNavigate={handleNavigate} />}
-        {navigation.page === "detail" && (
-          <LifeAreaDetailPage
-            areaId={navigation.data?.areaId}
-            fromPage={navigation.data?.fromPage}
-            onNavigate={handleNavigate}
-          />
-        )}
-        {navigation.page === "manage" && (
-          <LifeAreasManagementPage onNavigate={handleNavigate} />
-        )}
-        {navigation.page === "history" && (
-          <HistoryPage onNavigate={handleNavigate} />
-        )}
-      </div>
+      {encryption && !encryption.unlocked && (
+        <UnlockDatabaseDialog
+          path={encryption.path}
+          onUnlocked={() => setEncryption({ ...encryption, unlocked: true })}
+        />
+      )}
+
+      {encryption?.unlocked && (
+        <div className="container mx-auto px-4 sm:px-6 py-4 sm:py-6 max-w-7xl h-screen flex flex-col">
+          {navigation.page === "home" && <HomePage onNavigate={handleNavigate} />}
+          {navigation.page === "detail" && (
+            <LifeAreaDetailPage
+              areaId={navigation.data?.areaId}
+              fromPage={navigation.data?.fromPage}
+              onNavigate={handleNavigate}
+            />
+          )}
+          {navigation.page === "manage" && (
+            <LifeAreasManagementPage onNavigate={handleNavigate} />
+          )}
+          {navigation.page === "history" && (
+            <HistoryPage onNavigate={handleNavigate} />
+          )}
+        </div>
+      )}
     </div>
   );
 }
@@ -0,0 +1,67 @@
+import { Lock } from "lucide-react";
+import { useState, type FormEvent } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface UnlockDatabaseDialogProps {
+  path: string;
+  onUnlocked: () => void;
+}
+
+export default function UnlockDatabaseDialog({ path, onUnlocked }: UnlockDatabaseDialogProps) {
+  const [passphrase, setPassphrase] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [unlocking, setUnlocking] = useState(false);
+
+  const handleSubmit = async (event: FormEvent) => {
+    event.preventDefault();
+    if (!passphrase) return;
+
+    try {
+      setUnlocking(true);
+      setError(null);
+      await invoke("unlock_database", { passphrase });
+      onUnlocked();
+    } catch (err) {
+      setError(typeof err === "string" ? err : "Failed to unlock database");
+      setPassphrase("");
+    } finally {
+      setUnlocking(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 z-[110] flex items-center justify-center bg-gray-900/50">
+      <form
+        onSubmit={handleSubmit}
+        className="bg-white rounded-lg shadow-xl max-w-md w-full mx-4 p-6 z-[110]"
+      >
+        <div className="flex items-center gap-3 mb-4">
+          <Lock className="w-5 h-5 text-gray-700" />
+          <h3 className="text-xl font-semibold text-gray-900">Unlock database</h3>
+        </div>
+        <p className="text-gray-700 mb-4">
+          The database is encrypted. Enter its passphrase to open it.
+        </p>
+        <p className="text-xs text-gray-500 mb-4 break-all">{path}</p>
+        <input
+          type="password"
+          autoFocus
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+          placeholder="Passphrase"
+          className="w-full px-3 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+        />
+        {error && <p className="text-sm text-red-600 mt-2">{error}</p>}
+        <div className="flex justify-end mt-6">
+          <button
+            type="submit"
+            disabled={unlocking || !passphrase}
+            className="px-4 py-2 text-white bg-blue-600 rounded-lg shadow-md transition-colors hover:bg-blue-700 disabled:opacity-50"
+          >
+            {unlocking ? "Unlocking..." : "Unlock"}
+          </button>
+        </div>
+      </form>
+    </div>
+  );
+}
@@ -14,6 +14,39 @@ export interface Score {
   area_id: number;
   value: number; // 0-10
   recorded_at: number;
+  note?: string;
+}
+
+export type ScoreGranularity = "day" | "week" | "month" | "quarter" | "year";
+export type GapFill = "null" | "carry_forward";
+
+export interface ScoreBucket {
+  start: number; // local midnight at the start of the bucket
+  date: string; // YYYY-MM-DD
+  average?: number;
+  last?: number;
+  count: number;
+  filled: boolean; // true if values were carried forward into an empty bucket
+}
+
+export interface AreaScoreBuckets {
+  area_id: number;
+  buckets: ScoreBucket[];
+}
+
+export interface ScoreCursor {
+  recorded_at: number;
+  id: number;
+}
+
+export interface ScorePage {
+  scores: Score[];
+  next_cursor?: ScoreCursor; // undefined on the last page
+}
+
+export interface WheelEntry {
+  area: LifeArea; // as it was at the requested time
+  score?: Score;
 }
 
 export interface ActionItem {
@@ -23,6 +56,331 @@ export interface ActionItem {
   created_at: number;
   position: number;
   archived_at?: number;
+  description?: string;
+  due_at?: number;
+  recurrence?: string; // RRULE subset, e.g. "FREQ=WEEKLY;BYDAY=SU"
+}
+
+export interface ActionItemLink {
+  id: number;
+  action_item_id: number;
+  url: string;
+  label?: string;
+  position: number;
+  created_at: number;
+}
+
+export interface ActionItemStep {
+  id: number;
+  action_item_id: number;
+  title: string;
+  is_done: boolean;
+  position: number;
+  created_at: number;
+  parent_id?: number;
+}
+
+export interface Tag {
+  id: number;
+  name: string;
+  created_at: number;
+}
+
+export type HabitFrequency = "daily" | "weekly";
+
+export interface Habit {
+  id: number;
+  area_id: number;
+  name: string;
+  frequency: HabitFrequency;
+  target_count: number;
+  created_at: number;
+  archived_at?: number;
+}
+
+export interface HabitCheckin {
+  id: number;
+  habit_id: number;
+  checked_at: number;
+  note?: string;
+}
+
+export interface WeeklyCompletion {
+  week_start: string; // YYYY-MM-DD
+  checkins: number;
+  target: number;
+  rate: number; // 0-1
+}
+
+export interface HabitStats {
+  habit_id: number;
+  current_streak: number;
+  longest_streak: number;
+  total_checkins: number;
+  weekly_completion: WeeklyCompletion[];
+}
+
+export interface HeatmapDay {
+  date: string; // YYYY-MM-DD
+  count: number;
+}
+
+export type GoalStatus = "open" | "achieved" | "abandoned";
+
+export interface Goal {
+  id: number;
+  area_id: number;
+  target_score: number; // 0-10
+  target_date: number;
+  rationale?: string;
+  status: GoalStatus;
+  created_at: number;
+  updated_at: number;
+  closed_at?: number;
+}
+
+export interface GoalProgress {
+  goal_id: number;
+  target_score: number;
+  target_date: number;
+  baseline_score?: number;
+  current_score?: number;
+  progress: number; // 0-1
+  reached: boolean;
+  trend_per_day?: number;
+  projected_date?: number;
+  on_track: boolean;
+}
+
+export type ChangeAction = "create" | "update" | "delete";
+
+export interface ChangeLogEntry {
+  id: number;
+  operation_id: number;
+  operation: string; // command that made the change, e.g. "reset_all_data"
+  entity_type: string; // table name
+  entity_id: number;
+  action: ChangeAction;
+  old_value?: Record<string, unknown>;
+  new_value?: Record<string, unknown>;
+  changed_at: number;
+}
+
+export interface UndoableOperation {
+  id: number;
+  name: string;
+  created_at: number;
+  undone_at?: number;
+}
+
+export interface UndoStatus {
+  undo?: UndoableOperation;
+  redo?: UndoableOperation;
+}
+
+export interface RestoredChange {
+  entity_type: string;
+  entity_id: number;
+  action: ChangeAction;
+  value?: Record<string, unknown>; // undefined when the row was removed
+}
+
+export interface UndoResult {
+  operation: UndoableOperation;
+  changes: RestoredChange[];
+}
+
+export interface TrashBatch {
+  id: number;
+  operation: string; // command that moved the rows to the trash
+  deleted_at: number;
+  purge_at?: number; // undefined when retention is disabled
+  counts: Record<string, number>; // rows per table
+}
+
+export interface TrashEntry {
+  id: number;
+  batch_id: number;
+  entity_type: string;
+  entity_id: number;
+  data: Record<string, unknown>;
+  deleted_at: number;
+}
+
+export type DatabaseLocationSource =
+  | "flag"
+  | "environment"
+  | "portable"
+  | "config"
+  | "default";
+
+export interface TemplateArea {
+  name: string;
+  description?: string;
+  color: string;
+  order: number;
+}
+
+export interface WheelTemplate {
+  id: string;
+  name: string;
+  description?: string;
+  areas: TemplateArea[];
+  built_in: boolean;
+}
+
+export interface TemplateList {
+  templates: WheelTemplate[];
+  /** One message per saved template file that could not be read. */
+  skipped: string[];
+}
+
+export interface IcalImportSummary {
+  completed: number; // open items archived
+  reopened: number; // archived items reopened
+  unchanged: number;
+  skipped: number; // tasks not exported from this database
+}
+
+export interface VaultActionItem {
+  item: ActionItem;
+  tags: Tag[];
+  steps: ActionItemStep[];
+  links: ActionItemLink[];
+}
+
+export interface VaultArea {
+  area: LifeArea;
+  score: Score | null;
+  scores: Score[]; // newest first
+  open: VaultActionItem[];
+  archived: VaultActionItem[]; // most recently archived first
+}
+
+export interface MarkdownVault {
+  generated_at: number;
+  areas: VaultArea[];
+}
+
+export type CsvImportKind = "scores" | "action_items";
+
+// Header names of the columns holding each field
+export interface CsvColumnMapping {
+  kind: CsvImportKind;
+  area: string;
+  value?: string; // required for scores
+  date?: string;
+  date_format?: string; // chrono format, "%Y-%m-%d" by default
+  title?: string; // required for action items
+  note?: string;
+  delimiter?: string;
+}
+
+export interface CsvImportRow {
+  line: number;
+  area: string;
+  value: number | null;
+  date: number | null;
+  title: string | null;
+  note: string | null;
+}
+
+export interface CsvRowError {
+  line: number;
+  message: string;
+}
+
+export interface CsvImportPreview {
+  dry_run: boolean;
+  rows: CsvImportRow[];
+  errors: CsvRowError[];
+  new_areas: string[];
+}
+
+export type TaskListFormat = "todotxt" | "markdown";
+
+export interface SkippedTask {
+  line: number; // 1-based
+  reason: string;
+}
+
+export interface TaskImportSummary {
+  created: number;
+  updated: number;
+  unchanged: number;
+  skipped: SkippedTask[];
+}
+
+export type WheelTheme = 'light' | 'dark';
+
+export interface WheelImageOptions {
+  size?: number; // pixels, 128 to 4096; defaults to 600
+  theme?: WheelTheme;
+}
+
+export interface AreaReview {
+  area: LifeArea;
+  score?: Score; // latest at the end of the period
+  previous_score?: Score; // latest at the end of the previous period
+  change?: number;
+  notes: Score[]; // scores with a note recorded during the period
+  completed: ActionItem[]; // archived during the period
+  open: ActionItem[];
+}
+
+export interface ReviewReport {
+  title: string;
+  period_start: number;
+  period_end: number; // generation time while the period is still running
+  previous_start: number;
+  generated_at: number;
+  areas: AreaReview[];
+}
+
+export interface TeamContributor {
+  label: string; // "Member N" when anonymized
+  exported_at: number | null; // null when anonymized
+}
+
+export interface ContributorScore {
+  contributor: string;
+  value: number;
+}
+
+export interface TeamAreaStats {
+  name: string;
+  color: string;
+  responses: number;
+  mean?: number; // statistics are undefined when nobody scored the area
+  median?: number;
+  std_dev?: number;
+  min?: number;
+  max?: number;
+  histogram: number[]; // contributors per score, 0 to 10
+  scores: ContributorScore[]; // empty when anonymized
+}
+
+export interface TeamWheel {
+  contributors: TeamContributor[];
+  areas: TeamAreaStats[];
+}
+
+export interface DatabaseLocation {
+  path: string;
+  source: DatabaseLocationSource;
+}
+
+export interface DatabaseEncryption {
+  path: string;
+  encrypted: boolean;
+  /** Not encrypted, or opened with the passphrase given. */
+  unlocked: boolean;
+}
+
+export interface Profile {
+  name: string;
+  path: string;
+  is_active: boolean;
 }
 
 export type Page = "home" | "detail" | "manage" | "history";